@@ -1,69 +1,48 @@
+use std::env;
 use std::error::Error;
-
-use ndarray::prelude::*;
+use std::process;
 
 use rust_ml::neuron::{
     activations::{leaky_relu, linear},
-    layers::Layer,
+    adversarial::fgsm,
+    datasets::{read_csv, DataLoader, Dataset, InMemoryDataset},
     losses::cce,
     networks::Network,
     optimizers::{Optimizer, SGD},
-    transfers::dense,
+    preprocessing::{MinMaxScaler, OneHotEncoder, Transformer},
+    validation::evaluate,
 };
 
-const MNIST_TRAIN_PATH: &str = "/home/tom/Documents/Datasets/MNIST/mnist_train.csv";
-const MNIST_TEST_PATH: &str = "/home/tom/Documents/Datasets/MNIST/mnist_test.csv";
-
-fn read_dataset(
-    path: &str,
-    rows: &mut Vec<Array1<f32>>,
-    labels: &mut Vec<Array1<f32>>,
-) -> Result<(), Box<dyn Error>> {
-    let mut rdr = csv::Reader::from_path(path)?;
-    for csv_row in rdr.records() {
-        let record = csv_row?
-            .iter()
-            .map(|pixel| pixel.parse::<u8>().unwrap())
-            .collect::<Vec<u8>>();
-
-        // one-hot encode label
-        let mut label = Array1::zeros(10);
-        label[record[0] as usize] = 1.;
-
-        // skip label and normalize row data
-        // to be between 0 and 1
-        let row = arr1(
-            &record
-                .iter()
-                .skip(1)
-                .map(|&x| (x as f32) / 255.)
-                .collect::<Vec<f32>>(),
-        );
-
-        labels.push(label);
-        rows.push(row);
-    }
-    Ok(())
-}
+fn read_training_data(
+    train_path: &str,
+    test_path: &str,
+) -> Result<(InMemoryDataset, InMemoryDataset), Box<dyn Error>> {
+    let (train_rows, train_labels) = read_csv(train_path, &[0], None, true)?.to_arrays();
+    let (test_rows, test_labels) = read_csv(test_path, &[0], None, true)?.to_arrays();
 
-fn read_training_data() -> Result<
-    (
-        (Vec<Array1<f32>>, Vec<Array1<f32>>),
-        (Vec<Array1<f32>>, Vec<Array1<f32>>),
-    ),
-    Box<dyn Error>,
-> {
-    let mut train_rows = Vec::with_capacity(60000);
-    let mut train_labels = Vec::with_capacity(60000);
-    let mut test_rows = Vec::with_capacity(10000);
-    let mut test_labels = Vec::with_capacity(10000);
+    // normalize pixels to be between 0 and 1 and one-hot encode labels, fitted on
+    // the training data only
+    let mut scaler = MinMaxScaler::new(0., 1.);
+    let mut encoder = OneHotEncoder::new();
+    let train = InMemoryDataset::from_arrays(
+        &scaler.fit_transform(&train_rows).into_dyn(),
+        &encoder.fit_transform(&train_labels).into_dyn(),
+    );
+    let test = InMemoryDataset::from_arrays(
+        &scaler.transform(&test_rows).into_dyn(),
+        &encoder.transform(&test_labels).into_dyn(),
+    );
 
-    read_dataset(MNIST_TRAIN_PATH, &mut train_rows, &mut train_labels)?;
-    read_dataset(MNIST_TEST_PATH, &mut test_rows, &mut test_labels)?;
-    Ok(((train_rows, train_labels), (test_rows, test_labels)))
+    Ok((train, test))
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <mnist_train.csv> <mnist_test.csv>", args[0]);
+        process::exit(1);
+    }
+
     // parameters
     let epochs = 10_000;
     let batch_size = 10;
@@ -71,28 +50,55 @@ fn main() {
 
     // load data
     println!("Loading MNIST dataset");
-    let (train, test) = read_training_data().expect("failed to load datasets");
-    println!("Loaded training data: {} rows", train.0.len());
-    println!("Loaded test data: {} rows", test.0.len());
+    let (train, test) = read_training_data(&args[1], &args[2]).expect("failed to load datasets");
+    println!("Loaded training data: {} rows", train.len());
+    println!("Loaded test data: {} rows", test.len());
+    let train = DataLoader::new(train, batch_size, true, false);
 
     // build network and optimizer
     println!("building network and optimizer");
-    let mut network = Network::new(vec![
-        Layer::new(128, 784, dense(), leaky_relu()),
-        Layer::new(10, 128, dense(), linear()),
-    ]);
-    let optimizer = SGD::new(cce());
+    let mut network = Network::builder(784)
+        .dense(128, leaky_relu())
+        .dense(10, linear())
+        .build()
+        .expect("failed to build network");
+    println!("{}", network.summary());
+    let mut optimizer = SGD::new(cce());
 
-    // training loop
+    // training loop, resuming from the checkpoint of an interrupted run if there is one
     println!("beginning training loop");
-    optimizer.train(
-        &mut network,
-        &train,
-        &test,
-        learning_rate,
-        batch_size,
-        epochs,
-    );
+    optimizer
+        .train_checkpointed(
+            &mut network,
+            &train,
+            &test,
+            learning_rate,
+            epochs,
+            0,
+            "mnist_checkpoint.json",
+            1_000,
+        )
+        .expect("failed to checkpoint training");
+
+    println!("trained network:\n{}", network.summary());
+
+    // robustness to adversarial examples within 0.1 of each test image
+    let (inputs, expected): (Vec<_>, Vec<_>) = (0..test.len())
+        .map(|i| {
+            let (input, expected) = test.get(i);
+            let example = fgsm(
+                &network,
+                &input,
+                &expected,
+                optimizer.get_loss(),
+                0.1,
+                Some((0., 1.)),
+            );
 
-    println!("trained network: {:?}", network);
+            (example, expected)
+        })
+        .unzip();
+    let adversarial = InMemoryDataset::new(inputs, expected);
+    let score = evaluate(&network, &adversarial, optimizer.get_loss());
+    println!("fgsm accuracy: {:.2}%", score.accuracy * 100.);
 }
@@ -2,34 +2,48 @@ use std::fmt::{Debug, Formatter};
 
 use ndarray::Array1;
 
-pub type ActivationFn = fn(&Array1<f32>) -> Array1<f32>;
-pub type DerivationFn = fn(&Array1<f32>) -> Array1<f32>;
+use crate::neuron::Float;
+
+pub type ActivationFn<F = f32> = fn(&Array1<F>) -> Array1<F>;
+pub type DerivationFn<F = f32> = fn(&Array1<F>) -> Array1<F>;
 
 #[derive(Clone, Copy)]
-pub struct Activation {
-    activation: ActivationFn,
-    derivation: DerivationFn,
+pub struct Activation<F: Float = f32> {
+    name: &'static str,
+    activation: ActivationFn<F>,
+    derivation: DerivationFn<F>,
 }
 
-impl Debug for Activation {
+impl<F: Float> Debug for Activation<F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Activation").finish()
+        f.debug_struct("Activation")
+            .field("name", &self.name)
+            .finish()
     }
 }
 
-impl Activation {
-    pub fn new(activation: ActivationFn, derivation: DerivationFn) -> Self {
+impl<F: Float> Activation<F> {
+    pub fn new(
+        name: &'static str,
+        activation: ActivationFn<F>,
+        derivation: DerivationFn<F>,
+    ) -> Self {
         Self {
+            name,
             activation,
             derivation,
         }
     }
 
-    pub fn activate(&self, transfer: &Array1<f32>) -> Array1<f32> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn activate(&self, transfer: &Array1<F>) -> Array1<F> {
         (self.activation)(transfer)
     }
 
-    pub fn derive(&self, transfer: &Array1<f32>) -> Array1<f32> {
+    pub fn derive(&self, transfer: &Array1<F>) -> Array1<F> {
         (self.derivation)(transfer)
     }
 }
@@ -1,14 +1,17 @@
 use crate::neuron::activations::Activation;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn leaky_relu_activation(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| if x > 0. { x } else { 0.01 * x })
+pub fn leaky_relu_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    let slope = F::from(0.01).unwrap();
+    transfer.map(|&x| if x > F::zero() { x } else { slope * x })
 }
 
-pub fn leaky_relu_derivative(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| if x > 0. { 1. } else { 0.01 })
+pub fn leaky_relu_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    let slope = F::from(0.01).unwrap();
+    transfer.map(|&x| if x > F::zero() { F::one() } else { slope })
 }
 
-pub fn leaky_relu() -> Activation {
-    Activation::new(leaky_relu_activation, leaky_relu_derivative)
+pub fn leaky_relu<F: Float>() -> Activation<F> {
+    Activation::new("leaky_relu", leaky_relu_activation, leaky_relu_derivative)
 }
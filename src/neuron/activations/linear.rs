@@ -1,13 +1,14 @@
 use crate::neuron::activations::Activation;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn linear_activation(transfer: &Array1<f32>) -> Array1<f32> {
+pub fn linear_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
     transfer.clone()
 }
 
-pub fn linear_derivative(transfer: &Array1<f32>) -> Array1<f32> {
-    Array1::ones(transfer.len()).map(|&x: &usize| x as f32)
+pub fn linear_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    Array1::ones(transfer.len())
 }
-pub fn linear() -> Activation {
-    Activation::new(linear_activation, linear_derivative)
+pub fn linear<F: Float>() -> Activation<F> {
+    Activation::new("linear", linear_activation, linear_derivative)
 }
@@ -1,15 +1,16 @@
 use ndarray::Array1;
 
 use crate::neuron::activations::Activation;
+use crate::neuron::Float;
 
-pub fn relu_activation(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| if x > 0. { x } else { 0. })
+pub fn relu_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    transfer.map(|&x| if x > F::zero() { x } else { F::zero() })
 }
 
-pub fn relu_derivative(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| if x > 0. { 1. } else { 0. })
+pub fn relu_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    transfer.map(|&x| if x > F::zero() { F::one() } else { F::zero() })
 }
 
-pub fn relu() -> Activation {
-    Activation::new(relu_activation, relu_derivative)
+pub fn relu<F: Float>() -> Activation<F> {
+    Activation::new("relu", relu_activation, relu_derivative)
 }
@@ -1,13 +1,14 @@
 use crate::neuron::activations::Activation;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn sigmoid_activation(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| 1.0 / (1.0 + f32::exp(-x)))
+pub fn sigmoid_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    transfer.map(|&x| F::one() / (F::one() + (-x).exp()))
 }
 
-pub fn sigmoid_derivative(transfer: &Array1<f32>) -> Array1<f32> {
-    sigmoid_activation(transfer).map(|s| s * (1.0 - s))
+pub fn sigmoid_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    sigmoid_activation(transfer).map(|&s| s * (F::one() - s))
 }
-pub fn sigmoid() -> Activation {
-    Activation::new(sigmoid_activation, sigmoid_derivative)
+pub fn sigmoid<F: Float>() -> Activation<F> {
+    Activation::new("sigmoid", sigmoid_activation, sigmoid_derivative)
 }
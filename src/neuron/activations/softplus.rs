@@ -1,15 +1,16 @@
 use ndarray::Array1;
 
 use crate::neuron::activations::{sigmoid_activation, Activation};
+use crate::neuron::Float;
 
-pub fn softplus_activation(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| (1. + f32::exp(x)).ln())
+pub fn softplus_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    transfer.map(|&x| (F::one() + x.exp()).ln())
 }
 
-pub fn softplus_derivative(transfer: &Array1<f32>) -> Array1<f32> {
+pub fn softplus_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
     sigmoid_activation(transfer)
 }
 
-pub fn softplus() -> Activation {
-    Activation::new(softplus_activation, softplus_derivative)
+pub fn softplus<F: Float>() -> Activation<F> {
+    Activation::new("softplus", softplus_activation, softplus_derivative)
 }
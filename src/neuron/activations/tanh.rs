@@ -1,14 +1,15 @@
 use crate::neuron::activations::Activation;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn tanh_activation(transfer: &Array1<f32>) -> Array1<f32> {
-    transfer.map(|&x| (f32::exp(x) - f32::exp(-x)) / (f32::exp(x) + f32::exp(-x)))
+pub fn tanh_activation<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    transfer.map(|&x| (x.exp() - (-x).exp()) / (x.exp() + (-x).exp()))
 }
 
-pub fn tanh_derivative(transfer: &Array1<f32>) -> Array1<f32> {
-    1. - tanh_activation(transfer).map(|x| x * x)
+pub fn tanh_derivative<F: Float>(transfer: &Array1<F>) -> Array1<F> {
+    tanh_activation(transfer).map(|&x| F::one() - x * x)
 }
 
-pub fn tanh() -> Activation {
-    Activation::new(tanh_activation, tanh_derivative)
+pub fn tanh<F: Float>() -> Activation<F> {
+    Activation::new("tanh", tanh_activation, tanh_derivative)
 }
@@ -0,0 +1,100 @@
+use ndarray::Array1;
+
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Adversarial example of the fast gradient sign method, the input moved by
+/// `epsilon` in the direction of the sign of the loss's input gradient
+///
+/// If `bounds` are given the example is clamped to them, e.g. to the valid
+/// range of pixel values.
+pub fn fgsm<F: Float, M: Model<F>>(
+    network: &M,
+    input: &Array1<F>,
+    expected: &Array1<F>,
+    loss: &Loss<F>,
+    epsilon: F,
+    bounds: Option<(F, F)>,
+) -> Array1<F> {
+    let gradient = network.loss_input_gradient(input, expected, loss);
+    let example = input + &(gradient.mapv(signum) * epsilon);
+
+    clamp(example, bounds)
+}
+
+/// Sign of a value, zero for zero so inputs without a gradient don't move
+pub(super) fn signum<F: Float>(value: F) -> F {
+    if value == F::zero() {
+        F::zero()
+    } else {
+        value.signum()
+    }
+}
+
+pub(super) fn clamp<F: Float>(example: Array1<F>, bounds: Option<(F, F)>) -> Array1<F> {
+    match bounds {
+        Some((low, high)) => example.mapv(|x| x.max(low).min(high)),
+        None => example,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, sigmoid};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::sse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    #[test]
+    fn test_fgsm_moves_against_the_expected_output() {
+        let network = Network::new(vec![Layer::from_parameters(
+            1,
+            3,
+            array![[2., -1., 0.]],
+            array![0.],
+            dense(),
+            sigmoid(),
+        )]);
+        let input: Array1<f32> = array![0.5, 0.5, 0.5];
+        let expected = array![1.];
+
+        let example = fgsm(&network, &input, &expected, &sse(), 0.1, None);
+        assert!((&example - &array![0.4, 0.6, 0.5])
+            .iter()
+            .all(|d| d.abs() < 1e-6));
+
+        let example = fgsm(&network, &input, &expected, &sse(), 1., Some((0., 1.)));
+        assert_eq!(example, array![0., 1., 0.5]);
+
+        let loss = |x: &Array1<f32>| sse().loss(&network.predict(x), &expected).sum();
+        assert!(loss(&example) > loss(&input));
+    }
+
+    #[test]
+    fn test_fgsm_on_linear_output() {
+        let network = Network::new(vec![Layer::from_parameters(
+            2,
+            2,
+            array![[1., 0.], [0., -1.]],
+            array![0., 0.],
+            dense(),
+            linear(),
+        )]);
+
+        let example = fgsm(
+            &network,
+            &array![0., 0.],
+            &array![1., 1.],
+            &sse(),
+            0.25,
+            None,
+        );
+        assert_eq!(example, array![-0.25, 0.25]);
+    }
+}
@@ -0,0 +1,5 @@
+pub use fgsm::fgsm;
+pub use pgd::pgd;
+
+mod fgsm;
+mod pgd;
@@ -0,0 +1,91 @@
+use ndarray::Array1;
+
+use crate::neuron::adversarial::fgsm::{clamp, signum};
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Adversarial example of projected gradient descent, `steps` fast gradient
+/// sign steps of `step_size`, each projected back into the `epsilon` ball
+/// around the input in the maximum norm
+///
+/// If `bounds` are given the example is clamped to them after each step, e.g. to
+/// the valid range of pixel values.
+#[allow(clippy::too_many_arguments)]
+pub fn pgd<F: Float, M: Model<F>>(
+    network: &M,
+    input: &Array1<F>,
+    expected: &Array1<F>,
+    loss: &Loss<F>,
+    epsilon: F,
+    step_size: F,
+    steps: usize,
+    bounds: Option<(F, F)>,
+) -> Array1<F> {
+    let mut example = input.clone();
+    for _ in 0..steps {
+        let gradient = network.loss_input_gradient(&example, expected, loss);
+        example = example + gradient.mapv(signum) * step_size;
+
+        // project back into the epsilon ball
+        example.zip_mut_with(input, |x, &original| {
+            *x = x.max(original - epsilon).min(original + epsilon)
+        });
+        example = clamp(example, bounds);
+    }
+
+    example
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, tanh};
+    use crate::neuron::adversarial::fgsm;
+    use crate::neuron::losses::sse;
+    use crate::neuron::networks::Network;
+
+    use super::*;
+
+    #[test]
+    fn test_pgd_stays_in_the_epsilon_ball_and_beats_fgsm() {
+        let mut network: Network<f64> = Network::builder(4)
+            .dense(6, tanh())
+            .dense(2, linear())
+            .build()
+            .unwrap();
+        for weights in network.get_weights_mut() {
+            weights.mapv_inplace(|w| w * 100.);
+        }
+
+        let input = array![0.2, 0.4, 0.6, 0.8];
+        let expected = network.predict(&input) + array![0.5, -0.5];
+        let loss = |x: &Array1<f64>| sse().loss(&network.predict(x), &expected).sum();
+
+        let epsilon = 0.1;
+        let example = pgd(
+            &network,
+            &input,
+            &expected,
+            &sse(),
+            epsilon,
+            0.02,
+            20,
+            Some((0., 1.)),
+        );
+        assert!((&example - &input)
+            .iter()
+            .all(|d| d.abs() <= epsilon + 1e-12));
+        assert!(example.iter().all(|&x| (0. ..=1.).contains(&x)));
+
+        let single_step = fgsm(&network, &input, &expected, &sse(), epsilon, Some((0., 1.)));
+        assert!(loss(&example) > loss(&input));
+        assert!(
+            loss(&example) >= loss(&single_step) - 1e-9,
+            "pgd loss {} < fgsm loss {}",
+            loss(&example),
+            loss(&single_step)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use ndarray::Array1;
+
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Integrated gradients of output `output`, the contribution of each input value
+/// to the change of the output from `baseline` to `input`
+///
+/// The derivatives of the output are averaged at `steps` points along the
+/// straight path from the baseline to the input, at the middle of each step,
+/// and multiplied by the difference of the input from the baseline. The
+/// contributions add up to the change of the output, more accurately the more
+/// steps are taken.
+pub fn integrated_gradients<F: Float, M: Model<F>>(
+    network: &M,
+    input: &Array1<F>,
+    baseline: &Array1<F>,
+    output: usize,
+    steps: usize,
+) -> Array1<F> {
+    assert!(steps > 0, "steps must be positive");
+    assert_eq!(
+        input.len(),
+        baseline.len(),
+        "input and baseline lengths must be equal"
+    );
+
+    let difference = input - baseline;
+    let half = F::from(0.5).unwrap();
+    let steps_length = F::from(steps).unwrap();
+    let total = (0..steps)
+        .map(|step| {
+            let alpha = (F::from(step).unwrap() + half) / steps_length;
+            network.output_input_gradient(&(baseline + &(&difference * alpha)), output)
+        })
+        .fold(Array1::zeros(input.len()), |total, gradient| {
+            total + gradient
+        });
+
+    total / steps_length * difference
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, sigmoid, tanh};
+    use crate::neuron::networks::Network;
+
+    use super::*;
+
+    #[test]
+    fn test_integrated_gradients_add_up_to_output_change() {
+        let mut network: Network<f64> = Network::builder(3)
+            .dense(5, tanh())
+            .dense(2, sigmoid())
+            .dense(2, linear())
+            .build()
+            .unwrap();
+        for weights in network.get_weights_mut() {
+            weights.mapv_inplace(|w| w * 100.);
+        }
+
+        let input = array![0.8, -0.4, 0.3];
+        let baseline = Array1::zeros(3);
+        for output in 0..2 {
+            let attributions = integrated_gradients(&network, &input, &baseline, output, 200);
+            let change = network.predict(&input)[output] - network.predict(&baseline)[output];
+
+            assert!(
+                (attributions.sum() - change).abs() < 1e-3 * change.abs().max(1.),
+                "attributions sum {} != output change {}",
+                attributions.sum(),
+                change
+            );
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub use integrated_gradients::integrated_gradients;
+pub use saliency::saliency;
+
+mod integrated_gradients;
+mod saliency;
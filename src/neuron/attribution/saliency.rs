@@ -0,0 +1,47 @@
+use ndarray::Array1;
+
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Saliency map of an output, the absolute derivatives of output `output` with
+/// respect to each input value
+pub fn saliency<F: Float, M: Model<F>>(network: &M, input: &Array1<F>, output: usize) -> Array1<F> {
+    network
+        .output_input_gradient(input, output)
+        .mapv(|gradient| gradient.abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, relu};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    #[test]
+    fn test_saliency_of_linear_network_is_absolute_weights() {
+        let network = Network::new(vec![
+            Layer::from_parameters(
+                2,
+                3,
+                array![[1., -2., 0.], [0.5, 0., 3.]],
+                array![0., 0.],
+                dense(),
+                relu(),
+            ),
+            Layer::from_parameters(1, 2, array![[1., -1.]], array![0.], dense(), linear()),
+        ]);
+
+        // both hidden units are active
+        let input = array![4., 1., 1.];
+        assert_eq!(saliency(&network, &input, 0), array![0.5, 2., 3.]);
+
+        // the first hidden unit is dead
+        let input = array![0., 1., 1.];
+        assert_eq!(saliency(&network, &input, 0), array![0.5, 0., 3.]);
+    }
+}
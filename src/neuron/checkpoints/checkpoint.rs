@@ -0,0 +1,353 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::checkpoints::CheckpointError;
+use crate::neuron::networks::{LayerSummary, Model};
+use crate::neuron::Float;
+
+/// Parameters of a model, with its layers' shapes to check it is restored into
+/// the same architecture
+///
+/// Transfers and activations are functions, so a model is restored into one
+/// built by the same code rather than rebuilt from the checkpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelState {
+    pub layers: Vec<LayerSummary>,
+    /// Parameters in the order of `Model::parameters`, lossless for `f32` and
+    /// `f64` models
+    pub parameters: Vec<f64>,
+    pub trainable: Vec<bool>,
+    /// Pruning mask of each layer's weights in row-major order, `None` for
+    /// unpruned layers
+    pub masks: Vec<Option<Vec<bool>>>,
+}
+
+impl ModelState {
+    pub fn capture<F: Float, M: Model<F>>(model: &M) -> Self {
+        let layers = model.layers();
+
+        Self {
+            layers: layers
+                .iter()
+                .map(|layer| LayerSummary::new(*layer))
+                .collect(),
+            parameters: model
+                .parameters()
+                .iter()
+                .map(|p| p.to_f64().unwrap())
+                .collect(),
+            trainable: layers.iter().map(|layer| layer.is_trainable()).collect(),
+            masks: layers
+                .iter()
+                .map(|layer| layer.get_mask().map(|mask| mask.iter().copied().collect()))
+                .collect(),
+        }
+    }
+
+    /// Overwrite the model's parameters, frozen layers and pruning masks with the
+    /// state
+    ///
+    /// Returns an error, leaving the model unchanged, if its layers' types or
+    /// shapes differ from the captured model's.
+    pub fn restore<F: Float, M: Model<F>>(&self, model: &mut M) -> Result<(), CheckpointError> {
+        let layers = model.layers();
+        if layers.len() != self.layers.len() {
+            return Err(CheckpointError::Mismatch(format!(
+                "checkpoint has {} layers, model has {}",
+                self.layers.len(),
+                layers.len()
+            )));
+        }
+
+        for (i, (layer, saved)) in layers.iter().zip(self.layers.iter()).enumerate() {
+            let summary = LayerSummary::new(*layer);
+            let matches = summary.transfer == saved.transfer
+                && summary.activation == saved.activation
+                && summary.input_size == saved.input_size
+                && summary.output_size == saved.output_size
+                && summary.weights_shape == saved.weights_shape
+                && summary.parameters() == saved.parameters();
+            if !matches {
+                return Err(CheckpointError::Mismatch(format!(
+                    "layer {} is {} {}x{} {}, checkpoint has {} {}x{} {}",
+                    i,
+                    summary.transfer,
+                    summary.weights_shape.0,
+                    summary.weights_shape.1,
+                    summary.activation,
+                    saved.transfer,
+                    saved.weights_shape.0,
+                    saved.weights_shape.1,
+                    saved.activation
+                )));
+            }
+        }
+
+        if self.parameters.len() != model.parameter_count()
+            || self.trainable.len() != self.layers.len()
+            || self.masks.len() != self.layers.len()
+        {
+            return Err(CheckpointError::Mismatch(
+                "checkpoint parameters don't match its layers".to_string(),
+            ));
+        }
+
+        let masks = self
+            .masks
+            .iter()
+            .zip(self.layers.iter())
+            .map(|(mask, layer)| {
+                mask.as_ref()
+                    .map(|mask| Array2::from_shape_vec(layer.weights_shape, mask.clone()))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| {
+                CheckpointError::Mismatch("checkpoint masks don't match its layers".to_string())
+            })?;
+
+        let parameters: Vec<F> = self
+            .parameters
+            .iter()
+            .map(|&p| F::from(p).unwrap())
+            .collect();
+        model.set_parameters(&Array1::from(parameters));
+        let layers = model.layers_mut().into_iter().zip(self.trainable.iter());
+        for ((layer, &trainable), mask) in layers.zip(masks) {
+            layer.set_trainable(trainable);
+            layer.set_mask(mask);
+        }
+
+        Ok(())
+    }
+}
+
+/// Buffers an optimizer keeps between steps, e.g. moment estimates, by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptimizerState {
+    pub buffers: Vec<(String, Vec<f64>)>,
+}
+
+/// Position of a training run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Epoch or generation being run
+    pub epoch: usize,
+    /// Batches of the epoch already trained on
+    pub step: usize,
+    /// Seed the shuffling of every epoch, or the random draws of every
+    /// generation, are derived from
+    pub seed: u64,
+    /// Learning rate of an optimizer's run
+    pub learning_rate: Option<f64>,
+    /// Mutation rate of a genetic algorithm's run
+    pub mutation_rate: Option<f64>,
+}
+
+/// Everything needed to resume an interrupted training run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Trained models, e.g. a network or a population of agents
+    pub models: Vec<ModelState>,
+    pub optimizer: OptimizerState,
+    pub progress: Progress,
+}
+
+impl Checkpoint {
+    /// Write the checkpoint as json
+    ///
+    /// The checkpoint is written next to the path and then moved over it, so an
+    /// interrupted save leaves the previous checkpoint intact.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), CheckpointError> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+
+        fs::rename(&temporary, path)?;
+
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CheckpointError> {
+        let reader = BufReader::new(File::open(path)?);
+
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ndarray::array;
+
+    use crate::neuron::activations::{relu, sigmoid};
+    use crate::neuron::datasets::{DataLoader, InMemoryDataset};
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::pruning::{prune, PruningScope};
+
+    use super::*;
+
+    fn network() -> Network<f64> {
+        Network::builder(3)
+            .dense(4, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let mut trained = network();
+        trained.freeze(0).unwrap();
+        let checkpoint = Checkpoint {
+            models: vec![ModelState::capture(&trained)],
+            optimizer: OptimizerState {
+                buffers: vec![("velocity".to_string(), vec![0.1, -0.25])],
+            },
+            progress: Progress {
+                epoch: 3,
+                step: 7,
+                seed: 42,
+                learning_rate: Some(0.01),
+                mutation_rate: None,
+            },
+        };
+
+        let path = env::temp_dir().join(format!("checkpoint_{}.json", std::process::id()));
+        checkpoint.save(&path).unwrap();
+        let loaded = Checkpoint::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, checkpoint);
+
+        let mut restored = network();
+        loaded.models[0].restore(&mut restored).unwrap();
+        assert_eq!(restored.parameters(), trained.parameters());
+        assert_eq!(restored.frozen_layers(), vec![0]);
+    }
+
+    #[test]
+    fn test_restore_into_other_architecture() {
+        let state = ModelState::capture(&network());
+
+        let mut wider: Network<f64> = Network::builder(3)
+            .dense(5, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap();
+        let before = wider.parameters();
+        assert!(matches!(
+            state.restore(&mut wider),
+            Err(CheckpointError::Mismatch(_))
+        ));
+        assert_eq!(wider.parameters(), before);
+
+        let mut f32_network: Network = Network::builder(3)
+            .dense(4, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap();
+        state.restore(&mut f32_network).unwrap();
+    }
+
+    #[test]
+    fn test_restored_pruning_masks() {
+        let mut pruned = network();
+        prune(&mut pruned, 0.5, PruningScope::PerLayer);
+        let state = ModelState::capture(&pruned);
+
+        let mut restored = network();
+        state.restore(&mut restored).unwrap();
+        for (layer, pruned) in restored.get_layers().iter().zip(pruned.get_layers()) {
+            assert_eq!(layer.get_mask(), pruned.get_mask());
+        }
+
+        // pruned weights stay zero through training
+        let inputs = vec![array![0.5, -1., 0.25]];
+        let expected = vec![array![1., 0.]];
+        SGD::new(mse()).optimize_batch(&mut restored, &inputs, &expected, 0.5);
+        for layer in restored.get_layers() {
+            let mask = layer.get_mask().unwrap();
+            assert!(layer
+                .get_weights()
+                .iter()
+                .zip(mask.iter())
+                .all(|(&w, &kept)| kept || w == 0.));
+        }
+        assert_ne!(restored.parameters(), pruned.parameters());
+    }
+
+    #[test]
+    fn test_resumed_training_matches_uninterrupted() {
+        let inputs: Vec<Array1<f64>> = (0..10).map(|i| array![i as f64 / 10., 1., -0.5]).collect();
+        let expected: Vec<Array1<f64>> = inputs.iter().map(|x| array![x[0], 1. - x[0]]).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+        let loader = DataLoader::new(dataset.clone(), 3, true, false);
+        let mut optimizer = SGD::with_threads(mse(), 1);
+        let initial = network();
+        let directory = env::temp_dir();
+        let path = |name: &str| directory.join(format!("{}_{}.json", name, std::process::id()));
+
+        let mut uninterrupted = initial.clone();
+        optimizer
+            .train_checkpointed(
+                &mut uninterrupted,
+                &loader,
+                &dataset,
+                0.5,
+                3,
+                7,
+                path("full"),
+                2,
+            )
+            .unwrap();
+
+        // interrupted after the first epoch and two batches of the second
+        let mut interrupted = initial;
+        optimizer
+            .train_checkpointed(
+                &mut interrupted,
+                &loader,
+                &dataset,
+                0.5,
+                1,
+                7,
+                path("part"),
+                2,
+            )
+            .unwrap();
+        for (batch_inputs, batch_expected) in loader.seeded_batches(8).take(2) {
+            optimizer.optimize_batch(&mut interrupted, &batch_inputs, &batch_expected, 0.5);
+        }
+        let mut checkpoint = Checkpoint::load(path("part")).unwrap();
+        checkpoint.models[0] = ModelState::capture(&interrupted);
+        checkpoint.progress.step = 2;
+        checkpoint.save(path("part")).unwrap();
+
+        // resumed into a freshly initialized network, the learning rate comes from
+        // the checkpoint
+        let mut resumed = network();
+        optimizer
+            .train_checkpointed(&mut resumed, &loader, &dataset, 100., 3, 0, path("part"), 2)
+            .unwrap();
+        assert_eq!(resumed.parameters(), uninterrupted.parameters());
+
+        let finished = Checkpoint::load(path("part")).unwrap();
+        assert_eq!(finished.progress.epoch, 3);
+        assert_eq!(finished.progress.step, 0);
+        fs::remove_file(path("full")).unwrap();
+        fs::remove_file(path("part")).unwrap();
+    }
+}
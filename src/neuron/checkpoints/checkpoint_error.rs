@@ -0,0 +1,44 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Errors returned when saving, loading or restoring checkpoints
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The checkpoint was saved from a model with different layers
+    Mismatch(String),
+}
+
+impl Display for CheckpointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckpointError::Io(e) => write!(f, "io error: {}", e),
+            CheckpointError::Json(e) => write!(f, "json error: {}", e),
+            CheckpointError::Mismatch(message) => write!(f, "model mismatch: {}", message),
+        }
+    }
+}
+
+impl Error for CheckpointError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CheckpointError::Io(e) => Some(e),
+            CheckpointError::Json(e) => Some(e),
+            CheckpointError::Mismatch(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for CheckpointError {
+    fn from(e: io::Error) -> Self {
+        CheckpointError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CheckpointError {
+    fn from(e: serde_json::Error) -> Self {
+        CheckpointError::Json(e)
+    }
+}
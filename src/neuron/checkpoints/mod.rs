@@ -0,0 +1,5 @@
+pub use checkpoint::{Checkpoint, ModelState, OptimizerState, Progress};
+pub use checkpoint_error::CheckpointError;
+
+mod checkpoint;
+mod checkpoint_error;
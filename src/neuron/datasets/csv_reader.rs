@@ -0,0 +1,115 @@
+use std::io::Read;
+use std::path::Path;
+
+use ndarray::Array1;
+
+use crate::neuron::datasets::{DatasetError, InMemoryDataset};
+
+/// Read a CSV file into an `InMemoryDataset`
+///
+/// The values of `label_columns` become each sample's expected output. The
+/// values of `feature_columns` become its input, if `feature_columns` is `None`
+/// all columns that are not label columns are used.
+pub fn read_csv<P: AsRef<Path>>(
+    path: P,
+    label_columns: &[usize],
+    feature_columns: Option<&[usize]>,
+    has_headers: bool,
+) -> Result<InMemoryDataset, DatasetError> {
+    let reader = csv::ReaderBuilder::new()
+        .has_headers(has_headers)
+        .from_path(path)?;
+
+    parse_csv(reader, label_columns, feature_columns)
+}
+
+fn parse_csv<R: Read>(
+    mut reader: csv::Reader<R>,
+    label_columns: &[usize],
+    feature_columns: Option<&[usize]>,
+) -> Result<InMemoryDataset, DatasetError> {
+    let mut inputs = vec![];
+    let mut expected = vec![];
+    for (row, record) in reader.records().enumerate() {
+        let values = record?
+            .iter()
+            .enumerate()
+            .map(|(column, value)| {
+                value.trim().parse::<f32>().map_err(|_| {
+                    DatasetError::Format(format!(
+                        "row {} column {}: failed to parse '{}' as a number",
+                        row, column, value
+                    ))
+                })
+            })
+            .collect::<Result<Vec<f32>, DatasetError>>()?;
+
+        let select = |columns: &[usize]| {
+            columns
+                .iter()
+                .map(|&column| {
+                    values.get(column).copied().ok_or_else(|| {
+                        DatasetError::Format(format!(
+                            "row {} has {} columns, column {} is out of range",
+                            row,
+                            values.len(),
+                            column
+                        ))
+                    })
+                })
+                .collect::<Result<Array1<f32>, DatasetError>>()
+        };
+
+        let features = match feature_columns {
+            Some(columns) => select(columns)?,
+            None => values
+                .iter()
+                .enumerate()
+                .filter(|(column, _)| !label_columns.contains(column))
+                .map(|(_, &value)| value)
+                .collect(),
+        };
+
+        expected.push(select(label_columns)?);
+        inputs.push(features);
+    }
+
+    Ok(InMemoryDataset::new(inputs, expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::datasets::Dataset;
+
+    use super::*;
+
+    fn reader(contents: &'static str) -> csv::Reader<&'static [u8]> {
+        csv::ReaderBuilder::new()
+            .has_headers(true)
+            .from_reader(contents.as_bytes())
+    }
+
+    #[test]
+    fn test_parse_csv_columns() {
+        let contents = "label,a,b,c\n1,0.5,2,3\n0,1.5,4,5\n";
+
+        let dataset = parse_csv(reader(contents), &[0], Some(&[1, 3])).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get(0), (array![0.5, 3.], array![1.]));
+        assert_eq!(dataset.get(1), (array![1.5, 5.], array![0.]));
+
+        let dataset = parse_csv(reader(contents), &[0], None).unwrap();
+        assert_eq!(dataset.get(1), (array![1.5, 4., 5.], array![0.]));
+    }
+
+    #[test]
+    fn test_parse_csv_errors() {
+        let result = parse_csv(reader("a,b\n1,x\n"), &[0], None);
+        assert!(matches!(result, Err(DatasetError::Format(_))));
+
+        let result = parse_csv(reader("a,b\n1,2\n"), &[2], None);
+        assert!(matches!(result, Err(DatasetError::Format(_))));
+    }
+}
@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use ndarray::Array1;
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// Splits a `Dataset` into minibatches, visiting every sample once per epoch
+#[derive(Debug, Clone)]
+pub struct DataLoader<D: Dataset<F>, F: Float = f32> {
+    dataset: D,
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    precision: PhantomData<F>,
+}
+
+impl<D: Dataset<F>, F: Float> DataLoader<D, F> {
+    /// Create a new `DataLoader`
+    ///
+    /// If `shuffle` is set the sample order is reshuffled every epoch. If
+    /// `drop_last` is set the last batch is dropped when it is smaller than
+    /// `batch_size`, otherwise it is yielded as is.
+    pub fn new(dataset: D, batch_size: usize, shuffle: bool, drop_last: bool) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+
+        Self {
+            dataset,
+            batch_size,
+            shuffle,
+            drop_last,
+            precision: PhantomData,
+        }
+    }
+
+    pub fn dataset(&self) -> &D {
+        &self.dataset
+    }
+
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Amount of batches in a single epoch
+    pub fn len(&self) -> usize {
+        let samples = self.dataset.len();
+        if self.drop_last {
+            samples / self.batch_size
+        } else {
+            samples.div_ceil(self.batch_size)
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over the batches of a single epoch
+    pub fn batches(&self) -> Batches<'_, D, F> {
+        self.shuffled_batches(&mut thread_rng())
+    }
+
+    /// Iterate over the batches of a single epoch, shuffled by an rng seeded
+    /// with `seed`, so the same seed always gives the same batches
+    pub fn seeded_batches(&self, seed: u64) -> Batches<'_, D, F> {
+        self.shuffled_batches(&mut StdRng::seed_from_u64(seed))
+    }
+
+    fn shuffled_batches<R: Rng>(&self, rng: &mut R) -> Batches<'_, D, F> {
+        let mut indices: Vec<usize> = (0..self.dataset.len()).collect();
+        if self.shuffle {
+            indices.shuffle(rng);
+        }
+
+        if self.drop_last {
+            indices.truncate(self.len() * self.batch_size);
+        }
+
+        Batches {
+            loader: self,
+            indices,
+            position: 0,
+        }
+    }
+}
+
+/// Batches of a single `DataLoader` epoch
+pub struct Batches<'a, D: Dataset<F>, F: Float> {
+    loader: &'a DataLoader<D, F>,
+    indices: Vec<usize>,
+    position: usize,
+}
+
+impl<'a, D: Dataset<F>, F: Float> Iterator for Batches<'a, D, F> {
+    type Item = (Vec<Array1<F>>, Vec<Array1<F>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.indices.len() {
+            return None;
+        }
+
+        let end = usize::min(self.position + self.loader.batch_size, self.indices.len());
+        let batch = self.indices[self.position..end]
+            .iter()
+            .map(|&i| self.loader.dataset.get(i))
+            .unzip();
+
+        self.position = end;
+
+        Some(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::datasets::InMemoryDataset;
+
+    use super::*;
+
+    fn counting_dataset(samples: usize) -> InMemoryDataset {
+        let inputs = (0..samples).map(|i| array![i as f32]).collect();
+        let expected = (0..samples).map(|i| array![i as f32]).collect();
+
+        InMemoryDataset::new(inputs, expected)
+    }
+
+    #[test]
+    fn test_batches_visit_every_sample_once() {
+        let loader = DataLoader::new(counting_dataset(10), 3, true, false);
+        assert_eq!(loader.len(), 4);
+
+        let mut seen: Vec<usize> = loader
+            .batches()
+            .flat_map(|(inputs, expected)| {
+                assert_eq!(inputs, expected);
+                inputs
+            })
+            .map(|x| x[0] as usize)
+            .collect();
+        seen.sort_unstable();
+
+        assert_eq!(seen, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_drop_last() {
+        let loader = DataLoader::new(counting_dataset(10), 3, false, true);
+        assert_eq!(loader.len(), 3);
+
+        let batch_sizes: Vec<usize> = loader.batches().map(|(inputs, _)| inputs.len()).collect();
+        assert_eq!(batch_sizes, vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn test_keep_last() {
+        let loader = DataLoader::new(counting_dataset(10), 3, false, false);
+
+        let batches: Vec<Vec<f32>> = loader
+            .batches()
+            .map(|(inputs, _)| inputs.iter().map(|x| x[0]).collect())
+            .collect();
+        assert_eq!(
+            batches,
+            vec![
+                vec![0., 1., 2.],
+                vec![3., 4., 5.],
+                vec![6., 7., 8.],
+                vec![9.]
+            ]
+        );
+    }
+}
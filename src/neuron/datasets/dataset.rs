@@ -0,0 +1,26 @@
+use ndarray::Array1;
+
+use crate::neuron::Float;
+
+/// An indexable collection of `(input, expected)` samples
+pub trait Dataset<F: Float = f32> {
+    /// Amount of samples in the dataset
+    fn len(&self) -> usize;
+
+    /// Get the sample at `index`, loading it if needed
+    fn get(&self, index: usize) -> (Array1<F>, Array1<F>);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<F: Float, D: Dataset<F>> Dataset<F> for &D {
+    fn len(&self) -> usize {
+        (*self).len()
+    }
+
+    fn get(&self, index: usize) -> (Array1<F>, Array1<F>) {
+        (*self).get(index)
+    }
+}
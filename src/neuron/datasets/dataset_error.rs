@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Errors returned when reading or writing datasets
+#[derive(Debug)]
+pub enum DatasetError {
+    Io(io::Error),
+    Csv(csv::Error),
+    Zip(zip::result::ZipError),
+    /// The file contents do not match the expected format
+    Format(String),
+}
+
+impl Display for DatasetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatasetError::Io(e) => write!(f, "io error: {}", e),
+            DatasetError::Csv(e) => write!(f, "csv error: {}", e),
+            DatasetError::Zip(e) => write!(f, "zip error: {}", e),
+            DatasetError::Format(message) => write!(f, "format error: {}", message),
+        }
+    }
+}
+
+impl Error for DatasetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DatasetError::Io(e) => Some(e),
+            DatasetError::Csv(e) => Some(e),
+            DatasetError::Zip(e) => Some(e),
+            DatasetError::Format(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for DatasetError {
+    fn from(e: io::Error) -> Self {
+        DatasetError::Io(e)
+    }
+}
+
+impl From<csv::Error> for DatasetError {
+    fn from(e: csv::Error) -> Self {
+        DatasetError::Csv(e)
+    }
+}
+
+impl From<zip::result::ZipError> for DatasetError {
+    fn from(e: zip::result::ZipError) -> Self {
+        DatasetError::Zip(e)
+    }
+}
@@ -0,0 +1,163 @@
+use std::convert::TryInto;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use ndarray::{Array1, ArrayD, Axis, IxDyn};
+
+use crate::neuron::datasets::{DatasetError, InMemoryDataset};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const MNIST_CLASSES: usize = 10;
+
+/// Read an IDX file (the MNIST / Fashion-MNIST binary format), gzipped or not
+pub fn read_idx<P: AsRef<Path>>(path: P) -> Result<ArrayD<f32>, DatasetError> {
+    let mut bytes = fs::read(path)?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = vec![];
+        GzDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+        bytes = decompressed;
+    }
+
+    parse_idx(&bytes)
+}
+
+/// Read MNIST / Fashion-MNIST images and labels into an `InMemoryDataset`
+///
+/// Images are flattened and scaled to be between 0 and 1, labels are one-hot
+/// encoded.
+pub fn read_mnist<P: AsRef<Path>>(
+    images_path: P,
+    labels_path: P,
+) -> Result<InMemoryDataset, DatasetError> {
+    let images = read_idx(images_path)?;
+    let labels = read_idx(labels_path)?;
+
+    if images.ndim() < 2 || labels.ndim() != 1 {
+        return Err(DatasetError::Format(format!(
+            "expected images with at least 2 dimensions and 1 dimensional labels, got {:?} and {:?}",
+            images.shape(),
+            labels.shape()
+        )));
+    }
+
+    if images.len_of(Axis(0)) != labels.len() {
+        return Err(DatasetError::Format(format!(
+            "got {} images but {} labels",
+            images.len_of(Axis(0)),
+            labels.len()
+        )));
+    }
+
+    if let Some(label) = labels
+        .iter()
+        .find(|&&label| label as usize >= MNIST_CLASSES)
+    {
+        return Err(DatasetError::Format(format!(
+            "label {} is out of range for {} classes",
+            label, MNIST_CLASSES
+        )));
+    }
+
+    let inputs = images
+        .outer_iter()
+        .map(|image| image.iter().map(|&pixel| pixel / 255.).collect())
+        .collect();
+    let expected = labels
+        .iter()
+        .map(|&label| {
+            let mut one_hot = Array1::zeros(MNIST_CLASSES);
+            one_hot[label as usize] = 1.;
+            one_hot
+        })
+        .collect();
+
+    Ok(InMemoryDataset::new(inputs, expected))
+}
+
+fn parse_idx(bytes: &[u8]) -> Result<ArrayD<f32>, DatasetError> {
+    if bytes.len() < 4 || bytes[0] != 0 || bytes[1] != 0 {
+        return Err(DatasetError::Format("invalid IDX magic number".to_string()));
+    }
+
+    let (value_size, decode): (usize, fn(&[u8]) -> f32) = match bytes[2] {
+        0x08 => (1, |b| b[0] as f32),
+        0x09 => (1, |b| b[0] as i8 as f32),
+        0x0B => (2, |b| i16::from_be_bytes(b.try_into().unwrap()) as f32),
+        0x0C => (4, |b| i32::from_be_bytes(b.try_into().unwrap()) as f32),
+        0x0D => (4, |b| f32::from_be_bytes(b.try_into().unwrap())),
+        0x0E => (8, |b| f64::from_be_bytes(b.try_into().unwrap()) as f32),
+        data_type => {
+            return Err(DatasetError::Format(format!(
+                "unknown IDX data type 0x{:02x}",
+                data_type
+            )))
+        }
+    };
+
+    let dimensions = bytes[3] as usize;
+    let data_start = 4 + 4 * dimensions;
+    if bytes.len() < data_start {
+        return Err(DatasetError::Format("truncated IDX header".to_string()));
+    }
+
+    let shape: Vec<usize> = bytes[4..data_start]
+        .chunks_exact(4)
+        .map(|dimension| u32::from_be_bytes(dimension.try_into().unwrap()) as usize)
+        .collect();
+
+    let data = &bytes[data_start..];
+    let expected_len = shape
+        .iter()
+        .try_fold(value_size, |len, &dimension| len.checked_mul(dimension))
+        .ok_or_else(|| DatasetError::Format(format!("IDX shape {:?} is too large", shape)))?;
+    if data.len() != expected_len {
+        return Err(DatasetError::Format(format!(
+            "IDX shape {:?} requires {} bytes of data, got {}",
+            shape,
+            expected_len,
+            data.len()
+        )));
+    }
+
+    let values = data.chunks_exact(value_size).map(decode).collect();
+
+    Ok(ArrayD::from_shape_vec(IxDyn(&shape), values).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_idx_u8() {
+        let bytes = [0, 0, 0x08, 2, 0, 0, 0, 2, 0, 0, 0, 3, 1, 2, 3, 4, 5, 255];
+
+        let array = parse_idx(&bytes).unwrap();
+        assert_eq!(array, arr2(&[[1., 2., 3.], [4., 5., 255.]]).into_dyn());
+    }
+
+    #[test]
+    fn test_parse_idx_i16_big_endian() {
+        let bytes = [0, 0, 0x0B, 1, 0, 0, 0, 2, 0x01, 0x00, 0xff, 0xfe];
+
+        let array = parse_idx(&bytes).unwrap();
+        assert_eq!(array.into_raw_vec(), vec![256., -2.]);
+    }
+
+    #[test]
+    fn test_parse_idx_errors() {
+        assert!(parse_idx(&[1, 0, 0x08, 0]).is_err());
+        assert!(parse_idx(&[0, 0, 0x01, 0]).is_err());
+        assert!(parse_idx(&[0, 0, 0x08, 1, 0, 0, 0, 3, 1, 2]).is_err());
+
+        // the size of the shape overflows
+        let mut bytes = vec![0, 0, 0x0D, 4];
+        bytes.extend_from_slice(&[0xff; 16]);
+        let error = parse_idx(&bytes).unwrap_err();
+        assert!(error.to_string().contains("too large"));
+    }
+}
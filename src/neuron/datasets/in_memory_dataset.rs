@@ -0,0 +1,77 @@
+use ndarray::{Array1, Array2, ArrayD, Axis};
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// A `Dataset` with all of its samples loaded in memory
+#[derive(Debug, Clone)]
+pub struct InMemoryDataset<F: Float = f32> {
+    inputs: Vec<Array1<F>>,
+    expected: Vec<Array1<F>>,
+}
+
+impl<F: Float> InMemoryDataset<F> {
+    pub fn new(inputs: Vec<Array1<F>>, expected: Vec<Array1<F>>) -> Self {
+        assert_eq!(
+            inputs.len(),
+            expected.len(),
+            "inputs and expected lengths must be equal"
+        );
+
+        Self { inputs, expected }
+    }
+
+    /// Create a dataset from arrays whose first axis indexes the samples, the
+    /// remaining axes of each sample are flattened
+    pub fn from_arrays(inputs: &ArrayD<F>, expected: &ArrayD<F>) -> Self {
+        let rows = |array: &ArrayD<F>| {
+            array
+                .axis_iter(Axis(0))
+                .map(|sample| sample.iter().copied().collect())
+                .collect()
+        };
+
+        Self::new(rows(inputs), rows(expected))
+    }
+
+    /// Stack the inputs and the expected outputs into arrays with a row per sample
+    pub fn to_arrays(&self) -> (Array2<F>, Array2<F>) {
+        (stack_rows(&self.inputs), stack_rows(&self.expected))
+    }
+
+    pub fn inputs(&self) -> &[Array1<F>] {
+        &self.inputs
+    }
+
+    pub fn expected(&self) -> &[Array1<F>] {
+        &self.expected
+    }
+}
+
+fn stack_rows<F: Float>(rows: &[Array1<F>]) -> Array2<F> {
+    let columns = rows.first().map_or(0, |row| row.len());
+    assert!(
+        rows.iter().all(|row| row.len() == columns),
+        "all samples must have the same length"
+    );
+
+    let values = rows.iter().flat_map(|row| row.iter().copied()).collect();
+
+    Array2::from_shape_vec((rows.len(), columns), values).unwrap()
+}
+
+impl<F: Float> From<(Vec<Array1<F>>, Vec<Array1<F>>)> for InMemoryDataset<F> {
+    fn from((inputs, expected): (Vec<Array1<F>>, Vec<Array1<F>>)) -> Self {
+        Self::new(inputs, expected)
+    }
+}
+
+impl<F: Float> Dataset<F> for InMemoryDataset<F> {
+    fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    fn get(&self, index: usize) -> (Array1<F>, Array1<F>) {
+        (self.inputs[index].clone(), self.expected[index].clone())
+    }
+}
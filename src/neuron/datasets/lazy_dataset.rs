@@ -0,0 +1,32 @@
+use ndarray::Array1;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// A `Dataset` that loads each sample only when it is requested, e.g. from disk
+pub struct LazyDataset<L> {
+    len: usize,
+    loader: L,
+}
+
+impl<L> LazyDataset<L> {
+    pub fn new(len: usize, loader: L) -> Self {
+        Self { len, loader }
+    }
+}
+
+impl<F, L> Dataset<F> for LazyDataset<L>
+where
+    F: Float,
+    L: Fn(usize) -> (Array1<F>, Array1<F>),
+{
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn get(&self, index: usize) -> (Array1<F>, Array1<F>) {
+        assert!(index < self.len, "index {} out of bounds", index);
+
+        (self.loader)(index)
+    }
+}
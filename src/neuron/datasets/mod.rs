@@ -0,0 +1,19 @@
+pub use csv_reader::read_csv;
+pub use data_loader::{Batches, DataLoader};
+pub use dataset::Dataset;
+pub use dataset_error::DatasetError;
+pub use idx_reader::{read_idx, read_mnist};
+pub use in_memory_dataset::InMemoryDataset;
+pub use lazy_dataset::LazyDataset;
+pub use npy::{read_npy, read_npy_dataset, read_npz, read_npz_dataset, write_npy};
+pub use subset::Subset;
+
+mod csv_reader;
+mod data_loader;
+mod dataset;
+mod dataset_error;
+mod idx_reader;
+mod in_memory_dataset;
+mod lazy_dataset;
+mod npy;
+mod subset;
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use ndarray::{Array, ArrayD, Dimension, IxDyn, ShapeBuilder};
+
+use crate::neuron::datasets::{DatasetError, InMemoryDataset};
+
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// Read a NumPy `.npy` file
+///
+/// Any boolean, integer or floating point data type is converted to `f32`.
+pub fn read_npy<P: AsRef<Path>>(path: P) -> Result<ArrayD<f32>, DatasetError> {
+    parse_npy(&fs::read(path)?)
+}
+
+/// Read all arrays in a NumPy `.npz` file, by name
+pub fn read_npz<P: AsRef<Path>>(path: P) -> Result<HashMap<String, ArrayD<f32>>, DatasetError> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+
+    let mut arrays = HashMap::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name().trim_end_matches(".npy").to_string();
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        arrays.insert(name, parse_npy(&bytes)?);
+    }
+
+    Ok(arrays)
+}
+
+/// Read inputs and expected outputs from two `.npy` files into an `InMemoryDataset`
+///
+/// The first axis of each array indexes the samples.
+pub fn read_npy_dataset<P: AsRef<Path>>(
+    inputs_path: P,
+    expected_path: P,
+) -> Result<InMemoryDataset, DatasetError> {
+    arrays_to_dataset(&read_npy(inputs_path)?, &read_npy(expected_path)?)
+}
+
+/// Read inputs and expected outputs stored under two names of a `.npz` file into
+/// an `InMemoryDataset`
+///
+/// The first axis of each array indexes the samples.
+pub fn read_npz_dataset<P: AsRef<Path>>(
+    path: P,
+    inputs_name: &str,
+    expected_name: &str,
+) -> Result<InMemoryDataset, DatasetError> {
+    let arrays = read_npz(path)?;
+    let get = |name: &str| {
+        arrays
+            .get(name)
+            .ok_or_else(|| DatasetError::Format(format!("npz file has no array '{}'", name)))
+    };
+
+    arrays_to_dataset(get(inputs_name)?, get(expected_name)?)
+}
+
+/// Write an array to a NumPy `.npy` file as little endian `f32`
+pub fn write_npy<P: AsRef<Path>, D: Dimension>(
+    path: P,
+    array: &Array<f32, D>,
+) -> Result<(), DatasetError> {
+    let mut file = File::create(path)?;
+    file.write_all(&serialize_npy(array))?;
+
+    Ok(())
+}
+
+fn arrays_to_dataset(
+    inputs: &ArrayD<f32>,
+    expected: &ArrayD<f32>,
+) -> Result<InMemoryDataset, DatasetError> {
+    if inputs.ndim() == 0 || expected.ndim() == 0 || inputs.shape()[0] != expected.shape()[0] {
+        return Err(DatasetError::Format(format!(
+            "inputs shape {:?} and expected shape {:?} have a different amount of samples",
+            inputs.shape(),
+            expected.shape()
+        )));
+    }
+
+    Ok(InMemoryDataset::from_arrays(inputs, expected))
+}
+
+fn serialize_npy<D: Dimension>(array: &Array<f32, D>) -> Vec<u8> {
+    let shape = match array.shape() {
+        [length] => format!("({},)", length),
+        shape => format!(
+            "({})",
+            shape
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '<f4', 'fortran_order': False, 'shape': {}, }}",
+        shape
+    );
+
+    // pad header with spaces so the data is 64 byte aligned, header ends with a newline
+    let unpadded_len = NPY_MAGIC.len() + 4 + header.len() + 1;
+    let padding = (64 - unpadded_len % 64) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut bytes = NPY_MAGIC.to_vec();
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+    for value in array.iter() {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    bytes
+}
+
+fn parse_npy(bytes: &[u8]) -> Result<ArrayD<f32>, DatasetError> {
+    if !bytes.starts_with(NPY_MAGIC) || bytes.len() < NPY_MAGIC.len() + 4 {
+        return Err(DatasetError::Format("invalid npy magic string".to_string()));
+    }
+
+    let major_version = bytes[NPY_MAGIC.len()];
+    let (header_start, header_len) = match major_version {
+        1 => (
+            10,
+            u16::from_le_bytes(bytes[8..10].try_into().unwrap()) as usize,
+        ),
+        2 | 3 if bytes.len() >= 12 => (
+            12,
+            u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize,
+        ),
+        _ => {
+            return Err(DatasetError::Format(format!(
+                "unsupported npy version {}",
+                major_version
+            )))
+        }
+    };
+
+    let data_start = header_start + header_len;
+    if bytes.len() < data_start {
+        return Err(DatasetError::Format("truncated npy header".to_string()));
+    }
+
+    let header = String::from_utf8_lossy(&bytes[header_start..data_start]);
+    let descr = header_value(&header, "descr")?
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string();
+    let fortran_order = header_value(&header, "fortran_order")? == "True";
+    let shape = parse_shape(header_value(&header, "shape")?)?;
+
+    let (value_size, decode) = npy_decoder(&descr)?;
+    let data = &bytes[data_start..];
+    let expected_len = shape
+        .iter()
+        .try_fold(value_size, |len, &dimension| len.checked_mul(dimension))
+        .ok_or_else(|| DatasetError::Format(format!("npy shape {:?} is too large", shape)))?;
+    if data.len() != expected_len {
+        return Err(DatasetError::Format(format!(
+            "npy shape {:?} with dtype {} requires {} bytes of data, got {}",
+            shape,
+            descr,
+            expected_len,
+            data.len()
+        )));
+    }
+
+    let values = data.chunks_exact(value_size).map(decode).collect();
+    let array = if fortran_order {
+        ArrayD::from_shape_vec(IxDyn(&shape).f(), values)
+    } else {
+        ArrayD::from_shape_vec(IxDyn(&shape), values)
+    };
+
+    Ok(array.unwrap())
+}
+
+/// Get the raw value of a key in the npy header dictionary
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, DatasetError> {
+    let missing = || DatasetError::Format(format!("npy header is missing '{}'", key));
+
+    let key_start = header
+        .find(&format!("'{}'", key))
+        .or_else(|| header.find(&format!("\"{}\"", key)))
+        .ok_or_else(missing)?;
+    let value = header[key_start + key.len() + 2..]
+        .trim_start()
+        .strip_prefix(':')
+        .ok_or_else(missing)?
+        .trim_start();
+
+    // the shape tuple contains commas, so it ends at the closing parenthesis
+    let value_end = if value.starts_with('(') {
+        value.find(')').map(|end| end + 1)
+    } else {
+        value.find([',', '}'])
+    };
+
+    Ok(value[..value_end.ok_or_else(missing)?].trim())
+}
+
+fn parse_shape(shape: &str) -> Result<Vec<usize>, DatasetError> {
+    shape
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(|d| d.trim())
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.parse::<usize>()
+                .map_err(|_| DatasetError::Format(format!("invalid npy shape '{}'", shape)))
+        })
+        .collect()
+}
+
+type Decoder = fn(&[u8]) -> f32;
+
+fn npy_decoder(descr: &str) -> Result<(usize, Decoder), DatasetError> {
+    let decoder: (usize, Decoder) = match descr {
+        "|b1" | "|u1" | "<u1" | ">u1" => (1, |b| b[0] as f32),
+        "|i1" | "<i1" | ">i1" => (1, |b| b[0] as i8 as f32),
+        "<i2" => (2, |b| i16::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">i2" => (2, |b| i16::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<u2" => (2, |b| u16::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">u2" => (2, |b| u16::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<i4" => (4, |b| i32::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">i4" => (4, |b| i32::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<u4" => (4, |b| u32::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">u4" => (4, |b| u32::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<i8" => (8, |b| i64::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">i8" => (8, |b| i64::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<u8" => (8, |b| u64::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">u8" => (8, |b| u64::from_be_bytes(b.try_into().unwrap()) as f32),
+        "<f4" => (4, |b| f32::from_le_bytes(b.try_into().unwrap())),
+        ">f4" => (4, |b| f32::from_be_bytes(b.try_into().unwrap())),
+        "<f8" => (8, |b| f64::from_le_bytes(b.try_into().unwrap()) as f32),
+        ">f8" => (8, |b| f64::from_be_bytes(b.try_into().unwrap()) as f32),
+        _ => {
+            return Err(DatasetError::Format(format!(
+                "unsupported npy dtype '{}'",
+                descr
+            )))
+        }
+    };
+
+    Ok(decoder)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+
+    use super::*;
+
+    #[test]
+    fn test_npy_round_trip() {
+        let array = arr2(&[[1., 2., 3.], [4., 5., 6.]]);
+
+        let bytes = serialize_npy(&array);
+        assert_eq!(bytes.len() % 64, 6 * 4);
+        assert_eq!(parse_npy(&bytes).unwrap(), array.into_dyn());
+
+        let array = arr1(&[1., -2.5]);
+        assert_eq!(parse_npy(&serialize_npy(&array)).unwrap(), array.into_dyn());
+    }
+
+    #[test]
+    fn test_parse_npy_fortran_order_f8() {
+        let header = "{'descr': '<f8', 'fortran_order': True, 'shape': (2, 2), }\n";
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        for value in &[1f64, 3., 2., 4.] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        assert_eq!(
+            parse_npy(&bytes).unwrap(),
+            arr2(&[[1., 2.], [3., 4.]]).into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_parse_npy_errors() {
+        assert!(parse_npy(b"NUMPY").is_err());
+
+        let mut bytes = serialize_npy(&arr1(&[1., 2.]));
+        bytes.pop();
+        assert!(parse_npy(&bytes).is_err());
+
+        // the size of the shape overflows
+        let header = format!(
+            "{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}\n",
+            usize::MAX / 2,
+            3
+        );
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend_from_slice(&[1, 0]);
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        let error = parse_npy(&bytes).unwrap_err();
+        assert!(error.to_string().contains("too large"));
+    }
+}
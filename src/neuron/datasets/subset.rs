@@ -0,0 +1,40 @@
+use ndarray::Array1;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// A `Dataset` of some of the samples of another `Dataset`
+#[derive(Debug, Clone)]
+pub struct Subset<D> {
+    dataset: D,
+    indices: Vec<usize>,
+}
+
+impl<D> Subset<D> {
+    pub fn new<F: Float>(dataset: D, indices: Vec<usize>) -> Self
+    where
+        D: Dataset<F>,
+    {
+        assert!(
+            indices.iter().all(|&i| i < dataset.len()),
+            "subset indices must be smaller than the dataset length"
+        );
+
+        Self { dataset, indices }
+    }
+
+    /// Indices of the subset samples in the original dataset
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+impl<F: Float, D: Dataset<F>> Dataset<F> for Subset<D> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn get(&self, index: usize) -> (Array1<F>, Array1<F>) {
+        self.dataset.get(self.indices[index])
+    }
+}
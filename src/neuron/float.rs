@@ -0,0 +1,9 @@
+use ndarray::NdFloat;
+use ndarray_rand::rand_distr::uniform::SampleUniform;
+
+/// Floating point type of a `Network`'s parameters and values, `f32` or `f64`
+pub trait Float: NdFloat + SampleUniform {}
+
+impl Float for f32 {}
+
+impl Float for f64 {}
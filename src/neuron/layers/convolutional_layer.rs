@@ -2,31 +2,33 @@ use ndarray::{s, Array2};
 use ndarray_rand::rand_distr::Uniform;
 use ndarray_rand::RandomExt;
 
+use crate::neuron::Float;
+
 #[derive(Debug, Clone)]
-struct ConvLayer {
-    kernel: Array2<f32>,
+pub struct ConvLayer<F: Float = f32> {
+    kernel: Array2<F>,
 }
 
-impl ConvLayer {
-    fn new(kernel_size: (usize, usize)) -> Self {
-        let distribution = Uniform::new(-0.01, 0.01);
+impl<F: Float> ConvLayer<F> {
+    pub fn new(kernel_size: (usize, usize)) -> Self {
+        let distribution = Uniform::new(F::from(-0.01).unwrap(), F::from(0.01).unwrap());
 
         let kernel = Array2::random(kernel_size, distribution);
 
         Self { kernel }
     }
 
-    pub fn forward(&self, input: &Vec<Array2<f32>>) -> Vec<Array2<f32>> {
+    pub fn forward(&self, input: &[Array2<F>]) -> Vec<Array2<F>> {
         let (width, height) = input[0].dim();
         let (k_width, k_height) = self.kernel.dim();
 
         let mut output = vec![];
-        for channel in 0..input.len() {
+        for channel in input {
             let mut channel_convolutions = vec![];
             for w in 0..width - k_width + 1 {
                 for h in 0..height - k_height + 1 {
-                    let frame = input[channel].slice(s![w..(w + k_width), h..(h + k_height)]);
-                    let convolution: f32 = frame.dot(&self.kernel).sum();
+                    let frame = channel.slice(s![w..(w + k_width), h..(h + k_height)]);
+                    let convolution: F = frame.dot(&self.kernel).sum();
                     channel_convolutions.push(convolution);
                 }
             }
@@ -56,7 +58,7 @@ mod tests {
 
     #[test]
     fn test_conv_layer() {
-        let layer = ConvLayer::new((3, 3));
+        let layer: ConvLayer = ConvLayer::new((3, 3));
         assert_eq!(layer.kernel.shape(), &[3, 3]);
     }
 
@@ -3,40 +3,85 @@ use ndarray_rand::rand_distr::Uniform;
 use ndarray_rand::RandomExt;
 
 use crate::neuron::activations::Activation;
+use crate::neuron::layers::{LayerCache, LayerGradients};
 use crate::neuron::transfers::Transfer;
+use crate::neuron::Float;
 
 #[derive(Debug, Clone)]
-pub struct Layer {
+pub struct Layer<F: Float = f32> {
     outputs: usize,
     inputs: usize,
-    transfer_fn: Transfer,
-    activation_fn: Activation,
-    weights: Array2<f32>,
-    biases: Array1<f32>,
-    input_value: Option<Array1<f32>>,
-    transfer_value: Option<Array1<f32>>,
-    activation_value: Option<Array1<f32>>,
+    transfer_fn: Transfer<F>,
+    activation_fn: Activation<F>,
+    weights: Array2<F>,
+    biases: Array1<F>,
+    trainable: bool,
+    /// Weights kept by pruning, `None` if the layer isn't pruned
+    mask: Option<Array2<bool>>,
 }
 
-impl Layer {
+impl<F: Float> Layer<F> {
     pub fn new(
         outputs: usize,
         inputs: usize,
-        transfer_fn: Transfer,
-        activation_fn: Activation,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
     ) -> Self {
-        let distribution = Uniform::new(-0.01, 0.01);
+        let distribution = || Uniform::new(F::from(-0.01).unwrap(), F::from(0.01).unwrap());
+        let weights = Array2::random(transfer_fn.weights_shape(inputs, outputs), distribution());
+        let biases = Array1::random(transfer_fn.biases_size(inputs, outputs), distribution());
+
+        Self::from_parameters(outputs, inputs, weights, biases, transfer_fn, activation_fn)
+    }
+
+    /// Create a layer with existing weights and biases, e.g. pretrained ones
+    pub fn from_parameters(
+        outputs: usize,
+        inputs: usize,
+        mut weights: Array2<F>,
+        biases: Array1<F>,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    ) -> Self {
+        assert!(
+            transfer_fn.accepts_inputs(inputs),
+            "{} transfer doesn't accept {} inputs",
+            transfer_fn.name(),
+            inputs
+        );
+        if let Some(transfer_outputs) = transfer_fn.output_size(inputs) {
+            assert_eq!(
+                outputs,
+                transfer_outputs,
+                "{} transfer with {} inputs has {} outputs",
+                transfer_fn.name(),
+                inputs,
+                transfer_outputs
+            );
+        }
+
+        assert_eq!(
+            weights.dim(),
+            transfer_fn.weights_shape(inputs, outputs),
+            "weights shape doesn't match the layer"
+        );
+        assert_eq!(
+            biases.len(),
+            transfer_fn.biases_size(inputs, outputs),
+            "biases size doesn't match the layer"
+        );
+
+        transfer_fn.constrain(&mut weights);
 
         Self {
             outputs,
             inputs,
             transfer_fn,
             activation_fn,
-            weights: Array2::random((outputs, inputs), distribution),
-            biases: Array1::random(outputs, distribution),
-            input_value: None,
-            transfer_value: None,
-            activation_value: None,
+            weights,
+            biases,
+            trainable: true,
+            mask: None,
         }
     }
 
@@ -48,72 +93,137 @@ impl Layer {
         self.outputs
     }
 
-    pub fn get_weights(&self) -> &Array2<f32> {
-        &self.weights
+    pub fn get_transfer(&self) -> &Transfer<F> {
+        &self.transfer_fn
     }
 
-    pub fn get_weights_mut(&mut self) -> &mut Array2<f32> {
-        &mut self.weights
+    pub fn get_activation(&self) -> &Activation<F> {
+        &self.activation_fn
     }
 
-    pub fn get_biases(&self) -> &Array1<f32> {
-        &self.biases
+    /// Amount of weights and biases in the layer
+    pub fn parameter_count(&self) -> usize {
+        self.weights.len() + self.biases.len()
     }
 
-    pub fn get_biases_mut(&mut self) -> &mut Array1<f32> {
-        &mut self.biases
+    /// Whether optimizers update the layer's weights and biases, layers are
+    /// trainable by default
+    pub fn is_trainable(&self) -> bool {
+        self.trainable
     }
 
-    pub fn get_input(&self) -> Option<&Array1<f32>> {
-        self.input_value.as_ref()
+    pub fn set_trainable(&mut self, trainable: bool) {
+        self.trainable = trainable;
     }
 
-    pub fn set_input(&mut self, input: Array1<f32>) {
-        self.input_value = Some(input);
+    /// Which weights are kept, pruned weights are zero and stay zero through
+    /// updates
+    pub fn get_mask(&self) -> Option<&Array2<bool>> {
+        self.mask.as_ref()
     }
 
-    pub fn get_transfer(&self) -> Option<&Array1<f32>> {
-        self.transfer_value.as_ref()
+    /// Prune the weights where the mask is false, or stop pruning with `None`
+    ///
+    /// Panics if the mask's shape isn't the weights' shape.
+    pub fn set_mask(&mut self, mask: Option<Array2<bool>>) {
+        if let Some(mask) = &mask {
+            assert_eq!(
+                mask.dim(),
+                self.weights.dim(),
+                "mask shape doesn't match the weights"
+            );
+        }
+
+        self.mask = mask;
+        self.apply_mask();
     }
 
-    pub fn set_transfer(&mut self, transfer: Array1<f32>) {
-        self.transfer_value = Some(transfer);
+    /// Zero the pruned weights, e.g. after overwriting the weights
+    pub fn apply_mask(&mut self) {
+        if let Some(mask) = &self.mask {
+            self.weights.zip_mut_with(mask, |w, &kept| {
+                if !kept {
+                    *w = F::zero();
+                }
+            });
+        }
     }
 
-    pub fn get_activation(&self) -> Option<&Array1<f32>> {
-        self.activation_value.as_ref()
+    pub fn get_weights(&self) -> &Array2<F> {
+        &self.weights
     }
 
-    pub fn set_activation(&mut self, activation: Array1<f32>) {
-        self.activation_value = Some(activation);
+    pub fn get_weights_mut(&mut self) -> &mut Array2<F> {
+        &mut self.weights
     }
 
-    pub fn apply_transfer(&self, inputs: &Array1<f32>) -> Array1<f32> {
+    pub fn get_biases(&self) -> &Array1<F> {
+        &self.biases
+    }
+
+    pub fn get_biases_mut(&mut self) -> &mut Array1<F> {
+        &mut self.biases
+    }
+
+    pub fn apply_transfer(&self, inputs: &Array1<F>) -> Array1<F> {
         self.transfer_fn
             .transfer(&self.weights, &self.biases, inputs)
     }
 
-    pub fn apply_activation(&self, transfer: &Array1<f32>) -> Array1<f32> {
+    pub fn apply_activation(&self, transfer: &Array1<F>) -> Array1<F> {
         self.activation_fn.activate(transfer)
     }
 
-    pub fn apply_derivation(&self, transfer: &Array1<f32>) -> Array1<f32> {
+    pub fn apply_derivation(&self, transfer: &Array1<F>) -> Array1<F> {
         self.activation_fn.derive(transfer)
     }
 
-    pub fn forward(&self, inputs: &Array1<f32>) -> Array1<f32> {
+    pub fn forward(&self, inputs: &Array1<F>) -> Array1<F> {
         self.apply_activation(&self.apply_transfer(inputs))
     }
 
-    pub fn forward_cached(&mut self, input: &Array1<f32>) -> Array1<f32> {
+    /// Forward the input, keeping the intermediate values needed for back propagation
+    pub fn forward_cached(&self, input: &Array1<F>) -> LayerCache<F> {
         let transfer = self.apply_transfer(input);
         let activation = self.apply_activation(&transfer);
 
-        self.set_input(input.clone());
-        self.set_transfer(transfer);
-        self.set_activation(activation.clone());
+        LayerCache {
+            input: input.clone(),
+            transfer,
+            activation,
+        }
+    }
+
+    /// Back propagate the derivatives of the loss with respect to the layer's
+    /// activations through a cached forward pass
+    pub fn backward(&self, cache: &LayerCache<F>, dl_da: &Array1<F>) -> LayerGradients<F> {
+        // derivatives of the loss with respect to the transfers
+        let dl_dt = dl_da * &self.apply_derivation(&cache.transfer);
+
+        self.transfer_fn
+            .backward(&self.weights, &cache.input, &dl_dt)
+    }
 
-        activation
+    /// Take a gradient descent step, unless the layer isn't trainable. Pruned
+    /// weights stay zero.
+    pub fn update(
+        &mut self,
+        weights_gradients: &Array2<F>,
+        biases_gradients: &Array1<F>,
+        learning_rate: F,
+    ) {
+        if !self.trainable {
+            return;
+        }
+
+        self.transfer_fn.update(
+            &mut self.weights,
+            &mut self.biases,
+            weights_gradients,
+            biases_gradients,
+            learning_rate,
+        );
+        self.apply_mask();
     }
 }
 
@@ -121,21 +231,43 @@ impl Layer {
 mod tests {
     use crate::neuron::activations::linear;
     use crate::neuron::transfers::dense;
-    use ndarray::arr1;
+    use ndarray::{arr1, array};
 
     use super::*;
 
     #[test]
     fn test_layer() {
-        let layer = Layer::new(3, 2, dense(), linear());
+        let layer: Layer = Layer::new(3, 2, dense(), linear());
         let output = layer.forward(&arr1(&[1., 0.]));
         assert_eq!(output.len(), 3);
     }
 
     #[test]
     fn test_sizes() {
-        let layer = Layer::new(3, 2, dense(), linear());
+        let layer: Layer = Layer::new(3, 2, dense(), linear());
         assert_eq!(layer.input_size(), 2);
         assert_eq!(layer.output_size(), 3);
     }
+
+    #[test]
+    fn test_mask_survives_updates() {
+        let mut layer: Layer = Layer::from_parameters(
+            2,
+            2,
+            array![[1., 2.], [3., 4.]],
+            array![0., 0.],
+            dense(),
+            linear(),
+        );
+        layer.set_mask(Some(array![[true, false], [false, true]]));
+        assert_eq!(layer.get_weights(), &array![[1., 0.], [0., 4.]]);
+
+        layer.update(&array![[1., 1.], [1., 1.]], &array![1., 1.], 0.5);
+        assert_eq!(layer.get_weights(), &array![[0.5, 0.], [0., 3.5]]);
+        assert_eq!(layer.get_biases(), &array![-0.5, -0.5]);
+
+        layer.set_mask(None);
+        layer.update(&array![[1., 1.], [1., 1.]], &array![0., 0.], 1.);
+        assert_eq!(layer.get_weights(), &array![[-0.5, -1.], [-1., 2.5]]);
+    }
 }
@@ -0,0 +1,14 @@
+use ndarray::Array1;
+
+use crate::neuron::Float;
+
+/// Values computed by a `Layer` during a single forward pass
+///
+/// Caches are kept outside of the layer so a network can be shared between
+/// threads that forward different inputs.
+#[derive(Debug, Clone)]
+pub struct LayerCache<F: Float = f32> {
+    pub input: Array1<F>,
+    pub transfer: Array1<F>,
+    pub activation: Array1<F>,
+}
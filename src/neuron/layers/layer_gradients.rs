@@ -0,0 +1,12 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::Float;
+
+/// Derivatives of the loss with respect to a `Layer`'s parameters and input,
+/// computed by back propagating through a single forward pass
+#[derive(Debug, Clone)]
+pub struct LayerGradients<F: Float = f32> {
+    pub weights: Array2<F>,
+    pub biases: Array1<F>,
+    pub input: Array1<F>,
+}
@@ -1,4 +1,9 @@
+pub use convolutional_layer::ConvLayer;
 pub use layer::Layer;
+pub use layer_cache::LayerCache;
+pub use layer_gradients::LayerGradients;
 
-mod layer;
 mod convolutional_layer;
+mod layer;
+mod layer_cache;
+mod layer_gradients;
@@ -2,34 +2,36 @@ use std::fmt::{Debug, Formatter};
 
 use ndarray::Array1;
 
-pub type LossFn = fn(&Array1<f32>, &Array1<f32>) -> Array1<f32>;
-pub type LossDerivativeFn = fn(&Array1<f32>, &Array1<f32>) -> Array1<f32>;
+use crate::neuron::Float;
+
+pub type LossFn<F = f32> = fn(&Array1<F>, &Array1<F>) -> Array1<F>;
+pub type LossDerivativeFn<F = f32> = fn(&Array1<F>, &Array1<F>) -> Array1<F>;
 
 #[derive(Clone, Copy)]
-pub struct Loss {
-    loss: LossFn,
-    loss_derivative: LossDerivativeFn,
+pub struct Loss<F: Float = f32> {
+    loss: LossFn<F>,
+    loss_derivative: LossDerivativeFn<F>,
 }
 
-impl Debug for Loss {
+impl<F: Float> Debug for Loss<F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Loss").finish()
     }
 }
 
-impl Loss {
-    pub fn new(loss: LossFn, loss_derivative: LossDerivativeFn) -> Self {
+impl<F: Float> Loss<F> {
+    pub fn new(loss: LossFn<F>, loss_derivative: LossDerivativeFn<F>) -> Self {
         Self {
             loss,
             loss_derivative,
         }
     }
 
-    pub fn loss(&self, prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
+    pub fn loss(&self, prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
         (self.loss)(prediction, expected)
     }
 
-    pub fn derivative(&self, prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
+    pub fn derivative(&self, prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
         (self.loss_derivative)(prediction, expected)
     }
 }
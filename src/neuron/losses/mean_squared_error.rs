@@ -1,14 +1,17 @@
 use crate::neuron::losses::Loss;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn mse_loss(prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
-    (1. / prediction.len() as f32) * (prediction - expected).map(|e| e * e)
+pub fn mse_loss<F: Float>(prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
+    let samples = F::from(prediction.len()).unwrap();
+    (prediction - expected).map(|&e| e * e) / samples
 }
 
-pub fn mse_derivative(prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
-    (1. / 2. * prediction.len() as f32) * (prediction - expected)
+pub fn mse_derivative<F: Float>(prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
+    let samples = F::from(prediction.len()).unwrap();
+    (prediction - expected) * (F::from(2.).unwrap() / samples)
 }
 
-pub fn mse() -> Loss {
+pub fn mse<F: Float>() -> Loss<F> {
     Loss::new(mse_loss, mse_derivative)
 }
@@ -1,14 +1,15 @@
 use crate::neuron::losses::Loss;
+use crate::neuron::Float;
 use ndarray::Array1;
 
-pub fn sse_loss(prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
-    (prediction - expected).map(|e| e * e)
+pub fn sse_loss<F: Float>(prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
+    (prediction - expected).map(|&e| e * e)
 }
 
-pub fn sse_derivative(prediction: &Array1<f32>, expected: &Array1<f32>) -> Array1<f32> {
-    2. * (prediction - expected)
+pub fn sse_derivative<F: Float>(prediction: &Array1<F>, expected: &Array1<F>) -> Array1<F> {
+    (prediction - expected) * F::from(2.).unwrap()
 }
 
-pub fn sse() -> Loss {
+pub fn sse<F: Float>() -> Loss<F> {
     Loss::new(sse_loss, sse_derivative)
 }
@@ -1,6 +1,20 @@
+pub use float::Float;
+
 pub mod activations;
+pub mod adversarial;
+pub mod attribution;
+pub mod checkpoints;
+pub mod datasets;
 pub mod layers;
 pub mod losses;
 pub mod networks;
+pub mod onnx;
 pub mod optimizers;
+pub mod preprocessing;
+pub mod pruning;
+pub mod quantization;
 pub mod transfers;
+pub mod tuning;
+pub mod validation;
+
+mod float;
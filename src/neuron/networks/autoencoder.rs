@@ -0,0 +1,185 @@
+use ndarray::prelude::*;
+
+use crate::neuron::datasets::{DataLoader, Dataset};
+use crate::neuron::layers::{Layer, LayerCache};
+use crate::neuron::networks::{Model, ModelGradients, Network};
+use crate::neuron::optimizers::Optimizer;
+use crate::neuron::Float;
+
+/// An encoder `Network` followed by a decoder `Network` that reconstructs the
+/// encoder's input from its output, the code
+///
+/// An `Autoencoder` is a `Model` of its reconstruction, so any `Optimizer` can
+/// train it with the inputs as the expected outputs.
+#[derive(Debug, Clone)]
+pub struct Autoencoder<F: Float = f32> {
+    encoder: Network<F>,
+    decoder: Network<F>,
+}
+
+impl<F: Float> Autoencoder<F> {
+    pub fn new(encoder: Network<F>, decoder: Network<F>) -> Self {
+        assert_eq!(
+            encoder.output_size(),
+            decoder.input_size(),
+            "decoder input size must match the encoder output size"
+        );
+        assert_eq!(
+            decoder.output_size(),
+            encoder.input_size(),
+            "decoder output size must match the encoder input size"
+        );
+
+        Self { encoder, decoder }
+    }
+
+    pub fn encoder(&self) -> &Network<F> {
+        &self.encoder
+    }
+
+    pub fn decoder(&self) -> &Network<F> {
+        &self.decoder
+    }
+
+    pub fn code_size(&self) -> usize {
+        self.encoder.output_size()
+    }
+
+    pub fn encode(&self, input: &Array1<F>) -> Array1<F> {
+        self.encoder.predict(input)
+    }
+
+    pub fn decode(&self, code: &Array1<F>) -> Array1<F> {
+        self.decoder.predict(code)
+    }
+
+    /// Mean reconstruction loss of the optimizer's loss over a dataset's inputs
+    pub fn reconstruction_loss<O: Optimizer<F>, D: Dataset<F>>(
+        &self,
+        optimizer: &O,
+        dataset: &D,
+    ) -> F {
+        let total = (0..dataset.len())
+            .map(|i| {
+                let (input, _) = dataset.get(i);
+                optimizer
+                    .get_loss()
+                    .loss(&self.predict(&input), &input)
+                    .sum()
+            })
+            .fold(F::zero(), |total, loss| total + loss);
+
+        total / F::from(dataset.len().max(1)).unwrap()
+    }
+
+    /// Train the autoencoder to reconstruct the inputs of a dataset, ignoring
+    /// its expected outputs, printing the reconstruction loss after each epoch
+    pub fn train<O: Optimizer<F>, D: Dataset<F>>(
+        &mut self,
+        optimizer: &O,
+        train: &DataLoader<D, F>,
+        learning_rate: F,
+        epochs: usize,
+    ) {
+        for e in 0..epochs {
+            for (batch_inputs, _) in train.batches() {
+                optimizer.optimize_batch(self, &batch_inputs, &batch_inputs, learning_rate);
+            }
+
+            println!(
+                "epoch {} | reconstruction loss: {:.4}",
+                e,
+                self.reconstruction_loss(optimizer, train.dataset())
+            );
+        }
+    }
+}
+
+impl<F: Float> Model<F> for Autoencoder<F> {
+    type Cache = (Vec<LayerCache<F>>, Vec<LayerCache<F>>);
+
+    fn input_size(&self) -> usize {
+        self.encoder.input_size()
+    }
+
+    fn output_size(&self) -> usize {
+        self.decoder.output_size()
+    }
+
+    fn predict(&self, input: &Array1<F>) -> Array1<F> {
+        self.decode(&self.encode(input))
+    }
+
+    fn predict_cached(&self, input: &Array1<F>) -> (Array1<F>, Self::Cache) {
+        let (code, encoder_caches) = self.encoder.predict_cached(input);
+        let (reconstruction, decoder_caches) = self.decoder.predict_cached(&code);
+
+        (reconstruction, (encoder_caches, decoder_caches))
+    }
+
+    fn backward(&self, cache: &Self::Cache, dl_dprediction: &Array1<F>) -> ModelGradients<F> {
+        let (encoder_caches, decoder_caches) = cache;
+        let decoder_gradients = self.decoder.backward(decoder_caches, dl_dprediction);
+        let mut gradients = self
+            .encoder
+            .backward(encoder_caches, &decoder_gradients.input);
+
+        gradients.weights.extend(decoder_gradients.weights);
+        gradients.biases.extend(decoder_gradients.biases);
+
+        gradients
+    }
+
+    fn layers(&self) -> Vec<&Layer<F>> {
+        let mut layers = self.encoder.layers();
+        layers.extend(self.decoder.layers());
+
+        layers
+    }
+
+    fn layers_mut(&mut self) -> Vec<&mut Layer<F>> {
+        let mut layers = self.encoder.layers_mut();
+        layers.extend(self.decoder.layers_mut());
+
+        layers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, sigmoid};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::losses::sse;
+    use crate::neuron::optimizers::SGD;
+
+    use super::*;
+
+    #[test]
+    fn test_autoencoder_learns_low_dimensional_data() {
+        // 4 dimensional points on a 1 dimensional curve
+        let inputs: Vec<Array1<f32>> = Array1::linspace(0.1, 0.9, 20)
+            .iter()
+            .map(|&t| array![t, 1. - t, t * t, 0.5])
+            .collect();
+        let dataset = InMemoryDataset::new(inputs.clone(), inputs);
+        let loader = DataLoader::new(dataset, 5, true, false);
+
+        let encoder = Network::builder(4).dense(1, sigmoid()).build().unwrap();
+        let decoder = Network::builder(1).dense(4, linear()).build().unwrap();
+        let mut autoencoder = Autoencoder::new(encoder, decoder);
+        assert_eq!(autoencoder.code_size(), 1);
+        assert_eq!(autoencoder.layers().len(), 2);
+
+        let optimizer = SGD::new(sse());
+        let before = autoencoder.reconstruction_loss(&optimizer, loader.dataset());
+        autoencoder.train(&optimizer, &loader, 0.5, 300);
+        let after = autoencoder.reconstruction_loss(&optimizer, loader.dataset());
+
+        assert!(
+            after < before / 20. && after < 0.01,
+            "reconstruction loss {} didn't improve enough on {}",
+            after,
+            before
+        );
+    }
+}
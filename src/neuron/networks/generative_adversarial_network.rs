@@ -0,0 +1,451 @@
+use ndarray::prelude::*;
+use ndarray_rand::rand::{thread_rng, Rng};
+
+use crate::neuron::datasets::{DataLoader, Dataset};
+use crate::neuron::networks::variational_autoencoder::standard_normal;
+use crate::neuron::networks::{Model, ModelGradients, Network};
+use crate::neuron::optimizers::Optimizer;
+use crate::neuron::Float;
+
+/// The losses a `GAN` trains its networks on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GANLoss<F: Float = f32> {
+    /// The discriminator outputs the logit of its input being real, and the
+    /// generator maximizes the log probability of its samples being real
+    NonSaturating,
+    /// The discriminator is a critic whose output estimates the Wasserstein
+    /// distance, kept 1-Lipschitz by penalizing the norm of its input gradient
+    /// at random interpolations of real and generated samples for deviating
+    /// from 1, scaled by `gradient_penalty`
+    Wasserstein { gradient_penalty: F },
+}
+
+/// A generative adversarial network
+///
+/// The generator maps latent vectors sampled from a standard normal
+/// distribution to samples, and the discriminator maps samples to a single
+/// score. The networks are trained in turn, each with its own optimizer, the
+/// discriminator to tell real samples from generated ones and the generator to
+/// fool the discriminator, back propagating the discriminator's loss through
+/// the discriminator's input into the generator.
+#[derive(Debug, Clone)]
+pub struct GAN<F: Float = f32> {
+    generator: Network<F>,
+    discriminator: Network<F>,
+    loss: GANLoss<F>,
+}
+
+impl<F: Float> GAN<F> {
+    pub fn new(generator: Network<F>, discriminator: Network<F>, loss: GANLoss<F>) -> Self {
+        assert_eq!(
+            generator.output_size(),
+            discriminator.input_size(),
+            "discriminator input size must match the generator output size"
+        );
+        assert_eq!(
+            discriminator.output_size(),
+            1,
+            "discriminator must output a single score"
+        );
+
+        Self {
+            generator,
+            discriminator,
+            loss,
+        }
+    }
+
+    pub fn generator(&self) -> &Network<F> {
+        &self.generator
+    }
+
+    pub fn discriminator(&self) -> &Network<F> {
+        &self.discriminator
+    }
+
+    pub fn latent_size(&self) -> usize {
+        self.generator.input_size()
+    }
+
+    /// Sample `count` latent vectors from the standard normal distribution
+    pub fn sample_latent(&self, count: usize) -> Vec<Array1<F>> {
+        (0..count)
+            .map(|_| standard_normal(self.latent_size()))
+            .collect()
+    }
+
+    /// Generate `count` samples from random latent vectors
+    pub fn generate(&self, count: usize) -> Vec<Array1<F>> {
+        self.sample_latent(count)
+            .iter()
+            .map(|latent| self.generator.predict(latent))
+            .collect()
+    }
+
+    /// Score of a sample, the logit of it being real or its critic value
+    pub fn discriminate(&self, sample: &Array1<F>) -> F {
+        self.discriminator.predict(sample)[0]
+    }
+
+    /// Gradients of the discriminator's parameters and input with respect to its
+    /// score, at `sample`
+    fn score_gradients(&self, sample: &Array1<F>) -> (F, ModelGradients<F>) {
+        let (score, cache) = self.discriminator.predict_cached(sample);
+        let gradients = self.discriminator.backward(&cache, &array![F::one()]);
+
+        (score[0], gradients)
+    }
+
+    /// Gradients of the discriminator's loss on a real and a generated sample,
+    /// and the loss, `mix` is the weight of the real sample in the interpolation
+    /// of the gradient penalty
+    fn discriminator_gradients(
+        &self,
+        real: &Array1<F>,
+        fake: &Array1<F>,
+        mix: F,
+    ) -> (ModelGradients<F>, F) {
+        let (real_score, real_gradients) = self.score_gradients(real);
+        let (fake_score, fake_gradients) = self.score_gradients(fake);
+
+        match self.loss {
+            GANLoss::NonSaturating => {
+                // -log(sigmoid(real)) - log(1 - sigmoid(fake))
+                let loss = softplus(-real_score) + softplus(fake_score);
+                let gradients = real_gradients
+                    .scale(sigmoid(real_score) - F::one())
+                    .add_gradients(&fake_gradients.scale(sigmoid(fake_score)));
+
+                (gradients, loss)
+            }
+            GANLoss::Wasserstein { gradient_penalty } => {
+                let loss = fake_score - real_score;
+                let gradients = fake_gradients.add_gradients(&real_gradients.scale(-F::one()));
+
+                let interpolated = real * mix + fake * (F::one() - mix);
+                let (_, interpolated_gradients) = self.score_gradients(&interpolated);
+                let input_gradient = interpolated_gradients.input;
+                let norm = input_gradient.dot(&input_gradient).sqrt();
+                let penalty = gradient_penalty * (norm - F::one()).powi(2);
+                if norm == F::zero() {
+                    return (gradients, loss + penalty);
+                }
+
+                // the derivative of the penalty with respect to the parameters is
+                // the derivative of the input gradient's norm, the derivative of the
+                // score in the input gradient's direction, computed as a central
+                // difference of the parameter gradients along that direction
+                let direction = &input_gradient / norm;
+                let step =
+                    F::epsilon().cbrt() * (F::one() + interpolated.dot(&interpolated).sqrt());
+                let (_, plus) = self.score_gradients(&(&interpolated + &(&direction * step)));
+                let (_, minus) = self.score_gradients(&(&interpolated - &(&direction * step)));
+                let two = F::from(2.).unwrap();
+                let penalty_gradients = plus
+                    .add_gradients(&minus.scale(-F::one()))
+                    .scale(two * gradient_penalty * (norm - F::one()) / (two * step));
+
+                (gradients.add_gradients(&penalty_gradients), loss + penalty)
+            }
+        }
+    }
+
+    /// Gradients of the generator's loss on the sample generated from `latent`,
+    /// and the loss
+    fn generator_gradients(&self, latent: &Array1<F>) -> (ModelGradients<F>, F) {
+        let (fake, generator_cache) = self.generator.predict_cached(latent);
+        let (score, discriminator_cache) = self.discriminator.predict_cached(&fake);
+        let score = score[0];
+
+        let (dl_dscore, loss) = match self.loss {
+            // -log(sigmoid(fake))
+            GANLoss::NonSaturating => (sigmoid(score) - F::one(), softplus(-score)),
+            GANLoss::Wasserstein { .. } => (-F::one(), -score),
+        };
+
+        // back propagate through the discriminator's input into the generator
+        let discriminator_gradients = self
+            .discriminator
+            .backward(&discriminator_cache, &array![dl_dscore]);
+        let gradients = self
+            .generator
+            .backward(&generator_cache, &discriminator_gradients.input);
+
+        (gradients, loss)
+    }
+
+    /// Take a discriminator step on a batch of real samples and as many generated
+    /// samples, returning the mean discriminator loss
+    pub fn train_discriminator<O: Optimizer<F>>(
+        &mut self,
+        optimizer: &O,
+        real_batch: &[Array1<F>],
+        learning_rate: F,
+    ) -> F {
+        if real_batch.is_empty() {
+            return F::zero();
+        }
+
+        let mut rng = thread_rng();
+        let fake_batch = self.generate(real_batch.len());
+        let (gradients, loss) = real_batch
+            .iter()
+            .zip(fake_batch.iter())
+            .map(|(real, fake)| {
+                let mix = F::from(rng.gen::<f64>()).unwrap();
+                self.discriminator_gradients(real, fake, mix)
+            })
+            .reduce(|(total, total_loss), (gradients, loss)| {
+                (total.add_gradients(&gradients), total_loss + loss)
+            })
+            .unwrap();
+
+        let batch_length = F::from(real_batch.len()).unwrap();
+        let gradients = gradients.scale(F::one() / batch_length);
+        optimizer.apply_gradients(
+            &mut self.discriminator,
+            &gradients.weights,
+            &gradients.biases,
+            learning_rate,
+        );
+
+        loss / batch_length
+    }
+
+    /// Take a generator step on a batch of `batch_size` generated samples,
+    /// returning the mean generator loss
+    pub fn train_generator<O: Optimizer<F>>(
+        &mut self,
+        optimizer: &O,
+        batch_size: usize,
+        learning_rate: F,
+    ) -> F {
+        if batch_size == 0 {
+            return F::zero();
+        }
+
+        let (gradients, loss) = self
+            .sample_latent(batch_size)
+            .iter()
+            .map(|latent| self.generator_gradients(latent))
+            .reduce(|(total, total_loss), (gradients, loss)| {
+                (total.add_gradients(&gradients), total_loss + loss)
+            })
+            .unwrap();
+
+        let batch_length = F::from(batch_size).unwrap();
+        let gradients = gradients.scale(F::one() / batch_length);
+        optimizer.apply_gradients(
+            &mut self.generator,
+            &gradients.weights,
+            &gradients.biases,
+            learning_rate,
+        );
+
+        loss / batch_length
+    }
+
+    /// Train on the inputs of a dataset, ignoring its expected outputs, taking a
+    /// discriminator step on every batch and a generator step after every
+    /// `discriminator_steps` batches, printing the mean losses after each epoch
+    #[allow(clippy::too_many_arguments)]
+    pub fn train<G: Optimizer<F>, O: Optimizer<F>, D: Dataset<F>>(
+        &mut self,
+        generator_optimizer: &G,
+        discriminator_optimizer: &O,
+        train: &DataLoader<D, F>,
+        generator_learning_rate: F,
+        discriminator_learning_rate: F,
+        discriminator_steps: usize,
+        epochs: usize,
+    ) {
+        assert!(
+            discriminator_steps > 0,
+            "discriminator steps must be positive"
+        );
+
+        for e in 0..epochs {
+            let (mut generator_loss, mut generator_batches) = (F::zero(), 0);
+            let (mut discriminator_loss, mut discriminator_batches) = (F::zero(), 0);
+            for (b, (batch_inputs, _)) in train.batches().enumerate() {
+                discriminator_loss += self.train_discriminator(
+                    discriminator_optimizer,
+                    &batch_inputs,
+                    discriminator_learning_rate,
+                );
+                discriminator_batches += 1;
+
+                if (b + 1) % discriminator_steps == 0 {
+                    generator_loss += self.train_generator(
+                        generator_optimizer,
+                        batch_inputs.len(),
+                        generator_learning_rate,
+                    );
+                    generator_batches += 1;
+                }
+            }
+
+            println!(
+                "epoch {} | generator loss: {:.4} | discriminator loss: {:.4}",
+                e,
+                generator_loss / F::from(generator_batches.max(1)).unwrap(),
+                discriminator_loss / F::from(discriminator_batches.max(1)).unwrap(),
+            );
+        }
+    }
+}
+
+fn sigmoid<F: Float>(x: F) -> F {
+    F::one() / (F::one() + (-x).exp())
+}
+
+/// `ln(1 + exp(x))`, without overflowing for large `x`
+fn softplus<F: Float>(x: F) -> F {
+    x.max(F::zero()) + (-x.abs()).exp().ln_1p()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, tanh};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::losses::mse;
+    use crate::neuron::optimizers::SGD;
+
+    use super::*;
+
+    fn small_gan(loss: GANLoss<f64>) -> GAN<f64> {
+        let generator = Network::builder(2)
+            .dense(3, tanh())
+            .dense(2, linear())
+            .build()
+            .unwrap();
+        let discriminator = Network::builder(2)
+            .dense(4, tanh())
+            .dense(1, linear())
+            .build()
+            .unwrap();
+        let mut gan = GAN::new(generator, discriminator, loss);
+        // scale up the initial weights so the gradients aren't negligible
+        for layer in gan
+            .generator
+            .layers_mut()
+            .into_iter()
+            .chain(gan.discriminator.layers_mut())
+        {
+            layer.get_weights_mut().mapv_inplace(|w| w * 100.);
+        }
+
+        gan
+    }
+
+    fn assert_gradients_match<L: Fn(&Network<f64>) -> f64>(
+        network: &Network<f64>,
+        gradients: &ModelGradients<f64>,
+        loss: L,
+        tolerance: f64,
+    ) {
+        let epsilon = 1e-6;
+        for (l, layer_gradients) in gradients.weights.iter().enumerate() {
+            for (index, &gradient) in layer_gradients.indexed_iter() {
+                let mut plus = network.clone();
+                plus.get_weights_mut()[l][index] += epsilon;
+                let mut minus = network.clone();
+                minus.get_weights_mut()[l][index] -= epsilon;
+
+                let numeric = (loss(&plus) - loss(&minus)) / (2. * epsilon);
+                assert!(
+                    (numeric - gradient).abs() < tolerance,
+                    "numeric gradient {} != analytic gradient {}",
+                    numeric,
+                    gradient
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gan_discriminator_gradients_match_finite_differences() {
+        let real = array![0.5, -0.3];
+        let fake = array![-0.2, 0.8];
+        for &(loss, tolerance) in &[
+            (GANLoss::NonSaturating, 1e-6),
+            (
+                GANLoss::Wasserstein {
+                    gradient_penalty: 10.,
+                },
+                1e-4,
+            ),
+        ] {
+            let gan = small_gan(loss);
+            let (gradients, _) = gan.discriminator_gradients(&real, &fake, 0.3);
+            let discriminator_loss = |discriminator: &Network<f64>| {
+                let mut gan = gan.clone();
+                gan.discriminator = discriminator.clone();
+                gan.discriminator_gradients(&real, &fake, 0.3).1
+            };
+
+            assert_gradients_match(
+                &gan.discriminator,
+                &gradients,
+                discriminator_loss,
+                tolerance,
+            );
+        }
+    }
+
+    #[test]
+    fn test_gan_generator_gradients_flow_through_discriminator() {
+        let latent = array![0.4, -1.1];
+        for &loss in &[
+            GANLoss::NonSaturating,
+            GANLoss::Wasserstein {
+                gradient_penalty: 10.,
+            },
+        ] {
+            let gan = small_gan(loss);
+            let (gradients, _) = gan.generator_gradients(&latent);
+            let generator_loss = |generator: &Network<f64>| {
+                let mut gan = gan.clone();
+                gan.generator = generator.clone();
+                gan.generator_gradients(&latent).1
+            };
+
+            assert_gradients_match(&gan.generator, &gradients, generator_loss, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_gan_generator_learns_data_mean() {
+        // real samples are spread around (1, -1)
+        let inputs: Vec<Array1<f32>> = (0..32)
+            .map(|i| {
+                let t = i as f32 / 31. - 0.5;
+                array![1. + 0.2 * t, -1. - 0.2 * t]
+            })
+            .collect();
+        let loader = DataLoader::new(InMemoryDataset::new(inputs.clone(), inputs), 8, true, false);
+
+        let generator = Network::builder(2).dense(2, linear()).build().unwrap();
+        let discriminator = Network::builder(2)
+            .dense(8, tanh())
+            .dense(1, linear())
+            .build()
+            .unwrap();
+        let mut gan = GAN::new(generator, discriminator, GANLoss::NonSaturating);
+        assert_eq!(gan.latent_size(), 2);
+
+        let optimizer = SGD::new(mse());
+        gan.train(&optimizer, &optimizer, &loader, 0.05, 0.2, 1, 300);
+
+        let samples = gan.generate(200);
+        let mean = samples
+            .iter()
+            .fold(Array1::<f32>::zeros(2), |total, sample| total + sample)
+            / 200.;
+        assert!(
+            (mean[0] - 1.).abs() < 0.3 && (mean[1] + 1.).abs() < 0.3,
+            "generated mean {} is far from the data mean",
+            mean
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+
+use ndarray::{concatenate, s, Array1, ArrayView1, Axis};
+
+use crate::neuron::layers::{Layer, LayerCache};
+use crate::neuron::networks::{GraphBuilder, Model, ModelGradients};
+use crate::neuron::Float;
+
+#[derive(Debug, Clone)]
+pub(super) enum Node<F: Float> {
+    /// A slice of the model input, starting at `offset`
+    Input {
+        offset: usize,
+    },
+    Layer {
+        input: usize,
+        layer: Box<Layer<F>>,
+    },
+    Add(Vec<usize>),
+    Concat(Vec<usize>),
+}
+
+/// A directed acyclic graph of layers and merge operations, with named inputs
+/// and outputs
+///
+/// Used as a `Model` the graph takes all of its inputs concatenated in the order
+/// they were added, and predicts all of its outputs concatenated in the order
+/// they were marked.
+#[derive(Debug, Clone)]
+pub struct Graph<F: Float = f32> {
+    /// Nodes in topological order, each node only refers to nodes before it
+    nodes: Vec<Node<F>>,
+    sizes: Vec<usize>,
+    inputs: Vec<(String, usize)>,
+    outputs: Vec<(String, usize)>,
+}
+
+/// Values computed by each node of a `Graph` during a single forward pass
+#[derive(Debug, Clone)]
+pub struct GraphCache<F: Float = f32> {
+    values: Vec<Array1<F>>,
+    layers: Vec<Option<LayerCache<F>>>,
+}
+
+impl<F: Float> Graph<F> {
+    pub(super) fn new(
+        nodes: Vec<Node<F>>,
+        sizes: Vec<usize>,
+        inputs: Vec<(String, usize)>,
+        outputs: Vec<(String, usize)>,
+    ) -> Self {
+        Self {
+            nodes,
+            sizes,
+            inputs,
+            outputs,
+        }
+    }
+
+    pub fn builder() -> GraphBuilder<F> {
+        GraphBuilder::new()
+    }
+
+    pub fn input_names(&self) -> Vec<&str> {
+        self.inputs.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn output_names(&self) -> Vec<&str> {
+        self.outputs.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Predict the named outputs from the named inputs
+    pub fn predict_named(&self, inputs: &HashMap<String, Array1<F>>) -> HashMap<String, Array1<F>> {
+        let input_views: Vec<ArrayView1<F>> = self
+            .inputs
+            .iter()
+            .map(|(name, _)| {
+                inputs
+                    .get(name)
+                    .unwrap_or_else(|| panic!("missing graph input '{}'", name))
+                    .view()
+            })
+            .collect();
+        let input = concatenate(Axis(0), &input_views).expect("failed to concatenate inputs");
+
+        let (_, cache) = self.forward(&input);
+        self.outputs
+            .iter()
+            .map(|(name, node)| (name.clone(), cache.values[*node].clone()))
+            .collect()
+    }
+
+    fn forward(&self, input: &Array1<F>) -> (Array1<F>, GraphCache<F>) {
+        assert_eq!(
+            input.len(),
+            Model::input_size(self),
+            "graph input size doesn't match the sum of its input sizes"
+        );
+
+        let mut values: Vec<Array1<F>> = Vec::with_capacity(self.nodes.len());
+        let mut layers = Vec::with_capacity(self.nodes.len());
+        for (i, node) in self.nodes.iter().enumerate() {
+            let (value, layer_cache) = match node {
+                Node::Input { offset } => (
+                    input.slice(s![*offset..*offset + self.sizes[i]]).to_owned(),
+                    None,
+                ),
+                Node::Layer { input, layer } => {
+                    let cache = layer.forward_cached(&values[*input]);
+
+                    (cache.activation.clone(), Some(cache))
+                }
+                Node::Add(merged) => {
+                    let mut sum = values[merged[0]].clone();
+                    for &node in &merged[1..] {
+                        sum += &values[node];
+                    }
+
+                    (sum, None)
+                }
+                Node::Concat(merged) => (self.concatenate_nodes(&values, merged), None),
+            };
+
+            values.push(value);
+            layers.push(layer_cache);
+        }
+
+        let output_nodes: Vec<usize> = self.outputs.iter().map(|&(_, node)| node).collect();
+        let prediction = self.concatenate_nodes(&values, &output_nodes);
+
+        (prediction, GraphCache { values, layers })
+    }
+
+    fn concatenate_nodes(&self, values: &[Array1<F>], nodes: &[usize]) -> Array1<F> {
+        let views: Vec<ArrayView1<F>> = nodes.iter().map(|&node| values[node].view()).collect();
+
+        concatenate(Axis(0), &views).unwrap()
+    }
+}
+
+/// Add `gradient` to the gradient accumulated for a node
+fn accumulate<F: Float>(total: &mut Option<Array1<F>>, gradient: ArrayView1<F>) {
+    match total {
+        Some(total) => *total += &gradient,
+        None => *total = Some(gradient.to_owned()),
+    }
+}
+
+impl<F: Float> Model<F> for Graph<F> {
+    type Cache = GraphCache<F>;
+
+    fn input_size(&self) -> usize {
+        self.inputs.iter().map(|&(_, node)| self.sizes[node]).sum()
+    }
+
+    fn output_size(&self) -> usize {
+        self.outputs.iter().map(|&(_, node)| self.sizes[node]).sum()
+    }
+
+    fn predict(&self, input: &Array1<F>) -> Array1<F> {
+        self.forward(input).0
+    }
+
+    fn predict_cached(&self, input: &Array1<F>) -> (Array1<F>, Self::Cache) {
+        self.forward(input)
+    }
+
+    fn backward(&self, cache: &Self::Cache, dl_dprediction: &Array1<F>) -> ModelGradients<F> {
+        // derivatives of the loss with respect to each node's value, nodes that
+        // don't affect the outputs have no gradient
+        let mut gradients: Vec<Option<Array1<F>>> = vec![None; self.nodes.len()];
+        let mut offset = 0;
+        for &(_, node) in self.outputs.iter() {
+            let size = self.sizes[node];
+            accumulate(
+                &mut gradients[node],
+                dl_dprediction.slice(s![offset..offset + size]),
+            );
+            offset += size;
+        }
+
+        let mut weights = vec![];
+        let mut biases = vec![];
+        let mut input = Array1::zeros(Model::input_size(self));
+
+        // visit the nodes in reverse topological order, so each node's gradient is
+        // complete before it is propagated to the nodes it depends on
+        for (i, node) in self.nodes.iter().enumerate().rev() {
+            let gradient = gradients[i].take();
+            match node {
+                Node::Input { offset } => {
+                    if let Some(gradient) = gradient {
+                        input
+                            .slice_mut(s![*offset..*offset + self.sizes[i]])
+                            .assign(&gradient);
+                    }
+                }
+                Node::Layer {
+                    input: layer_input,
+                    layer,
+                } => match gradient {
+                    Some(gradient) => {
+                        let layer_cache = cache.layers[i].as_ref().unwrap();
+                        let layer_gradients = layer.backward(layer_cache, &gradient);
+                        accumulate(&mut gradients[*layer_input], layer_gradients.input.view());
+                        weights.push(layer_gradients.weights);
+                        biases.push(layer_gradients.biases);
+                    }
+                    None => {
+                        weights.push(layer.get_weights().map(|_| F::zero()));
+                        biases.push(layer.get_biases().map(|_| F::zero()));
+                    }
+                },
+                Node::Add(merged) => {
+                    if let Some(gradient) = gradient {
+                        for &node in merged {
+                            accumulate(&mut gradients[node], gradient.view());
+                        }
+                    }
+                }
+                Node::Concat(merged) => {
+                    if let Some(gradient) = gradient {
+                        let mut offset = 0;
+                        for &node in merged {
+                            let size = cache.values[node].len();
+                            accumulate(
+                                &mut gradients[node],
+                                gradient.slice(s![offset..offset + size]),
+                            );
+                            offset += size;
+                        }
+                    }
+                }
+            }
+        }
+
+        weights.reverse();
+        biases.reverse();
+
+        ModelGradients {
+            weights,
+            biases,
+            input,
+        }
+    }
+
+    fn layers(&self) -> Vec<&Layer<F>> {
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::Layer { layer, .. } => Some(&**layer),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn layers_mut(&mut self) -> Vec<&mut Layer<F>> {
+        self.nodes
+            .iter_mut()
+            .filter_map(|node| match node {
+                Node::Layer { layer, .. } => Some(&mut **layer),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, relu, sigmoid, tanh};
+    use crate::neuron::losses::{mse, sse};
+    use crate::neuron::optimizers::{Optimizer, SGD};
+
+    use super::*;
+
+    /// Two towers over two inputs, one with a residual connection, concatenated
+    /// into a shared head with two outputs
+    fn two_tower_graph() -> Graph<f64> {
+        let mut builder = Graph::builder();
+        let user = builder.input("user", 3);
+        let item = builder.input("item", 2);
+
+        let user_hidden = builder.dense(user, 4, tanh());
+        let user_residual = builder.dense(user_hidden, 4, sigmoid());
+        let user_tower = builder.add(&[user_hidden, user_residual]);
+        let item_tower = builder.dense(item, 3, tanh());
+
+        let joined = builder.concat(&[user_tower, item_tower]);
+        let score = builder.dense(joined, 1, sigmoid());
+        let embedding = builder.dense(joined, 2, linear());
+        builder.output("score", score);
+        builder.output("embedding", embedding);
+
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn test_graph_predict_named() {
+        let graph = two_tower_graph();
+        assert_eq!(graph.input_names(), vec!["user", "item"]);
+        assert_eq!(Model::input_size(&graph), 5);
+        assert_eq!(Model::output_size(&graph), 3);
+
+        let mut inputs = HashMap::new();
+        inputs.insert("user".to_string(), array![1., 2., 3.]);
+        inputs.insert("item".to_string(), array![-1., 1.]);
+        let outputs = graph.predict_named(&inputs);
+
+        let prediction = graph.predict(&array![1., 2., 3., -1., 1.]);
+        assert_eq!(outputs["score"], prediction.slice(s![..1]));
+        assert_eq!(outputs["embedding"], prediction.slice(s![1..]));
+    }
+
+    #[test]
+    fn test_graph_gradients_match_finite_differences() {
+        let graph = two_tower_graph();
+        let input = array![0.5, -1., 2., 0.3, -0.7];
+        let expected = array![1., 0.5, -0.5];
+        let loss = sse();
+
+        let (prediction, cache) = graph.predict_cached(&input);
+        let gradients = graph.backward(&cache, &loss.derivative(&prediction, &expected));
+
+        let epsilon = 1e-6;
+        let total_loss = |graph: &Graph<f64>, input: &Array1<f64>| {
+            loss.loss(&graph.predict(input), &expected).sum()
+        };
+        let assert_close = |numeric: f64, analytic: f64| {
+            assert!(
+                (numeric - analytic).abs() < 1e-8,
+                "numeric gradient {} != analytic gradient {}",
+                numeric,
+                analytic
+            );
+        };
+
+        for (l, layer_gradients) in gradients.weights.iter().enumerate() {
+            for (index, &gradient) in layer_gradients.indexed_iter() {
+                let mut plus = graph.clone();
+                plus.layers_mut()[l].get_weights_mut()[index] += epsilon;
+                let mut minus = graph.clone();
+                minus.layers_mut()[l].get_weights_mut()[index] -= epsilon;
+
+                let numeric =
+                    (total_loss(&plus, &input) - total_loss(&minus, &input)) / (2. * epsilon);
+                assert_close(numeric, gradient);
+            }
+        }
+
+        for (i, &gradient) in gradients.input.iter().enumerate() {
+            let mut plus = input.clone();
+            plus[i] += epsilon;
+            let mut minus = input.clone();
+            minus[i] -= epsilon;
+
+            let numeric = (total_loss(&graph, &plus) - total_loss(&graph, &minus)) / (2. * epsilon);
+            assert_close(numeric, gradient);
+        }
+    }
+
+    #[test]
+    fn test_sgd_trains_residual_graph() {
+        let mut builder = Graph::builder();
+        let x = builder.input("x", 2);
+        let hidden = builder.dense(x, 8, relu());
+        let residual = builder.dense(hidden, 8, relu());
+        let sum = builder.add(&[hidden, residual]);
+        let y = builder.dense(sum, 1, linear());
+        builder.output("y", y);
+        let mut graph: Graph = builder.build().unwrap();
+
+        let batch_inputs: Vec<Array1<f32>> = (0..20)
+            .map(|i| array![i as f32 / 20., 1. - i as f32 / 20.])
+            .collect();
+        let batch_expected: Vec<Array1<f32>> = batch_inputs
+            .iter()
+            .map(|x| array![2. * x[0] - x[1]])
+            .collect();
+
+        let optimizer = SGD::new(mse());
+        let total_loss = |graph: &Graph| {
+            batch_inputs
+                .iter()
+                .zip(batch_expected.iter())
+                .map(|(input, expected)| {
+                    optimizer
+                        .get_loss()
+                        .loss(&graph.predict(input), expected)
+                        .sum()
+                })
+                .sum::<f32>()
+        };
+
+        let initial_loss = total_loss(&graph);
+        for _ in 0..500 {
+            optimizer.optimize_batch(&mut graph, &batch_inputs, &batch_expected, 0.125);
+        }
+
+        let final_loss = total_loss(&graph);
+        assert!(
+            final_loss < initial_loss / 10.,
+            "graph failed to train (loss {} -> {})",
+            initial_loss,
+            final_loss
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashSet;
+
+use crate::neuron::activations::Activation;
+use crate::neuron::layers::Layer;
+use crate::neuron::networks::graph::Node;
+use crate::neuron::networks::network_builder::layer_outputs;
+use crate::neuron::networks::{Graph, NetworkError};
+use crate::neuron::transfers::{dense, Transfer};
+use crate::neuron::Float;
+
+/// Identifies a node added to a `GraphBuilder`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone)]
+enum NodeSpec<F: Float> {
+    Input {
+        name: String,
+        size: usize,
+    },
+    Sized {
+        input: NodeId,
+        outputs: Option<usize>,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    },
+    Built {
+        input: NodeId,
+        layer: Layer<F>,
+    },
+    Add(Vec<NodeId>),
+    Concat(Vec<NodeId>),
+}
+
+/// Builds a `Graph` node by node, inferring each layer's input size from the
+/// node it is applied to
+///
+/// Nodes can only be applied to nodes added before them, so the graph is always
+/// acyclic and the order the nodes are added in is a topological order.
+#[derive(Debug, Clone)]
+pub struct GraphBuilder<F: Float = f32> {
+    nodes: Vec<NodeSpec<F>>,
+    outputs: Vec<(String, NodeId)>,
+}
+
+impl<F: Float> GraphBuilder<F> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            outputs: vec![],
+        }
+    }
+
+    fn push_node(&mut self, node: NodeSpec<F>) -> NodeId {
+        self.nodes.push(node);
+
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Add a named input of size `size`
+    ///
+    /// The inputs of a `Graph` used as a `Model` are concatenated in the order
+    /// they are added.
+    pub fn input(&mut self, name: &str, size: usize) -> NodeId {
+        self.push_node(NodeSpec::Input {
+            name: name.to_string(),
+            size,
+        })
+    }
+
+    /// Apply a dense layer with `outputs` outputs to `input`
+    pub fn dense(&mut self, input: NodeId, outputs: usize, activation_fn: Activation<F>) -> NodeId {
+        self.layer(input, outputs, dense(), activation_fn)
+    }
+
+    /// Apply a layer with `outputs` outputs to `input`
+    pub fn layer(
+        &mut self,
+        input: NodeId,
+        outputs: usize,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    ) -> NodeId {
+        self.push_node(NodeSpec::Sized {
+            input,
+            outputs: Some(outputs),
+            transfer_fn,
+            activation_fn,
+        })
+    }
+
+    /// Apply a layer whose output size is determined by its transfer to `input`,
+    /// e.g. an `embedding`
+    pub fn transfer(
+        &mut self,
+        input: NodeId,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    ) -> NodeId {
+        self.push_node(NodeSpec::Sized {
+            input,
+            outputs: None,
+            transfer_fn,
+            activation_fn,
+        })
+    }
+
+    /// Apply an existing layer to `input`, its input size must match the size of `input`
+    pub fn push(&mut self, input: NodeId, layer: Layer<F>) -> NodeId {
+        self.push_node(NodeSpec::Built { input, layer })
+    }
+
+    /// Sum nodes of the same size element-wise, e.g. for a residual connection
+    pub fn add(&mut self, inputs: &[NodeId]) -> NodeId {
+        self.push_node(NodeSpec::Add(inputs.to_vec()))
+    }
+
+    /// Concatenate nodes, in order
+    pub fn concat(&mut self, inputs: &[NodeId]) -> NodeId {
+        self.push_node(NodeSpec::Concat(inputs.to_vec()))
+    }
+
+    /// Mark `node` as a named output
+    ///
+    /// The outputs of a `Graph` used as a `Model` are concatenated in the order
+    /// they are marked.
+    pub fn output(&mut self, name: &str, node: NodeId) {
+        self.outputs.push((name.to_string(), node));
+    }
+
+    /// Create the layers and build the graph
+    pub fn build(self) -> Result<Graph<F>, NetworkError> {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut sizes: Vec<usize> = Vec::with_capacity(self.nodes.len());
+        let mut inputs = vec![];
+        let mut names = HashSet::new();
+
+        for (i, spec) in self.nodes.into_iter().enumerate() {
+            // nodes may only be applied to nodes added before them
+            let resolve = |NodeId(node): NodeId| {
+                if node < i {
+                    Ok(node)
+                } else {
+                    Err(NetworkError::InvalidNode { node: i })
+                }
+            };
+
+            let (node, size) = match spec {
+                NodeSpec::Input { name, size } => {
+                    if size == 0 {
+                        return Err(NetworkError::ZeroSize { layer: Some(i) });
+                    }
+
+                    if !names.insert(name.clone()) {
+                        return Err(NetworkError::DuplicateName(name));
+                    }
+
+                    let offset = inputs.iter().map(|&(_, node)| sizes[node]).sum();
+                    inputs.push((name, i));
+
+                    (Node::Input { offset }, size)
+                }
+                NodeSpec::Sized {
+                    input,
+                    outputs,
+                    transfer_fn,
+                    activation_fn,
+                } => {
+                    let input = resolve(input)?;
+                    let outputs = layer_outputs(i, sizes[input], outputs, &transfer_fn)?;
+                    let layer = Layer::new(outputs, sizes[input], transfer_fn, activation_fn);
+
+                    (
+                        Node::Layer {
+                            input,
+                            layer: Box::new(layer),
+                        },
+                        outputs,
+                    )
+                }
+                NodeSpec::Built { input, layer } => {
+                    let input = resolve(input)?;
+                    if layer.input_size() != sizes[input] {
+                        return Err(NetworkError::ShapeMismatch {
+                            layer: i,
+                            inputs: layer.input_size(),
+                            previous_outputs: sizes[input],
+                        });
+                    }
+
+                    let size = layer.output_size();
+
+                    (
+                        Node::Layer {
+                            input,
+                            layer: Box::new(layer),
+                        },
+                        size,
+                    )
+                }
+                NodeSpec::Add(merged) | NodeSpec::Concat(merged) if merged.is_empty() => {
+                    return Err(NetworkError::InvalidNode { node: i });
+                }
+                NodeSpec::Add(merged) => {
+                    let merged = merged
+                        .into_iter()
+                        .map(resolve)
+                        .collect::<Result<Vec<usize>, NetworkError>>()?;
+                    let merged_sizes: Vec<usize> = merged.iter().map(|&node| sizes[node]).collect();
+                    if merged_sizes.iter().any(|&size| size != merged_sizes[0]) {
+                        return Err(NetworkError::MergeMismatch {
+                            node: i,
+                            sizes: merged_sizes,
+                        });
+                    }
+
+                    (Node::Add(merged), merged_sizes[0])
+                }
+                NodeSpec::Concat(merged) => {
+                    let merged = merged
+                        .into_iter()
+                        .map(resolve)
+                        .collect::<Result<Vec<usize>, NetworkError>>()?;
+                    let size = merged.iter().map(|&node| sizes[node]).sum();
+
+                    (Node::Concat(merged), size)
+                }
+            };
+
+            nodes.push(node);
+            sizes.push(size);
+        }
+
+        if inputs.is_empty() {
+            return Err(NetworkError::NoInputs);
+        }
+
+        if self.outputs.is_empty() {
+            return Err(NetworkError::NoOutputs);
+        }
+
+        let mut outputs = Vec::with_capacity(self.outputs.len());
+        for (name, NodeId(node)) in self.outputs {
+            if node >= nodes.len() {
+                return Err(NetworkError::InvalidNode { node });
+            }
+
+            if !names.insert(name.clone()) {
+                return Err(NetworkError::DuplicateName(name));
+            }
+
+            outputs.push((name, node));
+        }
+
+        Ok(Graph::new(nodes, sizes, inputs, outputs))
+    }
+}
+
+impl<F: Float> Default for GraphBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, relu};
+
+    use super::*;
+
+    #[test]
+    fn test_build_errors() {
+        let mut builder: GraphBuilder = GraphBuilder::new();
+        let x = builder.input("x", 4);
+        let h = builder.dense(x, 8, relu());
+        let sum = builder.add(&[x, h]);
+        builder.output("y", sum);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            NetworkError::MergeMismatch {
+                node: 2,
+                sizes: vec![4, 8]
+            }
+        );
+
+        let mut builder: GraphBuilder = GraphBuilder::new();
+        let x = builder.input("x", 4);
+        let y = builder.push(x, Layer::new(2, 3, dense(), linear()));
+        builder.output("y", y);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            NetworkError::ShapeMismatch {
+                layer: 1,
+                inputs: 3,
+                previous_outputs: 4
+            }
+        );
+
+        let mut builder: GraphBuilder = GraphBuilder::new();
+        let x = builder.input("x", 4);
+        builder.input("x", 2);
+        builder.output("y", x);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            NetworkError::DuplicateName("x".to_string())
+        );
+
+        let mut builder: GraphBuilder = GraphBuilder::new();
+        builder.input("x", 4);
+        assert_eq!(builder.build().unwrap_err(), NetworkError::NoOutputs);
+
+        // a node from another builder can't refer to a later node
+        let mut other: GraphBuilder = GraphBuilder::new();
+        let x = other.input("x", 4);
+        let late = other.dense(x, 2, relu());
+        let mut builder: GraphBuilder = GraphBuilder::new();
+        let y = builder.dense(late, 2, relu());
+        builder.output("y", y);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            NetworkError::InvalidNode { node: 0 }
+        );
+    }
+}
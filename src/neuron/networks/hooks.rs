@@ -0,0 +1,126 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use ndarray::Array1;
+
+use crate::neuron::layers::{LayerCache, LayerGradients};
+use crate::neuron::Float;
+
+/// Called with a layer's cache after each cached forward pass of the layer
+pub type ForwardHook<F = f32> = Arc<dyn Fn(&LayerCache<F>) + Send + Sync>;
+
+/// Called with the derivatives of the loss with respect to a layer's activations
+/// and the layer's gradients after each backward pass of the layer
+pub type BackwardHook<F = f32> = Arc<dyn Fn(&Array1<F>, &LayerGradients<F>) + Send + Sync>;
+
+/// Identifies a hook registered on a `Network`, to remove it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HookId(usize);
+
+/// Hooks registered on the layers of a network, by layer index
+#[derive(Clone)]
+pub(super) struct Hooks<F: Float> {
+    next_id: usize,
+    forward: Vec<(HookId, usize, ForwardHook<F>)>,
+    backward: Vec<(HookId, usize, BackwardHook<F>)>,
+}
+
+impl<F: Float> Hooks<F> {
+    pub(super) fn new() -> Self {
+        Self {
+            next_id: 0,
+            forward: vec![],
+            backward: vec![],
+        }
+    }
+
+    fn next_id(&mut self) -> HookId {
+        self.next_id += 1;
+
+        HookId(self.next_id - 1)
+    }
+
+    pub(super) fn add_forward(&mut self, layer: usize, hook: ForwardHook<F>) -> HookId {
+        let id = self.next_id();
+        self.forward.push((id, layer, hook));
+
+        id
+    }
+
+    pub(super) fn add_backward(&mut self, layer: usize, hook: BackwardHook<F>) -> HookId {
+        let id = self.next_id();
+        self.backward.push((id, layer, hook));
+
+        id
+    }
+
+    /// Remove a hook, returning whether it was registered
+    pub(super) fn remove(&mut self, id: HookId) -> bool {
+        let hooks = self.forward.len() + self.backward.len();
+        self.forward.retain(|&(hook_id, _, _)| hook_id != id);
+        self.backward.retain(|&(hook_id, _, _)| hook_id != id);
+
+        self.forward.len() + self.backward.len() != hooks
+    }
+
+    /// Move the hooks of the layers from `index` on to the next layer, after a
+    /// layer is inserted at `index`
+    pub(super) fn insert_layer(&mut self, index: usize) {
+        let shift = |layer: &mut usize| {
+            if *layer >= index {
+                *layer += 1;
+            }
+        };
+        self.forward
+            .iter_mut()
+            .for_each(|(_, layer, _)| shift(layer));
+        self.backward
+            .iter_mut()
+            .for_each(|(_, layer, _)| shift(layer));
+    }
+
+    /// Drop the hooks of the layer at `index` and move the hooks of the layers
+    /// after it to the previous layer, after the layer is removed
+    pub(super) fn remove_layer(&mut self, index: usize) {
+        self.forward.retain(|&(_, layer, _)| layer != index);
+        self.backward.retain(|&(_, layer, _)| layer != index);
+
+        let shift = |layer: &mut usize| {
+            if *layer > index {
+                *layer -= 1;
+            }
+        };
+        self.forward
+            .iter_mut()
+            .for_each(|(_, layer, _)| shift(layer));
+        self.backward
+            .iter_mut()
+            .for_each(|(_, layer, _)| shift(layer));
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.forward.clear();
+        self.backward.clear();
+    }
+
+    pub(super) fn forward(&self, layer: usize, cache: &LayerCache<F>) {
+        for (_, _, hook) in self.forward.iter().filter(|(_, l, _)| *l == layer) {
+            hook(cache);
+        }
+    }
+
+    pub(super) fn backward(&self, layer: usize, dl_da: &Array1<F>, gradients: &LayerGradients<F>) {
+        for (_, _, hook) in self.backward.iter().filter(|(_, l, _)| *l == layer) {
+            hook(dl_da, gradients);
+        }
+    }
+}
+
+impl<F: Float> Debug for Hooks<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("forward", &self.forward.len())
+            .field("backward", &self.backward.len())
+            .finish()
+    }
+}
@@ -1,3 +1,26 @@
+pub use autoencoder::Autoencoder;
+pub use generative_adversarial_network::{GANLoss, GAN};
+pub use graph::{Graph, GraphCache};
+pub use graph_builder::{GraphBuilder, NodeId};
+pub use hooks::{BackwardHook, ForwardHook, HookId};
+pub use model::{Model, ModelGradients};
 pub use network::Network;
+pub use network_builder::NetworkBuilder;
+pub use network_error::NetworkError;
+pub(crate) use network_summary::format_bytes;
+pub use network_summary::{LayerSummary, NetworkSummary};
+pub use network_trace::NetworkTrace;
+pub use variational_autoencoder::VAE;
 
+mod autoencoder;
+mod generative_adversarial_network;
+mod graph;
+mod graph_builder;
+mod hooks;
+mod model;
 mod network;
+mod network_builder;
+mod network_error;
+mod network_summary;
+mod network_trace;
+mod variational_autoencoder;
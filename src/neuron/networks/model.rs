@@ -0,0 +1,242 @@
+use ndarray::{s, Array1, Array2};
+
+use crate::neuron::layers::Layer;
+use crate::neuron::losses::Loss;
+use crate::neuron::Float;
+
+/// A trainable mapping from an input to a prediction, made of `Layer`s
+///
+/// Both `Network` and `Graph` are models, so optimizers can train either.
+pub trait Model<F: Float = f32>: Clone + Send + Sync {
+    /// Values computed during a forward pass, needed for back propagation
+    type Cache;
+
+    fn input_size(&self) -> usize;
+
+    fn output_size(&self) -> usize;
+
+    fn predict(&self, input: &Array1<F>) -> Array1<F>;
+
+    /// Predict the output, keeping the values needed for back propagation
+    fn predict_cached(&self, input: &Array1<F>) -> (Array1<F>, Self::Cache);
+
+    /// Back propagate the derivatives of the loss with respect to the prediction
+    /// through a cached forward pass
+    fn backward(&self, cache: &Self::Cache, dl_dprediction: &Array1<F>) -> ModelGradients<F>;
+
+    /// All layers of the model, in the same order as the gradients
+    fn layers(&self) -> Vec<&Layer<F>>;
+
+    fn layers_mut(&mut self) -> Vec<&mut Layer<F>>;
+
+    /// Amount of weights and biases in all layers, frozen or not
+    fn parameter_count(&self) -> usize {
+        self.layers().iter().map(|l| l.parameter_count()).sum()
+    }
+
+    /// All weights and biases as a single vector
+    ///
+    /// Parameters are ordered by layer, as in `Model::layers`, and within a layer
+    /// the weights in row-major order come before the biases. Frozen layers are
+    /// included, so the ordering doesn't depend on which layers are trained.
+    fn parameters(&self) -> Array1<F> {
+        let mut parameters = Vec::with_capacity(self.parameter_count());
+        for layer in self.layers() {
+            parameters.extend(layer.get_weights().iter());
+            parameters.extend(layer.get_biases().iter());
+        }
+
+        Array1::from(parameters)
+    }
+
+    /// Overwrite all weights and biases from a vector ordered as in
+    /// `Model::parameters`
+    ///
+    /// Frozen layers are overwritten too, pruned weights stay zero. Panics if the
+    /// vector's length isn't the parameter count.
+    fn set_parameters(&mut self, parameters: &Array1<F>) {
+        assert_eq!(
+            parameters.len(),
+            self.parameter_count(),
+            "parameters length doesn't match the model"
+        );
+
+        let mut values = parameters.iter();
+        for layer in self.layers_mut() {
+            for (weight, value) in layer.get_weights_mut().iter_mut().zip(&mut values) {
+                *weight = *value;
+            }
+            for (bias, value) in layer.get_biases_mut().iter_mut().zip(&mut values) {
+                *bias = *value;
+            }
+
+            let transfer = layer.get_transfer().clone();
+            transfer.constrain(layer.get_weights_mut());
+            layer.apply_mask();
+        }
+    }
+
+    /// Derivatives of the loss of the prediction for an input with respect to the
+    /// input
+    fn loss_input_gradient(
+        &self,
+        input: &Array1<F>,
+        expected: &Array1<F>,
+        loss: &Loss<F>,
+    ) -> Array1<F> {
+        let (prediction, cache) = self.predict_cached(input);
+        let dl_dprediction = loss.derivative(&prediction, expected);
+
+        self.backward(&cache, &dl_dprediction).input
+    }
+
+    /// Derivatives of output `output` of the prediction for an input with respect
+    /// to the input
+    fn output_input_gradient(&self, input: &Array1<F>, output: usize) -> Array1<F> {
+        assert!(output < self.output_size(), "output index out of bounds");
+
+        let (_, cache) = self.predict_cached(input);
+        let mut dl_dprediction = Array1::zeros(self.output_size());
+        dl_dprediction[output] = F::one();
+
+        self.backward(&cache, &dl_dprediction).input
+    }
+}
+
+/// Derivatives of the loss with respect to each layer's parameters and the
+/// model's input
+#[derive(Debug, Clone)]
+pub struct ModelGradients<F: Float = f32> {
+    pub weights: Vec<Array2<F>>,
+    pub biases: Vec<Array1<F>>,
+    pub input: Array1<F>,
+}
+
+impl<F: Float> ModelGradients<F> {
+    /// Sum gradients of the same model
+    pub fn add_gradients(self, other: &ModelGradients<F>) -> Self {
+        Self {
+            weights: self
+                .weights
+                .into_iter()
+                .zip(other.weights.iter())
+                .map(|(total, weights)| total + weights)
+                .collect(),
+            biases: self
+                .biases
+                .into_iter()
+                .zip(other.biases.iter())
+                .map(|(total, biases)| total + biases)
+                .collect(),
+            input: self.input + &other.input,
+        }
+    }
+
+    /// All weights and biases gradients as a single vector, ordered as in
+    /// `Model::parameters`
+    pub fn flatten(&self) -> Array1<F> {
+        let mut gradients = vec![];
+        for (weights, biases) in self.weights.iter().zip(self.biases.iter()) {
+            gradients.extend(weights.iter());
+            gradients.extend(biases.iter());
+        }
+
+        Array1::from(gradients)
+    }
+
+    /// Split a vector ordered as in `Model::parameters` into gradients of the
+    /// model's layers, with zero input gradients
+    ///
+    /// Panics if the vector's length isn't the model's parameter count.
+    pub fn from_flat<M: Model<F>>(model: &M, gradients: &Array1<F>) -> Self {
+        assert_eq!(
+            gradients.len(),
+            model.parameter_count(),
+            "gradients length doesn't match the model"
+        );
+
+        let mut weights = vec![];
+        let mut biases = vec![];
+        let mut offset = 0;
+        for layer in model.layers() {
+            let shape = layer.get_weights().dim();
+            let end = offset + shape.0 * shape.1;
+            let layer_weights = gradients.slice(s![offset..end]).to_owned();
+            weights.push(layer_weights.into_shape(shape).unwrap());
+
+            offset = end + layer.get_biases().len();
+            biases.push(gradients.slice(s![end..offset]).to_owned());
+        }
+
+        Self {
+            weights,
+            biases,
+            input: Array1::zeros(model.input_size()),
+        }
+    }
+
+    /// Multiply all gradients by `scale`
+    pub fn scale(self, scale: F) -> Self {
+        Self {
+            weights: self.weights.into_iter().map(|w| w * scale).collect(),
+            biases: self.biases.into_iter().map(|b| b * scale).collect(),
+            input: self.input * scale,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{relu, sigmoid};
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    #[test]
+    fn test_parameters_round_trip() {
+        let mut network = Network::new(vec![
+            Layer::from_parameters(
+                2,
+                3,
+                array![[1., 2., 3.], [4., 5., 6.]],
+                array![7., 8.],
+                dense(),
+                relu(),
+            ),
+            Layer::from_parameters(1, 2, array![[9., 10.]], array![11.], dense(), sigmoid()),
+        ]);
+        network.freeze(1).unwrap();
+
+        let parameters = network.parameters();
+        assert_eq!(network.parameter_count(), 11);
+        assert_eq!(parameters, Array1::range(1., 12., 1.));
+
+        network.set_parameters(&(&parameters * 2.));
+        assert_eq!(network.get_weights()[1], &array![[18., 20.]]);
+        assert_eq!(network.get_biases()[0], &array![14., 16.]);
+        assert_eq!(network.parameters(), parameters * 2.);
+    }
+
+    #[test]
+    fn test_gradients_round_trip() {
+        let network: Network = Network::builder(3)
+            .dense(4, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap();
+        let (prediction, caches) = network.predict_cached(&array![1., -1., 0.5]);
+        let gradients = network.backward(&caches, &mse().derivative(&prediction, &array![1., 0.]));
+
+        let flat = gradients.flatten();
+        assert_eq!(flat.len(), network.parameter_count());
+
+        let unflattened = ModelGradients::from_flat(&network, &flat);
+        assert_eq!(unflattened.weights, gradients.weights);
+        assert_eq!(unflattened.biases, gradients.biases);
+        assert_eq!(unflattened.input, Array1::<f32>::zeros(3));
+    }
+}
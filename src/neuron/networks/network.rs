@@ -1,21 +1,54 @@
+use std::sync::Arc;
+
 use ndarray::prelude::*;
 
-use crate::neuron::layers::Layer;
+use crate::neuron::layers::{Layer, LayerCache, LayerGradients};
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::hooks::Hooks;
+use crate::neuron::networks::{
+    HookId, LayerSummary, Model, ModelGradients, NetworkBuilder, NetworkError, NetworkSummary,
+    NetworkTrace,
+};
+use crate::neuron::Float;
 
 #[derive(Debug, Clone)]
-pub struct Network {
-    layers: Vec<Layer>,
+pub struct Network<F: Float = f32> {
+    layers: Vec<Layer<F>>,
+    hooks: Hooks<F>,
 }
 
-impl Network {
-    pub fn new(layers: Vec<Layer>) -> Self {
-        Self { layers }
+impl<F: Float> Network<F> {
+    /// Create a network from a stack of layers
+    ///
+    /// Panics if the layers are inconsistent, see `Network::try_new`.
+    pub fn new(layers: Vec<Layer<F>>) -> Self {
+        Self::try_new(layers).unwrap_or_else(|e| panic!("invalid network: {}", e))
+    }
+
+    /// Create a network from a stack of layers, checking that there is at least one
+    /// layer and that each layer's input size matches the previous layer's output size
+    pub fn try_new(layers: Vec<Layer<F>>) -> Result<Self, NetworkError> {
+        check_layers(&layers)?;
+
+        Ok(Self {
+            layers,
+            hooks: Hooks::new(),
+        })
+    }
+
+    /// Start building a network that takes inputs of size `input_size`
+    pub fn builder(input_size: usize) -> NetworkBuilder<F> {
+        NetworkBuilder::new(input_size)
     }
 
     pub fn len(&self) -> usize {
         self.layers.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
     pub fn shape(&self) -> Vec<usize> {
         let mut shape = vec![self.layers[0].input_size()];
         for layer in self.layers.iter() {
@@ -25,44 +58,596 @@ impl Network {
         shape
     }
 
-    pub fn get_weights(&self) -> Vec<&Array2<f32>> {
+    /// Summarize each layer's type, shapes and parameter counts
+    pub fn summary(&self) -> NetworkSummary {
+        NetworkSummary {
+            layers: self.layers.iter().map(LayerSummary::new).collect(),
+        }
+    }
+
+    pub fn get_weights(&self) -> Vec<&Array2<F>> {
         self.layers.iter().map(|l| l.get_weights()).collect()
     }
 
-    pub fn get_biases(&self) -> Vec<&Array1<f32>> {
+    pub fn get_biases(&self) -> Vec<&Array1<F>> {
         self.layers.iter().map(|l| l.get_biases()).collect()
     }
 
-    pub fn get_weights_mut(&mut self) -> Vec<&mut Array2<f32>> {
+    pub fn get_weights_mut(&mut self) -> Vec<&mut Array2<F>> {
         self.layers
             .iter_mut()
             .map(|l| l.get_weights_mut())
             .collect()
     }
 
-    pub fn get_biases_mut(&mut self) -> Vec<&mut Array1<f32>> {
+    pub fn get_biases_mut(&mut self) -> Vec<&mut Array1<F>> {
         self.layers.iter_mut().map(|l| l.get_biases_mut()).collect()
     }
 
-    pub fn get_layers(&self) -> &Vec<Layer> {
+    pub fn get_layers(&self) -> &Vec<Layer<F>> {
         &self.layers
     }
 
-    pub fn get_layers_mut(&mut self) -> &mut Vec<Layer> {
+    /// The raw layers, changing their shapes can leave the network inconsistent,
+    /// see `Network::insert_layer`, `Network::remove_layer` and
+    /// `Network::replace_layer` for checked changes
+    pub fn get_layers_mut(&mut self) -> &mut Vec<Layer<F>> {
         &mut self.layers
     }
-    pub fn predict(&self, input: &Array1<f32>) -> Array1<f32> {
+
+    /// Stop optimizers from updating the layer at `index`
+    pub fn freeze(&mut self, index: usize) -> Result<(), NetworkError> {
+        self.check_index(index)?;
+        self.layers[index].set_trainable(false);
+
+        Ok(())
+    }
+
+    pub fn unfreeze(&mut self, index: usize) -> Result<(), NetworkError> {
+        self.check_index(index)?;
+        self.layers[index].set_trainable(true);
+
+        Ok(())
+    }
+
+    /// Freeze the first `count` layers, and unfreeze the rest, e.g. to only
+    /// train a new head on top of a pretrained network
+    pub fn freeze_first(&mut self, count: usize) {
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            layer.set_trainable(i >= count);
+        }
+    }
+
+    pub fn is_frozen(&self, index: usize) -> Result<bool, NetworkError> {
+        self.check_index(index)?;
+
+        Ok(!self.layers[index].is_trainable())
+    }
+
+    /// Indices of the frozen layers
+    pub fn frozen_layers(&self) -> Vec<usize> {
         self.layers
             .iter()
-            .fold(input.clone(), |prev_layer_output, layer| {
-                layer.forward(&prev_layer_output)
+            .enumerate()
+            .filter(|(_, layer)| !layer.is_trainable())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Insert a layer before the layer at `index`, keeping the other layers'
+    /// weights, or return an error and leave the network unchanged if the
+    /// layer's shape doesn't fit between its neighbours
+    pub fn insert_layer(&mut self, index: usize, layer: Layer<F>) -> Result<(), NetworkError> {
+        // layers can also be appended after the last layer
+        if index > self.layers.len() {
+            return Err(NetworkError::LayerIndexOutOfRange {
+                index,
+                len: self.layers.len(),
+            });
+        }
+
+        self.layers.insert(index, layer);
+        check_layers(&self.layers).inspect_err(|_| {
+            self.layers.remove(index);
+        })?;
+        self.hooks.insert_layer(index);
+
+        Ok(())
+    }
+
+    /// Remove the layer at `index`, keeping the other layers' weights, or return
+    /// an error and leave the network unchanged if its neighbours don't fit
+    /// together or it is the last layer
+    pub fn remove_layer(&mut self, index: usize) -> Result<Layer<F>, NetworkError> {
+        self.check_index(index)?;
+
+        let layer = self.layers.remove(index);
+        match check_layers(&self.layers) {
+            Ok(()) => {
+                self.hooks.remove_layer(index);
+                Ok(layer)
+            }
+            Err(e) => {
+                self.layers.insert(index, layer);
+                Err(e)
+            }
+        }
+    }
+
+    /// Replace the layer at `index`, keeping the other layers' weights, e.g. to
+    /// swap the output head of a trained network, or return an error and leave
+    /// the network unchanged if the layer's shape doesn't fit between its
+    /// neighbours
+    pub fn replace_layer(
+        &mut self,
+        index: usize,
+        layer: Layer<F>,
+    ) -> Result<Layer<F>, NetworkError> {
+        self.check_index(index)?;
+
+        let replaced = std::mem::replace(&mut self.layers[index], layer);
+        match check_layers(&self.layers) {
+            Ok(()) => Ok(replaced),
+            Err(e) => {
+                self.layers[index] = replaced;
+                Err(e)
+            }
+        }
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), NetworkError> {
+        if index < self.layers.len() {
+            Ok(())
+        } else {
+            Err(NetworkError::LayerIndexOutOfRange {
+                index,
+                len: self.layers.len(),
             })
+        }
+    }
+
+    /// Register a hook called with layer `index`'s cache after each cached forward
+    /// pass, e.g. while training, or return an error if there is no such layer
+    ///
+    /// Hooks move with their layer when other layers are inserted or removed, are
+    /// dropped with their layer, and stay on the index when the layer is replaced.
+    /// Training may forward inputs on several threads, so hooks that collect values
+    /// should use atomics or a `Mutex`.
+    pub fn add_forward_hook(
+        &mut self,
+        index: usize,
+        hook: impl Fn(&LayerCache<F>) + Send + Sync + 'static,
+    ) -> Result<HookId, NetworkError> {
+        self.check_index(index)?;
+
+        Ok(self.hooks.add_forward(index, Arc::new(hook)))
+    }
+
+    /// Register a hook called with the derivatives of the loss with respect to layer
+    /// `index`'s activations and the layer's gradients after each backward pass
+    ///
+    /// See `Network::add_forward_hook`.
+    pub fn add_backward_hook(
+        &mut self,
+        index: usize,
+        hook: impl Fn(&Array1<F>, &LayerGradients<F>) + Send + Sync + 'static,
+    ) -> Result<HookId, NetworkError> {
+        self.check_index(index)?;
+
+        Ok(self.hooks.add_backward(index, Arc::new(hook)))
+    }
+
+    /// Remove a hook, returning whether it was registered
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        self.hooks.remove(id)
+    }
+
+    pub fn clear_hooks(&mut self) {
+        self.hooks.clear();
+    }
+
+    /// Forward and back propagate an input, keeping every layer's intermediate
+    /// values and gradients
+    ///
+    /// Registered hooks are called as in training.
+    pub fn trace(
+        &self,
+        input: &Array1<F>,
+        expected: &Array1<F>,
+        loss: &Loss<F>,
+    ) -> NetworkTrace<F> {
+        let (prediction, caches) = self.predict_cached(input);
+        let (activation_gradients, gradients) =
+            self.backward_layers(&caches, &loss.derivative(&prediction, expected));
+
+        NetworkTrace {
+            loss: loss.loss(&prediction, expected).sum(),
+            prediction,
+            caches,
+            activation_gradients,
+            gradients,
+        }
     }
-    pub fn predict_cached(&mut self, input: &Array1<f32>) -> Array1<f32> {
+
+    pub fn predict(&self, input: &Array1<F>) -> Array1<F> {
         self.layers
-            .iter_mut()
+            .iter()
             .fold(input.clone(), |prev_layer_output, layer| {
-                layer.forward_cached(&prev_layer_output)
+                layer.forward(&prev_layer_output)
+            })
+    }
+
+    /// Predict the output, keeping the values computed by each layer
+    pub fn predict_cached(&self, input: &Array1<F>) -> (Array1<F>, Vec<LayerCache<F>>) {
+        let mut caches: Vec<LayerCache<F>> = Vec::with_capacity(self.layers.len());
+        for (i, layer) in self.layers.iter().enumerate() {
+            let cache = match caches.last() {
+                Some(previous) => layer.forward_cached(&previous.activation),
+                None => layer.forward_cached(input),
+            };
+
+            self.hooks.forward(i, &cache);
+            caches.push(cache);
+        }
+
+        let prediction = match caches.last() {
+            Some(cache) => cache.activation.clone(),
+            None => input.clone(),
+        };
+
+        (prediction, caches)
+    }
+
+    /// Back propagate the derivatives of the loss with respect to the prediction
+    /// through the layer caches of a forward pass
+    pub fn backward(
+        &self,
+        caches: &[LayerCache<F>],
+        dl_dprediction: &Array1<F>,
+    ) -> ModelGradients<F> {
+        let (_, gradients) = self.backward_layers(caches, dl_dprediction);
+        let input = match gradients.first() {
+            Some(first) => first.input.clone(),
+            None => dl_dprediction.clone(),
+        };
+        let (weights, biases) = gradients
+            .into_iter()
+            .map(|gradients| (gradients.weights, gradients.biases))
+            .unzip();
+
+        ModelGradients {
+            weights,
+            biases,
+            input,
+        }
+    }
+
+    /// Back propagate through every layer, calling the backward hooks, returning
+    /// the derivatives of the loss with respect to each layer's activations and
+    /// each layer's gradients, in the order of the layers
+    fn backward_layers(
+        &self,
+        caches: &[LayerCache<F>],
+        dl_dprediction: &Array1<F>,
+    ) -> (Vec<Array1<F>>, Vec<LayerGradients<F>>) {
+        let mut activation_gradients = Vec::with_capacity(self.layers.len());
+        let mut gradients = Vec::with_capacity(self.layers.len());
+
+        // derivatives of the loss with respect to the last layer's activations
+        let mut dl_da = dl_dprediction.clone();
+        for (i, (layer, cache)) in self.layers.iter().zip(caches.iter()).enumerate().rev() {
+            let layer_gradients = layer.backward(cache, &dl_da);
+            self.hooks.backward(i, &dl_da, &layer_gradients);
+
+            // BACK PROPAGATION: the derivatives of the loss with respect to the
+            // current layer's input are the derivatives of the loss with respect
+            // to the *previous* layer's activations
+            activation_gradients.push(dl_da);
+            dl_da = layer_gradients.input.clone();
+            gradients.push(layer_gradients);
+        }
+
+        activation_gradients.reverse();
+        gradients.reverse();
+
+        (activation_gradients, gradients)
+    }
+}
+
+/// Check that there is at least one layer and that each layer's input size matches
+/// the previous layer's output size
+fn check_layers<F: Float>(layers: &[Layer<F>]) -> Result<(), NetworkError> {
+    let first = layers.first().ok_or(NetworkError::NoLayers)?;
+    let mut previous_outputs = first.input_size();
+    if previous_outputs == 0 {
+        return Err(NetworkError::ZeroSize { layer: None });
+    }
+
+    for (i, layer) in layers.iter().enumerate() {
+        if layer.input_size() != previous_outputs {
+            return Err(NetworkError::ShapeMismatch {
+                layer: i,
+                inputs: layer.input_size(),
+                previous_outputs,
+            });
+        }
+
+        if layer.output_size() == 0 {
+            return Err(NetworkError::ZeroSize { layer: Some(i) });
+        }
+
+        previous_outputs = layer.output_size();
+    }
+
+    Ok(())
+}
+
+impl<F: Float> Model<F> for Network<F> {
+    type Cache = Vec<LayerCache<F>>;
+
+    fn input_size(&self) -> usize {
+        self.layers[0].input_size()
+    }
+
+    fn output_size(&self) -> usize {
+        self.layers[self.layers.len() - 1].output_size()
+    }
+
+    fn predict(&self, input: &Array1<F>) -> Array1<F> {
+        Network::predict(self, input)
+    }
+
+    fn predict_cached(&self, input: &Array1<F>) -> (Array1<F>, Self::Cache) {
+        Network::predict_cached(self, input)
+    }
+
+    fn backward(&self, cache: &Self::Cache, dl_dprediction: &Array1<F>) -> ModelGradients<F> {
+        Network::backward(self, cache, dl_dprediction)
+    }
+
+    fn layers(&self) -> Vec<&Layer<F>> {
+        self.layers.iter().collect()
+    }
+
+    fn layers_mut(&mut self) -> Vec<&mut Layer<F>> {
+        self.layers.iter_mut().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, relu, sigmoid};
+    use crate::neuron::losses::mse;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    fn pretrained() -> Network {
+        Network::builder(4)
+            .dense(8, relu())
+            .dense(6, relu())
+            .dense(10, linear())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_frozen_layers_are_not_updated() {
+        let mut network = pretrained();
+        network.freeze_first(2);
+        assert_eq!(network.frozen_layers(), vec![0, 1]);
+        network.unfreeze(1).unwrap();
+        assert!(!network.is_frozen(1).unwrap());
+        network.freeze(1).unwrap();
+        assert_eq!(
+            network.freeze(3),
+            Err(NetworkError::LayerIndexOutOfRange { index: 3, len: 3 })
+        );
+
+        let before = network.clone();
+        let inputs = vec![array![1., 0.5, -0.5, 2.]];
+        let expected = vec![Array1::ones(10)];
+        SGD::new(mse()).optimize_batch(&mut network, &inputs, &expected, 0.1);
+
+        for i in 0..2 {
+            assert_eq!(network.get_weights()[i], before.get_weights()[i]);
+            assert_eq!(network.get_biases()[i], before.get_biases()[i]);
+        }
+        assert_ne!(network.get_weights()[2], before.get_weights()[2]);
+    }
+
+    #[test]
+    fn test_replace_head() {
+        let mut network = pretrained();
+        let body_weights = network.get_weights()[1].clone();
+
+        let old_head = network
+            .replace_layer(2, Layer::new(3, 6, dense(), sigmoid()))
+            .unwrap();
+        assert_eq!(old_head.output_size(), 10);
+        assert_eq!(network.shape(), vec![4, 8, 6, 3]);
+        assert_eq!(network.get_weights()[1], &body_weights);
+
+        let error = network
+            .replace_layer(2, Layer::new(3, 5, dense(), sigmoid()))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            NetworkError::ShapeMismatch {
+                layer: 2,
+                inputs: 5,
+                previous_outputs: 6
+            }
+        );
+        assert_eq!(network.shape(), vec![4, 8, 6, 3]);
+    }
+
+    #[test]
+    fn test_insert_and_remove_layers() {
+        let mut network = pretrained();
+
+        network
+            .insert_layer(2, Layer::new(6, 6, dense(), relu()))
+            .unwrap();
+        assert_eq!(network.shape(), vec![4, 8, 6, 6, 10]);
+        assert!(network
+            .insert_layer(0, Layer::new(4, 3, dense(), relu()))
+            .is_ok());
+        assert_eq!(network.shape(), vec![3, 4, 8, 6, 6, 10]);
+
+        // layers must fit between both neighbours
+        assert!(network
+            .insert_layer(1, Layer::new(5, 4, dense(), relu()))
+            .is_err());
+        assert_eq!(network.shape(), vec![3, 4, 8, 6, 6, 10]);
+
+        assert_eq!(network.remove_layer(3).unwrap().input_size(), 6);
+        assert!(network.remove_layer(0).is_ok());
+        assert_eq!(network.shape(), vec![4, 8, 6, 10]);
+        assert!(network.remove_layer(1).is_err());
+        assert_eq!(network.shape(), vec![4, 8, 6, 10]);
+
+        // out of range indices leave the network unchanged
+        let out_of_range = NetworkError::LayerIndexOutOfRange { index: 5, len: 3 };
+        assert_eq!(
+            network
+                .insert_layer(5, Layer::new(10, 10, dense(), relu()))
+                .unwrap_err(),
+            out_of_range
+        );
+        assert_eq!(network.remove_layer(5).unwrap_err(), out_of_range);
+        assert_eq!(network.is_frozen(5), Err(out_of_range));
+        network
+            .insert_layer(3, Layer::new(2, 10, dense(), relu()))
+            .unwrap();
+        assert_eq!(network.shape(), vec![4, 8, 6, 10, 2]);
+        assert_eq!(
+            network
+                .replace_layer(5, Layer::new(2, 2, dense(), relu()))
+                .unwrap_err(),
+            NetworkError::LayerIndexOutOfRange { index: 5, len: 4 }
+        );
+
+        let mut single: Network = Network::new(vec![Layer::new(2, 2, dense(), relu())]);
+        assert_eq!(single.remove_layer(0).unwrap_err(), NetworkError::NoLayers);
+        assert_eq!(single.len(), 1);
+    }
+
+    #[test]
+    fn test_trace_matches_backward() {
+        let network = pretrained();
+        let input = array![1., 0.5, -0.5, 2.];
+        let expected = Array1::ones(10);
+
+        let trace = network.trace(&input, &expected, &mse());
+        let (prediction, caches) = network.predict_cached(&input);
+        let gradients = network.backward(&caches, &mse().derivative(&prediction, &expected));
+
+        assert_eq!(trace.prediction, prediction);
+        assert_eq!(trace.caches.len(), 3);
+        assert_eq!(
+            trace.activation_gradients[2],
+            mse().derivative(&prediction, &expected)
+        );
+        for (i, cache) in caches.iter().enumerate() {
+            assert_eq!(trace.caches[i].activation, cache.activation);
+            assert_eq!(trace.gradients[i].weights, gradients.weights[i]);
+            assert_eq!(trace.activation_gradients[i].len(), network.shape()[i + 1]);
+        }
+        assert_eq!(trace.activation_gradients[1], trace.gradients[2].input);
+        assert_eq!(trace.gradients[0].input, gradients.input);
+
+        let zeros = trace.zero_activations();
+        assert_eq!(zeros.len(), 3);
+        assert!(zeros.iter().all(|z| (0. ..=1.).contains(z)));
+    }
+
+    #[test]
+    fn test_hooks_observe_training() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+
+        let mut network = pretrained();
+        let forwards = Arc::new(AtomicUsize::new(0));
+        let dead = Arc::new(Mutex::new(vec![0; 8]));
+        let backwards = Arc::new(AtomicUsize::new(0));
+
+        let (counter, dead_counts) = (forwards.clone(), dead.clone());
+        let forward_id = network
+            .add_forward_hook(0, move |cache| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut dead_counts = dead_counts.lock().unwrap();
+                for (count, a) in dead_counts.iter_mut().zip(cache.activation.iter()) {
+                    if *a == 0. {
+                        *count += 1;
+                    }
+                }
+            })
+            .unwrap();
+        let counter = backwards.clone();
+        network
+            .add_backward_hook(2, move |dl_da, gradients| {
+                assert_eq!(dl_da.len(), 10);
+                assert_eq!(gradients.weights.dim(), (10, 6));
+                counter.fetch_add(1, Ordering::SeqCst);
             })
+            .unwrap();
+        assert_eq!(
+            network.add_backward_hook(3, |_, _| {}).unwrap_err(),
+            NetworkError::LayerIndexOutOfRange { index: 3, len: 3 }
+        );
+
+        let inputs = vec![array![1., 0.5, -0.5, 2.], array![-1., 0., 0.5, 1.]];
+        let expected = vec![Array1::ones(10), Array1::zeros(10)];
+        SGD::new(mse()).optimize_batch(&mut network, &inputs, &expected, 0.1);
+        assert_eq!(forwards.load(Ordering::SeqCst), 2);
+        assert_eq!(backwards.load(Ordering::SeqCst), 2);
+        assert!(dead.lock().unwrap().iter().all(|&count| count <= 2));
+
+        // plain predictions don't call hooks, removed hooks are not called again
+        network.predict(&inputs[0]);
+        assert_eq!(forwards.load(Ordering::SeqCst), 2);
+        assert!(network.remove_hook(forward_id));
+        assert!(!network.remove_hook(forward_id));
+        network.trace(&inputs[0], &expected[0], &mse());
+        assert_eq!(forwards.load(Ordering::SeqCst), 2);
+        assert_eq!(backwards.load(Ordering::SeqCst), 3);
+
+        network.clear_hooks();
+        network.trace(&inputs[0], &expected[0], &mse());
+        assert_eq!(backwards.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_hooks_follow_their_layer() {
+        use std::sync::{Arc, Mutex};
+
+        let mut network = pretrained();
+        let shapes = Arc::new(Mutex::new(vec![]));
+        let seen = shapes.clone();
+        network
+            .add_forward_hook(1, move |cache| {
+                seen.lock()
+                    .unwrap()
+                    .push((cache.input.len(), cache.activation.len()));
+            })
+            .unwrap();
+
+        network
+            .insert_layer(0, Layer::new(4, 4, dense(), relu()))
+            .unwrap();
+        network.predict_cached(&array![1., 0.5, -0.5, 2.]);
+        network.remove_layer(0).unwrap();
+        network.predict_cached(&array![1., 0.5, -0.5, 2.]);
+        assert_eq!(*shapes.lock().unwrap(), vec![(8, 6), (8, 6)]);
+
+        // hooks are dropped with their layer
+        network
+            .insert_layer(2, Layer::new(6, 6, dense(), relu()))
+            .unwrap();
+        let id = network.add_forward_hook(2, |_| {}).unwrap();
+        network.remove_layer(2).unwrap();
+        assert!(!network.remove_hook(id));
     }
 }
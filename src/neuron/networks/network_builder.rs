@@ -0,0 +1,214 @@
+use crate::neuron::activations::Activation;
+use crate::neuron::layers::Layer;
+use crate::neuron::networks::{Network, NetworkError};
+use crate::neuron::transfers::{dense, Transfer};
+use crate::neuron::Float;
+
+#[derive(Debug, Clone)]
+enum LayerSpec<F: Float> {
+    Sized {
+        outputs: Option<usize>,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    },
+    Built(Layer<F>),
+}
+
+/// Builds a `Network` layer by layer, inferring each layer's input size from the
+/// output size of the layer before it
+#[derive(Debug, Clone)]
+pub struct NetworkBuilder<F: Float = f32> {
+    input_size: usize,
+    layers: Vec<LayerSpec<F>>,
+}
+
+impl<F: Float> NetworkBuilder<F> {
+    pub fn new(input_size: usize) -> Self {
+        Self {
+            input_size,
+            layers: vec![],
+        }
+    }
+
+    /// Add a dense layer with `outputs` outputs
+    pub fn dense(self, outputs: usize, activation_fn: Activation<F>) -> Self {
+        self.layer(outputs, dense(), activation_fn)
+    }
+
+    /// Add a layer with `outputs` outputs
+    pub fn layer(
+        mut self,
+        outputs: usize,
+        transfer_fn: Transfer<F>,
+        activation_fn: Activation<F>,
+    ) -> Self {
+        self.layers.push(LayerSpec::Sized {
+            outputs: Some(outputs),
+            transfer_fn,
+            activation_fn,
+        });
+
+        self
+    }
+
+    /// Add a layer whose output size is determined by its transfer, e.g. an
+    /// `embedding`
+    pub fn transfer(mut self, transfer_fn: Transfer<F>, activation_fn: Activation<F>) -> Self {
+        self.layers.push(LayerSpec::Sized {
+            outputs: None,
+            transfer_fn,
+            activation_fn,
+        });
+
+        self
+    }
+
+    /// Add an existing layer, its input size must match the previous layer's output size
+    pub fn push(mut self, layer: Layer<F>) -> Self {
+        self.layers.push(LayerSpec::Built(layer));
+
+        self
+    }
+
+    /// Create the layers and build the network
+    pub fn build(self) -> Result<Network<F>, NetworkError> {
+        if self.input_size == 0 {
+            return Err(NetworkError::ZeroSize { layer: None });
+        }
+
+        let mut previous_outputs = self.input_size;
+        let mut layers = Vec::with_capacity(self.layers.len());
+        for (i, spec) in self.layers.into_iter().enumerate() {
+            let layer = match spec {
+                LayerSpec::Sized {
+                    outputs,
+                    transfer_fn,
+                    activation_fn,
+                } => {
+                    let outputs = layer_outputs(i, previous_outputs, outputs, &transfer_fn)?;
+                    Layer::new(outputs, previous_outputs, transfer_fn, activation_fn)
+                }
+                LayerSpec::Built(layer) if layer.input_size() != previous_outputs => {
+                    return Err(NetworkError::ShapeMismatch {
+                        layer: i,
+                        inputs: layer.input_size(),
+                        previous_outputs,
+                    })
+                }
+                LayerSpec::Built(layer) => layer,
+            };
+
+            previous_outputs = layer.output_size();
+            layers.push(layer);
+        }
+
+        Network::try_new(layers)
+    }
+}
+
+/// Output size of a layer with `inputs` inputs, either the size given when adding
+/// it or the size its transfer determines
+pub(super) fn layer_outputs<F: Float>(
+    layer: usize,
+    inputs: usize,
+    outputs: Option<usize>,
+    transfer_fn: &Transfer<F>,
+) -> Result<usize, NetworkError> {
+    if !transfer_fn.accepts_inputs(inputs) {
+        return Err(NetworkError::InvalidInputSize { layer, inputs });
+    }
+
+    let outputs = match (outputs, transfer_fn.output_size(inputs)) {
+        (Some(outputs), Some(transfer_outputs)) if outputs != transfer_outputs => {
+            return Err(NetworkError::OutputSizeMismatch {
+                layer,
+                outputs,
+                transfer_outputs,
+            })
+        }
+        (_, Some(outputs)) | (Some(outputs), None) => outputs,
+        (None, None) => return Err(NetworkError::UnknownOutputSize { layer }),
+    };
+
+    if outputs == 0 {
+        return Err(NetworkError::ZeroSize { layer: Some(layer) });
+    }
+
+    Ok(outputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, relu, sigmoid};
+    use crate::neuron::transfers::{conv1d, Padding};
+
+    use super::*;
+
+    #[test]
+    fn test_build_infers_input_sizes() {
+        let network: Network = Network::builder(4)
+            .dense(8, relu())
+            .push(Layer::new(3, 8, dense(), sigmoid()))
+            .dense(2, linear())
+            .build()
+            .unwrap();
+
+        assert_eq!(network.shape(), vec![4, 8, 3, 2]);
+        assert_eq!(network.predict(&array![1., 2., 3., 4.]).len(), 2);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let result: Result<Network, NetworkError> = Network::builder(4).build();
+        assert_eq!(result.unwrap_err(), NetworkError::NoLayers);
+
+        let result: Result<Network, NetworkError> = Network::builder(0).dense(2, relu()).build();
+        assert_eq!(result.unwrap_err(), NetworkError::ZeroSize { layer: None });
+
+        let result: Result<Network, NetworkError> = Network::builder(4)
+            .dense(8, relu())
+            .dense(0, relu())
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            NetworkError::ZeroSize { layer: Some(1) }
+        );
+
+        let result: Result<Network, NetworkError> = Network::builder(4)
+            .dense(8, relu())
+            .push(Layer::new(3, 7, dense(), sigmoid()))
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            NetworkError::ShapeMismatch {
+                layer: 1,
+                inputs: 7,
+                previous_outputs: 8
+            }
+        );
+
+        // 7 inputs don't split into 2 channels
+        let result: Result<Network, NetworkError> = Network::builder(4)
+            .dense(7, relu())
+            .transfer(conv1d(2, 3, 3, 1, 1, Padding::Valid), relu())
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            NetworkError::InvalidInputSize {
+                layer: 1,
+                inputs: 7
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "layer 1 expects 7 inputs")]
+    fn test_network_new_panics_on_mismatch() {
+        Network::<f32>::new(vec![
+            Layer::new(8, 4, dense(), relu()),
+            Layer::new(3, 7, dense(), sigmoid()),
+        ]);
+    }
+}
@@ -0,0 +1,108 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors returned when building a `Network` from an inconsistent stack of layers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkError {
+    /// The network has no layers
+    NoLayers,
+    /// A layer was added without an output size, and its transfer doesn't
+    /// determine one
+    UnknownOutputSize { layer: usize },
+    /// A layer's output size differs from the output size its transfer determines
+    OutputSizeMismatch {
+        layer: usize,
+        outputs: usize,
+        transfer_outputs: usize,
+    },
+    /// The graph has no inputs
+    NoInputs,
+    /// The graph has no outputs
+    NoOutputs,
+    /// A graph node refers to a node that wasn't added before it
+    InvalidNode { node: usize },
+    /// Two graph inputs or outputs have the same name
+    DuplicateName(String),
+    /// The nodes summed by a graph node have different sizes
+    MergeMismatch { node: usize, sizes: Vec<usize> },
+    /// A layer or the network input has a size of zero, for graphs `layer` is the
+    /// index of the node
+    ZeroSize { layer: Option<usize> },
+    /// A layer's input size doesn't match the output size of the layer before it,
+    /// or the network input size for the first layer. For graphs `layer` is the
+    /// index of the node
+    ShapeMismatch {
+        layer: usize,
+        inputs: usize,
+        previous_outputs: usize,
+    },
+    /// A layer's transfer doesn't accept its input size, e.g. inputs that don't
+    /// split into its channels. For graphs `layer` is the index of the node
+    InvalidInputSize { layer: usize, inputs: usize },
+    /// A layer index is past the network's `len` layers
+    LayerIndexOutOfRange { index: usize, len: usize },
+}
+
+impl Display for NetworkError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetworkError::NoLayers => write!(f, "network has no layers"),
+            NetworkError::UnknownOutputSize { layer } => write!(
+                f,
+                "layer {} has no output size and its transfer doesn't determine one",
+                layer
+            ),
+            NetworkError::OutputSizeMismatch {
+                layer,
+                outputs,
+                transfer_outputs,
+            } => write!(
+                f,
+                "layer {} has {} outputs, but its transfer outputs {}",
+                layer, outputs, transfer_outputs
+            ),
+            NetworkError::NoInputs => write!(f, "graph has no inputs"),
+            NetworkError::NoOutputs => write!(f, "graph has no outputs"),
+            NetworkError::InvalidNode { node } => write!(
+                f,
+                "node {} must only refer to nodes added before it in the same graph",
+                node
+            ),
+            NetworkError::DuplicateName(name) => {
+                write!(
+                    f,
+                    "graph has more than one input or output named '{}'",
+                    name
+                )
+            }
+            NetworkError::MergeMismatch { node, sizes } => {
+                write!(f, "node {} adds nodes of different sizes {:?}", node, sizes)
+            }
+            NetworkError::ZeroSize { layer: None } => write!(f, "network input size is zero"),
+            NetworkError::ZeroSize { layer: Some(layer) } => {
+                write!(f, "layer {} has a size of zero", layer)
+            }
+            NetworkError::ShapeMismatch {
+                layer,
+                inputs,
+                previous_outputs,
+            } => write!(
+                f,
+                "layer {} expects {} inputs, but receives {} outputs from the previous layer",
+                layer, inputs, previous_outputs
+            ),
+            NetworkError::InvalidInputSize { layer, inputs } => write!(
+                f,
+                "layer {} doesn't accept {} inputs from the previous layer",
+                layer, inputs
+            ),
+            NetworkError::LayerIndexOutOfRange { index, len } => write!(
+                f,
+                "layer index {} is out of range for a network of {} layers",
+                index, len
+            ),
+        }
+    }
+}
+
+impl Error for NetworkError {}
@@ -0,0 +1,165 @@
+use std::fmt::{Display, Formatter};
+use std::mem::size_of;
+
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::layers::Layer;
+use crate::neuron::Float;
+
+/// Shapes and parameter counts of a single `Layer`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerSummary {
+    /// Name of the layer's `Transfer`
+    pub transfer: String,
+    /// Name of the layer's `Activation`
+    pub activation: String,
+    pub input_size: usize,
+    pub output_size: usize,
+    pub weights_shape: (usize, usize),
+    pub trainable_parameters: usize,
+    pub frozen_parameters: usize,
+    /// Estimated memory used by the layer's parameters, in bytes
+    pub memory: usize,
+}
+
+impl LayerSummary {
+    pub fn new<F: Float>(layer: &Layer<F>) -> Self {
+        let parameters = layer.parameter_count();
+        let (trainable_parameters, frozen_parameters) = if layer.is_trainable() {
+            (parameters, 0)
+        } else {
+            (0, parameters)
+        };
+
+        Self {
+            transfer: layer.get_transfer().name().to_string(),
+            activation: layer.get_activation().name().to_string(),
+            input_size: layer.input_size(),
+            output_size: layer.output_size(),
+            weights_shape: layer.get_weights().dim(),
+            trainable_parameters,
+            frozen_parameters,
+            memory: parameters * size_of::<F>(),
+        }
+    }
+
+    pub fn parameters(&self) -> usize {
+        self.trainable_parameters + self.frozen_parameters
+    }
+}
+
+/// Per layer summary of a `Network`, with totals
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSummary {
+    pub layers: Vec<LayerSummary>,
+}
+
+impl NetworkSummary {
+    pub fn trainable_parameters(&self) -> usize {
+        self.layers.iter().map(|l| l.trainable_parameters).sum()
+    }
+
+    pub fn frozen_parameters(&self) -> usize {
+        self.layers.iter().map(|l| l.frozen_parameters).sum()
+    }
+
+    pub fn parameters(&self) -> usize {
+        self.layers.iter().map(|l| l.parameters()).sum()
+    }
+
+    /// Estimated memory used by the network's parameters, in bytes
+    pub fn memory(&self) -> usize {
+        self.layers.iter().map(|l| l.memory).sum()
+    }
+}
+
+impl Display for NetworkSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header = format!(
+            "{:<6} {:<20} {:<12} {:>8} {:>8} {:>12} {:>12} {:>12}",
+            "layer", "type", "activation", "inputs", "outputs", "trainable", "frozen", "memory"
+        );
+        let separator = "-".repeat(header.len());
+
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", separator)?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<6} {:<20} {:<12} {:>8} {:>8} {:>12} {:>12} {:>12}",
+                i,
+                format!(
+                    "{} {}x{}",
+                    layer.transfer, layer.weights_shape.0, layer.weights_shape.1
+                ),
+                layer.activation,
+                layer.input_size,
+                layer.output_size,
+                layer.trainable_parameters,
+                layer.frozen_parameters,
+                format_bytes(layer.memory),
+            )?;
+        }
+        writeln!(f, "{}", separator)?;
+        writeln!(f, "total parameters:     {}", self.parameters())?;
+        writeln!(f, "trainable parameters: {}", self.trainable_parameters())?;
+        writeln!(f, "frozen parameters:    {}", self.frozen_parameters())?;
+        write!(f, "parameters memory:    {}", format_bytes(self.memory()))
+    }
+}
+
+pub(crate) fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024. && unit < UNITS.len() - 1 {
+        value /= 1024.;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, relu};
+    use crate::neuron::networks::Network;
+
+    use super::*;
+
+    #[test]
+    fn test_summary_counts_parameters() {
+        let mut network: Network<f64> = Network::builder(784)
+            .dense(128, relu())
+            .dense(10, linear())
+            .build()
+            .unwrap();
+        network.get_layers_mut()[0].set_trainable(false);
+
+        let summary = network.summary();
+        assert_eq!(summary.layers.len(), 2);
+        assert_eq!(summary.layers[0].transfer, "dense");
+        assert_eq!(summary.layers[0].activation, "relu");
+        assert_eq!(summary.layers[0].weights_shape, (128, 784));
+        assert_eq!(summary.layers[0].frozen_parameters, 128 * 784 + 128);
+        assert_eq!(summary.layers[1].trainable_parameters, 10 * 128 + 10);
+        assert_eq!(summary.parameters(), 128 * 784 + 128 + 10 * 128 + 10);
+        assert_eq!(summary.memory(), summary.parameters() * 8);
+
+        let table = summary.to_string();
+        assert!(table.contains("dense 128x784"));
+        assert!(table.ends_with("parameters memory:    795.1 KiB"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(12), "12 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,32 @@
+use ndarray::Array1;
+
+use crate::neuron::layers::{LayerCache, LayerGradients};
+use crate::neuron::Float;
+
+/// Every intermediate value of a forward and backward pass through a `Network`,
+/// indexed by layer
+#[derive(Debug, Clone)]
+pub struct NetworkTrace<F: Float = f32> {
+    pub prediction: Array1<F>,
+    pub loss: F,
+    /// Input, transfer and activation of each layer
+    pub caches: Vec<LayerCache<F>>,
+    /// Derivatives of the loss with respect to each layer's activations
+    pub activation_gradients: Vec<Array1<F>>,
+    /// Derivatives of the loss with respect to each layer's parameters and input
+    pub gradients: Vec<LayerGradients<F>>,
+}
+
+impl<F: Float> NetworkTrace<F> {
+    /// Fraction of each layer's activations that are zero, e.g. dead relus
+    pub fn zero_activations(&self) -> Vec<f64> {
+        self.caches
+            .iter()
+            .map(|cache| {
+                let zeros = cache.activation.iter().filter(|a| a.is_zero()).count();
+
+                zeros as f64 / cache.activation.len().max(1) as f64
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,346 @@
+use ndarray::prelude::*;
+use ndarray::Zip;
+use ndarray_rand::rand_distr::StandardNormal;
+use ndarray_rand::RandomExt;
+
+use crate::neuron::datasets::{DataLoader, Dataset};
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::{Model, ModelGradients, Network};
+use crate::neuron::optimizers::Optimizer;
+use crate::neuron::Float;
+
+/// Gradients of the encoder and the decoder, and the sample's reconstruction
+/// and KL losses
+type VAEGradients<F> = (ModelGradients<F>, ModelGradients<F>, F, F);
+
+/// A variational autoencoder
+///
+/// The encoder maps an input to the mean and the log variance of a normal
+/// distribution over the latent space, concatenated, so its output size is
+/// twice the latent size. The decoder reconstructs the input from a latent
+/// vector sampled from that distribution with the reparameterization trick,
+/// `mean + exp(log_variance / 2) * noise` with standard normal noise, so the
+/// reconstruction loss can be back propagated into the encoder. The KL
+/// divergence of each distribution from the standard normal prior, scaled by
+/// `beta`, is added to the reconstruction loss.
+#[derive(Debug, Clone)]
+pub struct VAE<F: Float = f32> {
+    encoder: Network<F>,
+    decoder: Network<F>,
+    reconstruction: Loss<F>,
+    beta: F,
+}
+
+impl<F: Float> VAE<F> {
+    pub fn new(encoder: Network<F>, decoder: Network<F>, reconstruction: Loss<F>, beta: F) -> Self {
+        assert_eq!(
+            encoder.output_size() % 2,
+            0,
+            "encoder output size must be twice the latent size"
+        );
+        assert_eq!(
+            encoder.output_size() / 2,
+            decoder.input_size(),
+            "decoder input size must match the latent size"
+        );
+        assert_eq!(
+            decoder.output_size(),
+            encoder.input_size(),
+            "decoder output size must match the encoder input size"
+        );
+        assert!(beta >= F::zero(), "beta must not be negative");
+
+        Self {
+            encoder,
+            decoder,
+            reconstruction,
+            beta,
+        }
+    }
+
+    pub fn encoder(&self) -> &Network<F> {
+        &self.encoder
+    }
+
+    pub fn decoder(&self) -> &Network<F> {
+        &self.decoder
+    }
+
+    pub fn latent_size(&self) -> usize {
+        self.decoder.input_size()
+    }
+
+    /// Mean and log variance of the latent distribution of an input
+    pub fn encode(&self, input: &Array1<F>) -> (Array1<F>, Array1<F>) {
+        let encoded = self.encoder.predict(input);
+        let (mean, log_variance) = encoded.view().split_at(Axis(0), self.latent_size());
+
+        (mean.to_owned(), log_variance.to_owned())
+    }
+
+    pub fn decode(&self, latent: &Array1<F>) -> Array1<F> {
+        self.decoder.predict(latent)
+    }
+
+    /// Decode the mean of the latent distribution of an input
+    pub fn reconstruct(&self, input: &Array1<F>) -> Array1<F> {
+        self.decode(&self.encode(input).0)
+    }
+
+    /// Decode `count` latent vectors sampled from the standard normal prior
+    pub fn sample(&self, count: usize) -> Vec<Array1<F>> {
+        (0..count)
+            .map(|_| self.decode(&standard_normal(self.latent_size())))
+            .collect()
+    }
+
+    /// Mean reconstruction and KL losses over a dataset's inputs, each estimated
+    /// with a single latent sample
+    pub fn loss<D: Dataset<F>>(&self, dataset: &D) -> (F, F) {
+        let (reconstruction, kl) = (0..dataset.len())
+            .map(|i| {
+                let (input, _) = dataset.get(i);
+                let (_, _, reconstruction, kl) =
+                    self.gradients(&input, &standard_normal(self.latent_size()));
+
+                (reconstruction, kl)
+            })
+            .fold((F::zero(), F::zero()), |(total_r, total_kl), (r, kl)| {
+                (total_r + r, total_kl + kl)
+            });
+        let samples = F::from(dataset.len().max(1)).unwrap();
+
+        (reconstruction / samples, kl / samples)
+    }
+
+    /// Gradients of the loss of reconstructing an input from the latent vector
+    /// sampled with `noise`
+    fn gradients(&self, input: &Array1<F>, noise: &Array1<F>) -> VAEGradients<F> {
+        let (encoded, encoder_caches) = self.encoder.predict_cached(input);
+        let (mean, log_variance) = encoded.view().split_at(Axis(0), self.latent_size());
+        let half = F::from(0.5).unwrap();
+        let deviation = log_variance.mapv(|v| (v * half).exp());
+        let latent = &mean + &(&deviation * noise);
+
+        let (reconstruction, decoder_caches) = self.decoder.predict_cached(&latent);
+        let reconstruction_loss = self.reconstruction.loss(&reconstruction, input).sum();
+        let kl = Zip::from(&mean)
+            .and(&log_variance)
+            .fold(F::zero(), |kl, &m, &v| {
+                kl + half * (v.exp() + m * m - F::one() - v)
+            });
+
+        let dl_dreconstruction = self.reconstruction.derivative(&reconstruction, input);
+        let decoder_gradients = self.decoder.backward(&decoder_caches, &dl_dreconstruction);
+
+        // derivatives of the reconstruction loss through the latent vector, plus
+        // derivatives of the KL divergence
+        let dl_dlatent = decoder_gradients.input;
+        let dl_dmean = &dl_dlatent + &mean.mapv(|m| m * self.beta);
+        let dl_dlog_variance = Zip::from(&dl_dlatent)
+            .and(&deviation)
+            .and(noise)
+            .and(&log_variance)
+            .apply_collect(|&dl, &d, &n, &v| {
+                dl * d * n * half + self.beta * half * (v.exp() - F::one())
+            });
+
+        let mut dl_dencoded = dl_dmean.to_vec();
+        dl_dencoded.extend(dl_dlog_variance.iter());
+        let encoder_gradients = self
+            .encoder
+            .backward(&encoder_caches, &Array1::from(dl_dencoded));
+
+        (
+            encoder_gradients,
+            ModelGradients {
+                input: Array1::zeros(0),
+                ..decoder_gradients
+            },
+            reconstruction_loss,
+            kl,
+        )
+    }
+
+    /// Take a step along the mean gradients of a batch, returning the batch's
+    /// mean reconstruction and KL losses
+    pub fn train_batch<O: Optimizer<F>>(
+        &mut self,
+        optimizer: &O,
+        batch_inputs: &[Array1<F>],
+        learning_rate: F,
+    ) -> (F, F) {
+        if batch_inputs.is_empty() {
+            return (F::zero(), F::zero());
+        }
+
+        let batch_length = F::from(batch_inputs.len()).unwrap();
+        let (encoder_gradients, decoder_gradients, reconstruction, kl) = batch_inputs
+            .iter()
+            .map(|input| self.gradients(input, &standard_normal(self.latent_size())))
+            .reduce(|(total_e, total_d, total_r, total_kl), (e, d, r, kl)| {
+                (
+                    total_e.add_gradients(&e),
+                    total_d.add_gradients(&d),
+                    total_r + r,
+                    total_kl + kl,
+                )
+            })
+            .unwrap();
+
+        let encoder_gradients = encoder_gradients.scale(F::one() / batch_length);
+        let decoder_gradients = decoder_gradients.scale(F::one() / batch_length);
+        optimizer.apply_gradients(
+            &mut self.encoder,
+            &encoder_gradients.weights,
+            &encoder_gradients.biases,
+            learning_rate,
+        );
+        optimizer.apply_gradients(
+            &mut self.decoder,
+            &decoder_gradients.weights,
+            &decoder_gradients.biases,
+            learning_rate,
+        );
+
+        (reconstruction / batch_length, kl / batch_length)
+    }
+
+    /// Train the VAE on the inputs of a dataset, ignoring its expected outputs,
+    /// printing the losses after each epoch
+    pub fn train<O: Optimizer<F>, D: Dataset<F>>(
+        &mut self,
+        optimizer: &O,
+        train: &DataLoader<D, F>,
+        learning_rate: F,
+        epochs: usize,
+    ) {
+        for e in 0..epochs {
+            for (batch_inputs, _) in train.batches() {
+                self.train_batch(optimizer, &batch_inputs, learning_rate);
+            }
+
+            let (reconstruction, kl) = self.loss(train.dataset());
+            println!(
+                "epoch {} | reconstruction loss: {:.4} kl: {:.4}",
+                e, reconstruction, kl
+            );
+        }
+    }
+}
+
+/// Sample a vector of independent standard normal values
+pub(crate) fn standard_normal<F: Float>(size: usize) -> Array1<F> {
+    Array1::<f64>::random(size, StandardNormal).mapv(|x| F::from(x).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, sigmoid, tanh};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::losses::sse;
+    use crate::neuron::optimizers::SGD;
+
+    use super::*;
+
+    #[test]
+    fn test_vae_gradients_match_finite_differences() {
+        let encoder: Network<f64> = Network::builder(3)
+            .dense(4, tanh())
+            .dense(4, linear())
+            .build()
+            .unwrap();
+        let decoder = Network::builder(2).dense(3, sigmoid()).build().unwrap();
+        let mut vae = VAE::new(encoder, decoder, sse(), 0.5);
+        // scale up the initial weights so the gradients aren't negligible
+        for layer in vae.encoder.get_layers_mut().iter_mut() {
+            layer.get_weights_mut().mapv_inplace(|w| w * 50.);
+        }
+
+        let input = array![0.2, 0.9, 0.4];
+        let noise = array![0.3, -1.2];
+        let loss = |vae: &VAE<f64>| {
+            let (_, _, reconstruction, kl) = vae.gradients(&input, &noise);
+            reconstruction + 0.5 * kl
+        };
+
+        let (encoder_gradients, decoder_gradients, _, _) = vae.gradients(&input, &noise);
+        let epsilon = 1e-6;
+        let assert_close = |numeric: f64, analytic: f64| {
+            assert!(
+                (numeric - analytic).abs() < 1e-6,
+                "numeric gradient {} != analytic gradient {}",
+                numeric,
+                analytic
+            );
+        };
+
+        let encoder_layers = encoder_gradients
+            .weights
+            .iter()
+            .zip(&encoder_gradients.biases);
+        for (l, (weights, biases)) in encoder_layers.enumerate() {
+            for (index, &gradient) in weights.indexed_iter() {
+                let mut plus = vae.clone();
+                plus.encoder.get_weights_mut()[l][index] += epsilon;
+                let mut minus = vae.clone();
+                minus.encoder.get_weights_mut()[l][index] -= epsilon;
+                assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+            }
+            for (index, &gradient) in biases.indexed_iter() {
+                let mut plus = vae.clone();
+                plus.encoder.get_biases_mut()[l][index] += epsilon;
+                let mut minus = vae.clone();
+                minus.encoder.get_biases_mut()[l][index] -= epsilon;
+                assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+            }
+        }
+
+        for (index, &gradient) in decoder_gradients.weights[0].indexed_iter() {
+            let mut plus = vae.clone();
+            plus.decoder.get_weights_mut()[0][index] += epsilon;
+            let mut minus = vae.clone();
+            minus.decoder.get_weights_mut()[0][index] -= epsilon;
+            assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+        }
+        for (index, &gradient) in decoder_gradients.biases[0].indexed_iter() {
+            let mut plus = vae.clone();
+            plus.decoder.get_biases_mut()[0][index] += epsilon;
+            let mut minus = vae.clone();
+            minus.decoder.get_biases_mut()[0][index] -= epsilon;
+            assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+        }
+    }
+
+    #[test]
+    fn test_vae_trains_and_samples() {
+        let inputs: Vec<Array1<f32>> = Array1::linspace(0.1, 0.9, 20)
+            .iter()
+            .map(|&t| array![t, 1. - t, t * t])
+            .collect();
+        let dataset = InMemoryDataset::new(inputs.clone(), inputs);
+        let loader = DataLoader::new(dataset, 5, true, false);
+
+        let encoder = Network::builder(3).dense(2, linear()).build().unwrap();
+        let decoder = Network::builder(1).dense(3, sigmoid()).build().unwrap();
+        let mut vae = VAE::new(encoder, decoder, sse(), 0.01);
+        assert_eq!(vae.latent_size(), 1);
+
+        let optimizer = SGD::new(sse());
+        let (before, _) = vae.loss(loader.dataset());
+        vae.train(&optimizer, &loader, 0.5, 300);
+        let (after, kl) = vae.loss(loader.dataset());
+
+        assert!(
+            after < before / 5.,
+            "reconstruction loss {} didn't improve enough on {}",
+            after,
+            before
+        );
+        assert!(kl > 0.);
+
+        let samples = vae.sample(4);
+        assert_eq!(samples.len(), 4);
+        assert!(samples.iter().all(|sample| sample.len() == 3));
+    }
+}
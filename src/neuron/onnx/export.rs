@@ -0,0 +1,230 @@
+use std::fs;
+use std::mem;
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::Layer;
+use crate::neuron::networks::Network;
+use crate::neuron::onnx::protobuf::Message;
+use crate::neuron::onnx::schema::{
+    attribute, graph, model, node, operator_set, tensor, type_proto, value_info, IR_VERSION,
+    OPSET_VERSION,
+};
+use crate::neuron::onnx::OnnxError;
+use crate::neuron::Float;
+
+/// Name of the exported graph's input, of shape `[batch, inputs]`
+pub const INPUT_NAME: &str = "input";
+/// Name of the exported graph's output, of shape `[batch, outputs]`
+pub const OUTPUT_NAME: &str = "output";
+
+/// Write the network as an ONNX model, see `encode_onnx`
+pub fn write_onnx<F: Float, P: AsRef<Path>>(
+    network: &Network<F>,
+    path: P,
+) -> Result<(), OnnxError> {
+    fs::write(path, encode_onnx(network)?)?;
+
+    Ok(())
+}
+
+/// Encode the network as an ONNX model, with a `Gemm` node and an activation
+/// node per layer
+///
+/// Only dense layers are supported, with relu, leaky relu, sigmoid, tanh,
+/// softplus or linear activations. Linear activations don't add a node.
+/// Parameters are stored as `float` tensors for `f32` networks and as `double`
+/// tensors otherwise.
+pub fn encode_onnx<F: Float>(network: &Network<F>) -> Result<Vec<u8>, OnnxError> {
+    let elem_type = element_type::<F>();
+    let layers = network.get_layers();
+
+    let mut nodes = vec![];
+    let mut initializers = vec![];
+    let mut current = INPUT_NAME.to_string();
+    for (i, layer) in layers.iter().enumerate() {
+        if layer.get_transfer().name() != "dense" {
+            return Err(OnnxError::Unsupported(format!(
+                "layer {} has a {} transfer",
+                i,
+                layer.get_transfer().name()
+            )));
+        }
+
+        let activation = activation_node(i, layer)?;
+        let last = i + 1 == layers.len();
+        let gemm_output = match (&activation, last) {
+            (None, true) => OUTPUT_NAME.to_string(),
+            _ => format!("layer{}.gemm", i),
+        };
+
+        let weights_name = format!("layer{}.weight", i);
+        let biases_name = format!("layer{}.bias", i);
+        initializers.push(matrix_tensor(&weights_name, layer.get_weights(), elem_type));
+        initializers.push(vector_tensor(&biases_name, layer.get_biases(), elem_type));
+
+        let gemm = Message::new()
+            .string(node::INPUT, &current)
+            .string(node::INPUT, &weights_name)
+            .string(node::INPUT, &biases_name)
+            .string(node::OUTPUT, &gemm_output)
+            .string(node::NAME, &format!("layer{}.Gemm", i))
+            .string(node::OP_TYPE, "Gemm")
+            .message(node::ATTRIBUTE, int_attribute("transB", 1));
+        nodes.push(gemm);
+        current = gemm_output;
+
+        if let Some((op_type, alpha)) = activation {
+            let output = if last {
+                OUTPUT_NAME.to_string()
+            } else {
+                format!("layer{}.{}", i, layer.get_activation().name())
+            };
+
+            let mut activation = Message::new()
+                .string(node::INPUT, &current)
+                .string(node::OUTPUT, &output)
+                .string(node::NAME, &format!("layer{}.{}", i, op_type))
+                .string(node::OP_TYPE, op_type);
+            if let Some(alpha) = alpha {
+                activation = activation.message(node::ATTRIBUTE, float_attribute("alpha", alpha));
+            }
+            nodes.push(activation);
+            current = output;
+        }
+    }
+
+    let mut graph = Message::new();
+    for node in nodes {
+        graph = graph.message(graph::NODE, node);
+    }
+    graph = graph.string(graph::NAME, "network");
+    for initializer in initializers {
+        graph = graph.message(graph::INITIALIZER, initializer);
+    }
+    let input_size = layers.first().map_or(0, |layer| layer.input_size());
+    let output_size = layers.last().map_or(0, |layer| layer.output_size());
+    graph = graph
+        .message(graph::INPUT, value_info(INPUT_NAME, input_size, elem_type))
+        .message(
+            graph::OUTPUT,
+            value_info(OUTPUT_NAME, output_size, elem_type),
+        );
+
+    let model = Message::new()
+        .int(model::IR_VERSION, IR_VERSION)
+        .string(model::PRODUCER_NAME, env!("CARGO_PKG_NAME"))
+        .string(model::PRODUCER_VERSION, env!("CARGO_PKG_VERSION"))
+        .message(model::GRAPH, graph)
+        .message(
+            model::OPSET_IMPORT,
+            Message::new().int(operator_set::VERSION, OPSET_VERSION),
+        );
+
+    Ok(model.into_bytes())
+}
+
+/// ONNX operator and `alpha` attribute of the layer's activation, `None` for
+/// linear activations
+fn activation_node<F: Float>(
+    index: usize,
+    layer: &Layer<F>,
+) -> Result<Option<(&'static str, Option<f32>)>, OnnxError> {
+    let node = match layer.get_activation().name() {
+        "linear" => return Ok(None),
+        "relu" => ("Relu", None),
+        "leaky_relu" => ("LeakyRelu", Some(0.01)),
+        "sigmoid" => ("Sigmoid", None),
+        "tanh" => ("Tanh", None),
+        "softplus" => ("Softplus", None),
+        name => {
+            return Err(OnnxError::Unsupported(format!(
+                "layer {} has a {} activation",
+                index, name
+            )))
+        }
+    };
+
+    Ok(Some(node))
+}
+
+fn element_type<F: Float>() -> i64 {
+    if mem::size_of::<F>() == mem::size_of::<f32>() {
+        tensor::FLOAT
+    } else {
+        tensor::DOUBLE
+    }
+}
+
+fn matrix_tensor<F: Float>(name: &str, matrix: &Array2<F>, elem_type: i64) -> Message {
+    let (rows, columns) = matrix.dim();
+    tensor_message(name, &[rows, columns], matrix.iter(), elem_type)
+}
+
+fn vector_tensor<F: Float>(name: &str, vector: &Array1<F>, elem_type: i64) -> Message {
+    tensor_message(name, &[vector.len()], vector.iter(), elem_type)
+}
+
+/// Tensor with its values in row-major order as little-endian `raw_data`
+fn tensor_message<'a, F: Float>(
+    name: &str,
+    dims: &[usize],
+    values: impl Iterator<Item = &'a F>,
+    elem_type: i64,
+) -> Message {
+    let raw_data: Vec<u8> = if elem_type == tensor::FLOAT {
+        values
+            .flat_map(|v| v.to_f32().unwrap().to_le_bytes().to_vec())
+            .collect()
+    } else {
+        values
+            .flat_map(|v| v.to_f64().unwrap().to_le_bytes().to_vec())
+            .collect()
+    };
+
+    let mut message = Message::new();
+    for &dim in dims {
+        message = message.int(tensor::DIMS, dim as i64);
+    }
+
+    message
+        .int(tensor::DATA_TYPE, elem_type)
+        .string(tensor::NAME, name)
+        .bytes(tensor::RAW_DATA, &raw_data)
+}
+
+/// Tensor of shape `[batch, size]`
+fn value_info(name: &str, size: usize, elem_type: i64) -> Message {
+    let shape = Message::new()
+        .message(
+            type_proto::DIM,
+            Message::new().string(type_proto::DIM_PARAM, "batch"),
+        )
+        .message(
+            type_proto::DIM,
+            Message::new().int(type_proto::DIM_VALUE, size as i64),
+        );
+    let tensor_type = Message::new()
+        .int(type_proto::ELEM_TYPE, elem_type)
+        .message(type_proto::SHAPE, shape);
+
+    Message::new().string(value_info::NAME, name).message(
+        value_info::TYPE,
+        Message::new().message(type_proto::TENSOR_TYPE, tensor_type),
+    )
+}
+
+fn int_attribute(name: &str, value: i64) -> Message {
+    Message::new()
+        .string(attribute::NAME, name)
+        .int(attribute::I, value)
+        .int(attribute::TYPE, attribute::INT)
+}
+
+fn float_attribute(name: &str, value: f32) -> Message {
+    Message::new()
+        .string(attribute::NAME, name)
+        .float(attribute::F, value)
+        .int(attribute::TYPE, attribute::FLOAT)
+}
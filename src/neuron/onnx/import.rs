@@ -0,0 +1,602 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+
+use crate::neuron::activations::{leaky_relu, linear, relu, sigmoid, softplus, tanh, Activation};
+use crate::neuron::layers::Layer;
+use crate::neuron::networks::Network;
+use crate::neuron::onnx::protobuf::{decode_error, fields, Value};
+use crate::neuron::onnx::schema::{attribute, graph, model, node, tensor, value_info};
+use crate::neuron::onnx::OnnxError;
+use crate::neuron::transfers::dense;
+use crate::neuron::Float;
+
+/// Read an ONNX model into a network, see `decode_onnx`
+pub fn read_onnx<F: Float, P: AsRef<Path>>(path: P) -> Result<Network<F>, OnnxError> {
+    decode_onnx(&fs::read(path)?)
+}
+
+/// Decode an ONNX model of a multilayer perceptron into a network of dense
+/// layers
+///
+/// The graph must be a chain from its input to its output of `Gemm` nodes, or
+/// `MatMul` nodes optionally followed by an `Add` of the biases, each optionally
+/// followed by a `Relu`, `LeakyRelu` with the default slope, `Sigmoid`, `Tanh`
+/// or `Softplus` node. `Identity` nodes are skipped. Weights and biases must be
+/// `float` or `double` initializers.
+pub fn decode_onnx<F: Float>(bytes: &[u8]) -> Result<Network<F>, OnnxError> {
+    let mut graph_bytes = None;
+    for (field, value) in fields(bytes)? {
+        if field == model::GRAPH {
+            graph_bytes = Some(value.bytes()?);
+        }
+    }
+    let graph = Graph::decode(graph_bytes.ok_or_else(|| decode_error("model has no graph"))?)?;
+
+    let mut layers = vec![];
+    let mut pending: Option<(Array2<f64>, Array1<f64>)> = None;
+    let mut current = graph.input()?.to_string();
+    for node in &graph.nodes {
+        if node.inputs.first() != Some(&current) || node.outputs.len() != 1 {
+            return Err(OnnxError::Unsupported(format!(
+                "{} node {} isn't part of a chain of layers",
+                node.op_type, node.name
+            )));
+        }
+
+        match node.op_type.as_str() {
+            "Gemm" | "MatMul" => {
+                if let Some((weights, biases)) = pending.take() {
+                    layers.push(layer(weights, biases, linear())?);
+                }
+
+                pending = Some(if node.op_type == "Gemm" {
+                    gemm(&graph, node)?
+                } else {
+                    let weights = graph.matrix(node.input(1)?)?.reversed_axes();
+                    let biases = Array1::zeros(weights.nrows());
+                    (weights, biases)
+                });
+            }
+            "Add" => {
+                let (_, biases) = pending.as_mut().ok_or_else(|| {
+                    OnnxError::Unsupported(format!("Add node {} doesn't follow a layer", node.name))
+                })?;
+                let added = graph.tensor(node.input(1)?)?;
+                *biases += &broadcast(&added.values, biases.len(), node)?;
+            }
+            "Identity" => {}
+            op_type => {
+                let activation = match op_type {
+                    "Relu" => relu(),
+                    "LeakyRelu" => {
+                        let alpha = node.float_attribute("alpha").unwrap_or(0.01);
+                        if (alpha - 0.01).abs() > 1e-6 {
+                            return Err(OnnxError::Unsupported(format!(
+                                "LeakyRelu node {} has a slope of {}",
+                                node.name, alpha
+                            )));
+                        }
+
+                        leaky_relu()
+                    }
+                    "Sigmoid" => sigmoid(),
+                    "Tanh" => tanh(),
+                    "Softplus" => softplus(),
+                    _ => {
+                        return Err(OnnxError::Unsupported(format!(
+                            "{} node {}",
+                            op_type, node.name
+                        )))
+                    }
+                };
+
+                let (weights, biases) = pending.take().ok_or_else(|| {
+                    OnnxError::Unsupported(format!(
+                        "{} node {} doesn't follow a layer",
+                        op_type, node.name
+                    ))
+                })?;
+                layers.push(layer(weights, biases, activation)?);
+            }
+        }
+
+        current = node.outputs[0].clone();
+    }
+
+    if let Some((weights, biases)) = pending.take() {
+        layers.push(layer(weights, biases, linear())?);
+    }
+
+    if graph.outputs.first() != Some(&current) {
+        return Err(OnnxError::Unsupported(
+            "graph output isn't the end of the chain of layers".to_string(),
+        ));
+    }
+
+    Network::try_new(layers).map_err(|e| OnnxError::Unsupported(e.to_string()))
+}
+
+/// Weights of shape `(outputs, inputs)` and biases of `alpha * A · B + beta * C`
+fn gemm(graph: &Graph, node: &Node) -> Result<(Array2<f64>, Array1<f64>), OnnxError> {
+    if node.int_attribute("transA").unwrap_or(0) != 0 {
+        return Err(OnnxError::Unsupported(format!(
+            "Gemm node {} transposes its input",
+            node.name
+        )));
+    }
+
+    let mut weights = graph.matrix(node.input(1)?)?;
+    if node.int_attribute("transB").unwrap_or(0) == 0 {
+        weights = weights.reversed_axes();
+    }
+    weights *= node.float_attribute("alpha").unwrap_or(1.) as f64;
+
+    let mut biases = match node.inputs.get(2).filter(|name| !name.is_empty()) {
+        Some(name) => broadcast(&graph.tensor(name)?.values, weights.nrows(), node)?,
+        None => Array1::zeros(weights.nrows()),
+    };
+    biases *= node.float_attribute("beta").unwrap_or(1.) as f64;
+
+    Ok((weights.as_standard_layout().to_owned(), biases))
+}
+
+/// Biases from a tensor of `size` values or a single value
+fn broadcast(values: &[f64], size: usize, node: &Node) -> Result<Array1<f64>, OnnxError> {
+    match values.len() {
+        1 => Ok(Array1::from_elem(size, values[0])),
+        n if n == size => Ok(Array1::from(values.to_vec())),
+        n => Err(OnnxError::Unsupported(format!(
+            "{} node {} adds {} biases to {} outputs",
+            node.op_type, node.name, n, size
+        ))),
+    }
+}
+
+fn layer<F: Float>(
+    weights: Array2<f64>,
+    biases: Array1<f64>,
+    activation: Activation<F>,
+) -> Result<Layer<F>, OnnxError> {
+    let (outputs, inputs) = weights.dim();
+    if outputs == 0 || inputs == 0 {
+        return Err(decode_error("layer weights are empty"));
+    }
+
+    Ok(Layer::from_parameters(
+        outputs,
+        inputs,
+        weights.mapv(|w| F::from(w).unwrap()),
+        biases.mapv(|b| F::from(b).unwrap()),
+        dense(),
+        activation,
+    ))
+}
+
+struct Graph {
+    nodes: Vec<Node>,
+    initializers: HashMap<String, Tensor>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl Graph {
+    fn decode(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let mut graph = Self {
+            nodes: vec![],
+            initializers: HashMap::new(),
+            inputs: vec![],
+            outputs: vec![],
+        };
+
+        for (field, value) in fields(bytes)? {
+            match field {
+                graph::NODE => graph.nodes.push(Node::decode(value.bytes()?)?),
+                graph::INITIALIZER => {
+                    let (name, tensor) = Tensor::decode(value.bytes()?)?;
+                    graph.initializers.insert(name, tensor);
+                }
+                graph::INPUT => graph.inputs.push(value_info_name(value)?),
+                graph::OUTPUT => graph.outputs.push(value_info_name(value)?),
+                _ => {}
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// The graph's data input, older models also list initializers as inputs
+    fn input(&self) -> Result<&str, OnnxError> {
+        self.inputs
+            .iter()
+            .find(|name| !self.initializers.contains_key(*name))
+            .map(|name| name.as_str())
+            .ok_or_else(|| decode_error("graph has no input"))
+    }
+
+    fn tensor(&self, name: &str) -> Result<&Tensor, OnnxError> {
+        self.initializers
+            .get(name)
+            .ok_or_else(|| OnnxError::Unsupported(format!("{} isn't an initializer", name)))
+    }
+
+    fn matrix(&self, name: &str) -> Result<Array2<f64>, OnnxError> {
+        let tensor = self.tensor(name)?;
+        match tensor.dims[..] {
+            [rows, columns] if rows >= 0 && columns >= 0 => {
+                Array2::from_shape_vec((rows as usize, columns as usize), tensor.values.clone())
+                    .map_err(|_| decode_error(&format!("{} has the wrong amount of values", name)))
+            }
+            _ => Err(OnnxError::Unsupported(format!(
+                "{} has shape {:?}, expected a matrix",
+                name, tensor.dims
+            ))),
+        }
+    }
+}
+
+fn value_info_name(value: Value) -> Result<String, OnnxError> {
+    for (field, value) in fields(value.bytes()?)? {
+        if field == value_info::NAME {
+            return value.string();
+        }
+    }
+
+    Err(decode_error("value info has no name"))
+}
+
+struct Node {
+    name: String,
+    op_type: String,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    attributes: HashMap<String, Attribute>,
+}
+
+impl Node {
+    fn decode(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let mut node = Self {
+            name: String::new(),
+            op_type: String::new(),
+            inputs: vec![],
+            outputs: vec![],
+            attributes: HashMap::new(),
+        };
+
+        for (field, value) in fields(bytes)? {
+            match field {
+                node::INPUT => node.inputs.push(value.string()?),
+                node::OUTPUT => node.outputs.push(value.string()?),
+                node::NAME => node.name = value.string()?,
+                node::OP_TYPE => node.op_type = value.string()?,
+                node::ATTRIBUTE => {
+                    let (name, attribute) = Attribute::decode(value.bytes()?)?;
+                    node.attributes.insert(name, attribute);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(node)
+    }
+
+    fn input(&self, index: usize) -> Result<&str, OnnxError> {
+        self.inputs
+            .get(index)
+            .map(|name| name.as_str())
+            .ok_or_else(|| {
+                decode_error(&format!(
+                    "{} node {} is missing inputs",
+                    self.op_type, self.name
+                ))
+            })
+    }
+
+    fn int_attribute(&self, name: &str) -> Option<i64> {
+        self.attributes.get(name).and_then(|a| a.i)
+    }
+
+    fn float_attribute(&self, name: &str) -> Option<f32> {
+        self.attributes.get(name).and_then(|a| a.f)
+    }
+}
+
+struct Attribute {
+    f: Option<f32>,
+    i: Option<i64>,
+}
+
+impl Attribute {
+    fn decode(bytes: &[u8]) -> Result<(String, Self), OnnxError> {
+        let mut name = String::new();
+        let mut attribute = Self { f: None, i: None };
+        for (field, value) in fields(bytes)? {
+            match field {
+                attribute::NAME => name = value.string()?,
+                attribute::F => attribute.f = Some(value.float()?),
+                attribute::I => attribute.i = Some(value.int()?),
+                _ => {}
+            }
+        }
+
+        Ok((name, attribute))
+    }
+}
+
+struct Tensor {
+    dims: Vec<i64>,
+    values: Vec<f64>,
+}
+
+impl Tensor {
+    fn decode(bytes: &[u8]) -> Result<(String, Self), OnnxError> {
+        let mut name = String::new();
+        let mut dims = vec![];
+        let mut data_type = 0;
+        let mut typed_values = vec![];
+        let mut raw_data = None;
+        for (field, value) in fields(bytes)? {
+            match field {
+                tensor::DIMS => dims.extend(value.ints()?),
+                tensor::DATA_TYPE => data_type = value.int()?,
+                tensor::FLOAT_DATA => {
+                    typed_values.extend(value.floats()?.into_iter().map(f64::from))
+                }
+                tensor::DOUBLE_DATA => typed_values.extend(value.doubles()?),
+                tensor::NAME => name = value.string()?,
+                tensor::RAW_DATA => raw_data = Some(value.bytes()?),
+                _ => {}
+            }
+        }
+
+        let values = match (data_type, raw_data) {
+            (tensor::FLOAT, Some(raw)) => Value::Bytes(raw)
+                .floats()?
+                .into_iter()
+                .map(f64::from)
+                .collect(),
+            (tensor::DOUBLE, Some(raw)) => Value::Bytes(raw).doubles()?,
+            (tensor::FLOAT, None) | (tensor::DOUBLE, None) => typed_values,
+            _ => {
+                return Err(OnnxError::Unsupported(format!(
+                    "tensor {} has data type {}",
+                    name, data_type
+                )))
+            }
+        };
+
+        // negative dimensions could cancel out, and a large shape overflow, in the
+        // product
+        let size = dims.iter().try_fold(
+            1i64,
+            |size, &dim| {
+                if dim < 0 {
+                    None
+                } else {
+                    size.checked_mul(dim)
+                }
+            },
+        );
+        let size = size.ok_or_else(|| {
+            decode_error(&format!("tensor {} has an invalid shape {:?}", name, dims))
+        })?;
+        if size != values.len() as i64 {
+            return Err(decode_error(&format!(
+                "tensor {} has {} values for shape {:?}",
+                name,
+                values.len(),
+                dims
+            )));
+        }
+
+        Ok((name, Self { dims, values }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ndarray::array;
+
+    use crate::neuron::onnx::encode_onnx;
+    use crate::neuron::onnx::protobuf::Message;
+    use crate::neuron::onnx::write_onnx;
+    use crate::neuron::transfers::{conv1d, Padding};
+
+    use super::*;
+
+    fn assert_same_predictions<F: Float>(a: &Network<F>, b: &Network<F>, inputs: usize) {
+        for i in 0..5 {
+            let input = Array1::from_shape_fn(inputs, |j| {
+                F::from((i * inputs + j) as f64 * 0.37 - 1.).unwrap()
+            });
+            assert_eq!(a.predict(&input), b.predict(&input));
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let network: Network = Network::builder(3)
+            .dense(5, relu())
+            .dense(4, leaky_relu())
+            .dense(4, tanh())
+            .dense(3, softplus())
+            .dense(2, sigmoid())
+            .dense(2, linear())
+            .build()
+            .unwrap();
+
+        let path = env::temp_dir().join(format!("network_{}.onnx", std::process::id()));
+        write_onnx(&network, &path).unwrap();
+        let imported: Network = read_onnx(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(imported.shape(), network.shape());
+        assert_eq!(imported.get_weights(), network.get_weights());
+        assert_eq!(imported.get_biases(), network.get_biases());
+        let activations: Vec<&str> = imported
+            .get_layers()
+            .iter()
+            .map(|layer| layer.get_activation().name())
+            .collect();
+        assert_eq!(
+            activations,
+            vec![
+                "relu",
+                "leaky_relu",
+                "tanh",
+                "softplus",
+                "sigmoid",
+                "linear"
+            ]
+        );
+        assert_same_predictions(&network, &imported, 3);
+
+        let network: Network<f64> = Network::builder(2).dense(3, tanh()).build().unwrap();
+        let imported: Network<f64> = decode_onnx(&encode_onnx(&network).unwrap()).unwrap();
+        assert_eq!(imported.get_weights(), network.get_weights());
+        assert_same_predictions(&network, &imported, 2);
+    }
+
+    fn tensor(name: &str, dims: &[i64], values: &[f32], packed: bool) -> Message {
+        let mut message = Message::new();
+        for &dim in dims {
+            message = message.int(tensor::DIMS, dim);
+        }
+        message = message
+            .int(tensor::DATA_TYPE, tensor::FLOAT)
+            .string(tensor::NAME, name);
+        if packed {
+            let bytes: Vec<u8> = values
+                .iter()
+                .flat_map(|v| v.to_le_bytes().to_vec())
+                .collect();
+            message.bytes(tensor::FLOAT_DATA, &bytes)
+        } else {
+            values
+                .iter()
+                .fold(message, |m, &v| m.float(tensor::FLOAT_DATA, v))
+        }
+    }
+
+    fn node_message(op_type: &str, inputs: &[&str], output: &str) -> Message {
+        let message = inputs
+            .iter()
+            .fold(Message::new(), |m, input| m.string(node::INPUT, input));
+        message
+            .string(node::OUTPUT, output)
+            .string(node::NAME, output)
+            .string(node::OP_TYPE, op_type)
+    }
+
+    fn model(nodes: Vec<Message>, initializers: Vec<Message>, output: &str) -> Vec<u8> {
+        let mut graph = Message::new();
+        for node in nodes {
+            graph = graph.message(graph::NODE, node);
+        }
+        for initializer in initializers {
+            graph = graph.message(graph::INITIALIZER, initializer);
+        }
+        graph = graph
+            .message(graph::INPUT, Message::new().string(value_info::NAME, "W"))
+            .message(graph::INPUT, Message::new().string(value_info::NAME, "x"))
+            .message(
+                graph::OUTPUT,
+                Message::new().string(value_info::NAME, output),
+            );
+
+        Message::new().message(model::GRAPH, graph).into_bytes()
+    }
+
+    #[test]
+    fn test_decode_external_model() {
+        // a Gemm with untransposed weights, alpha, beta and a 2d bias, then a
+        // MatMul and Add as exported by some converters
+        let gemm = node_message("Gemm", &["x", "W", "C"], "h")
+            .message(
+                node::ATTRIBUTE,
+                Message::new()
+                    .string(attribute::NAME, "alpha")
+                    .float(attribute::F, 2.),
+            )
+            .message(
+                node::ATTRIBUTE,
+                Message::new()
+                    .string(attribute::NAME, "beta")
+                    .float(attribute::F, 0.5),
+            );
+        let bytes = model(
+            vec![
+                gemm,
+                node_message("Relu", &["h"], "a"),
+                node_message("Identity", &["a"], "b"),
+                node_message("MatMul", &["b", "V"], "m"),
+                node_message("Add", &["m", "d"], "y"),
+            ],
+            vec![
+                tensor("W", &[2, 3], &[1., 0., -1., 0.5, 1., 0.], true),
+                tensor("C", &[1, 3], &[2., 4., 6.], false),
+                tensor("V", &[3, 1], &[1., 1., 1.], true),
+                tensor("d", &[1], &[0.25], false),
+            ],
+            "y",
+        );
+
+        let network: Network = decode_onnx(&bytes).unwrap();
+        assert_eq!(network.shape(), vec![2, 3, 1]);
+        assert_eq!(
+            network.get_weights()[0],
+            &array![[2., 1.], [0., 2.], [-2., 0.]]
+        );
+        assert_eq!(network.get_biases()[0], &array![1., 2., 3.]);
+        assert_eq!(network.get_layers()[1].get_activation().name(), "linear");
+
+        // relu(2 * [[1, 0, -1], [0.5, 1, 0]]ᵀ · [1, 1] + [1, 2, 3]) = [4, 4, 1]
+        assert_eq!(network.predict(&array![1., 1.]), array![9.25]);
+    }
+
+    #[test]
+    fn test_unsupported_models() {
+        let conv: Network = Network::builder(4)
+            .layer(4, conv1d(1, 2, 3, 1, 1, Padding::Valid), relu())
+            .build()
+            .unwrap();
+        assert!(matches!(encode_onnx(&conv), Err(OnnxError::Unsupported(_))));
+
+        let bytes = model(
+            vec![
+                node_message("Gemm", &["x", "W"], "h"),
+                node_message("Softmax", &["h"], "y"),
+            ],
+            vec![tensor("W", &[1, 2], &[1., 1.], true)],
+            "y",
+        );
+        match decode_onnx::<f32>(&bytes) {
+            Err(OnnxError::Unsupported(message)) => assert!(message.contains("Softmax")),
+            _ => panic!("expected an unsupported operator"),
+        }
+
+        assert!(matches!(
+            decode_onnx::<f32>(&[0x3a, 0x10, 0x0a]),
+            Err(OnnxError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_tensor_shapes() {
+        for dims in [vec![-1, -2], vec![i64::MAX, 2, 2]].iter() {
+            let bytes = model(
+                vec![node_message("Gemm", &["x", "W"], "y")],
+                vec![tensor("W", dims, &[1., 1.], true)],
+                "y",
+            );
+            match decode_onnx::<f32>(&bytes) {
+                Err(OnnxError::Decode(message)) => assert!(message.contains("invalid shape")),
+                _ => panic!("expected an invalid shape for {:?}", dims),
+            }
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub use export::{encode_onnx, write_onnx, INPUT_NAME, OUTPUT_NAME};
+pub use import::{decode_onnx, read_onnx};
+pub use onnx_error::OnnxError;
+
+mod export;
+mod import;
+mod onnx_error;
+mod protobuf;
+mod schema;
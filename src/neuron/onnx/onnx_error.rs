@@ -0,0 +1,38 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Errors returned when reading or writing ONNX models
+#[derive(Debug)]
+pub enum OnnxError {
+    Io(io::Error),
+    /// The file isn't a valid ONNX protobuf message
+    Decode(String),
+    /// The model uses operators, attributes or layers that can't be converted
+    Unsupported(String),
+}
+
+impl Display for OnnxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnnxError::Io(e) => write!(f, "io error: {}", e),
+            OnnxError::Decode(message) => write!(f, "decode error: {}", message),
+            OnnxError::Unsupported(message) => write!(f, "unsupported: {}", message),
+        }
+    }
+}
+
+impl Error for OnnxError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OnnxError::Io(e) => Some(e),
+            OnnxError::Decode(_) | OnnxError::Unsupported(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for OnnxError {
+    fn from(e: io::Error) -> Self {
+        OnnxError::Io(e)
+    }
+}
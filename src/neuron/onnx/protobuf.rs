@@ -0,0 +1,246 @@
+//! The subset of the protobuf wire format used by ONNX models
+
+use std::convert::TryInto;
+
+use crate::neuron::onnx::OnnxError;
+
+const VARINT: u32 = 0;
+const FIXED64: u32 = 1;
+const LENGTH_DELIMITED: u32 = 2;
+const FIXED32: u32 = 5;
+
+/// An encoded protobuf message, built field by field
+#[derive(Debug, Clone, Default)]
+pub(super) struct Message {
+    bytes: Vec<u8>,
+}
+
+impl Message {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(super) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn key(&mut self, field: u32, wire_type: u32) {
+        write_varint(&mut self.bytes, ((field << 3) | wire_type) as u64);
+    }
+
+    /// An `int32`, `int64` or enum field
+    pub(super) fn int(mut self, field: u32, value: i64) -> Self {
+        self.key(field, VARINT);
+        write_varint(&mut self.bytes, value as u64);
+        self
+    }
+
+    pub(super) fn float(mut self, field: u32, value: f32) -> Self {
+        self.key(field, FIXED32);
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(super) fn bytes(mut self, field: u32, value: &[u8]) -> Self {
+        self.key(field, LENGTH_DELIMITED);
+        write_varint(&mut self.bytes, value.len() as u64);
+        self.bytes.extend_from_slice(value);
+        self
+    }
+
+    pub(super) fn string(self, field: u32, value: &str) -> Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    pub(super) fn message(self, field: u32, value: Message) -> Self {
+        self.bytes(field, &value.bytes)
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Value of a decoded field, by wire type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Value<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> Value<'a> {
+    pub(super) fn int(&self) -> Result<i64, OnnxError> {
+        match self {
+            Value::Varint(value) => Ok(*value as i64),
+            _ => Err(decode_error("expected an integer")),
+        }
+    }
+
+    pub(super) fn float(&self) -> Result<f32, OnnxError> {
+        match self {
+            Value::Fixed32(value) => Ok(f32::from_bits(*value)),
+            _ => Err(decode_error("expected a float")),
+        }
+    }
+
+    pub(super) fn bytes(&self) -> Result<&'a [u8], OnnxError> {
+        match self {
+            Value::Bytes(bytes) => Ok(bytes),
+            _ => Err(decode_error("expected a length delimited field")),
+        }
+    }
+
+    pub(super) fn string(&self) -> Result<String, OnnxError> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| decode_error("invalid utf-8 string"))
+    }
+
+    /// Integers of a repeated field, which may be packed into a single field
+    pub(super) fn ints(&self) -> Result<Vec<i64>, OnnxError> {
+        match self {
+            Value::Varint(value) => Ok(vec![*value as i64]),
+            Value::Bytes(bytes) => {
+                let mut values = vec![];
+                let mut position = 0;
+                while position < bytes.len() {
+                    values.push(read_varint(bytes, &mut position)? as i64);
+                }
+
+                Ok(values)
+            }
+            _ => Err(decode_error("expected integers")),
+        }
+    }
+
+    /// Floats of a repeated field, which may be packed into a single field
+    pub(super) fn floats(&self) -> Result<Vec<f32>, OnnxError> {
+        match self {
+            Value::Fixed32(value) => Ok(vec![f32::from_bits(*value)]),
+            Value::Bytes(bytes) => Ok(bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()),
+            _ => Err(decode_error("expected floats")),
+        }
+    }
+
+    /// Doubles of a repeated field, which may be packed into a single field
+    pub(super) fn doubles(&self) -> Result<Vec<f64>, OnnxError> {
+        match self {
+            Value::Fixed64(value) => Ok(vec![f64::from_bits(*value)]),
+            Value::Bytes(bytes) => Ok(bytes
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect()),
+            _ => Err(decode_error("expected doubles")),
+        }
+    }
+}
+
+/// Decode the fields of a message in order, as field numbers and values
+pub(super) fn fields(bytes: &[u8]) -> Result<Vec<(u32, Value<'_>)>, OnnxError> {
+    let mut fields = vec![];
+    let mut position = 0;
+    while position < bytes.len() {
+        let key = read_varint(bytes, &mut position)?;
+        let field = (key >> 3) as u32;
+        let value = match (key & 0x7) as u32 {
+            VARINT => Value::Varint(read_varint(bytes, &mut position)?),
+            FIXED64 => Value::Fixed64(u64::from_le_bytes(read_fixed(bytes, &mut position)?)),
+            LENGTH_DELIMITED => {
+                let length = read_varint(bytes, &mut position)? as usize;
+                let end = position
+                    .checked_add(length)
+                    .filter(|&end| end <= bytes.len())
+                    .ok_or_else(|| decode_error("truncated message"))?;
+                let value = Value::Bytes(&bytes[position..end]);
+                position = end;
+                value
+            }
+            FIXED32 => Value::Fixed32(u32::from_le_bytes(read_fixed(bytes, &mut position)?)),
+            wire_type => return Err(decode_error(&format!("wire type {}", wire_type))),
+        };
+
+        fields.push((field, value));
+    }
+
+    Ok(fields)
+}
+
+fn read_varint(bytes: &[u8], position: &mut usize) -> Result<u64, OnnxError> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes
+            .get(*position)
+            .ok_or_else(|| decode_error("truncated varint"))?;
+        *position += 1;
+
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte < 0x80 {
+            return Ok(value);
+        }
+    }
+
+    Err(decode_error("varint too long"))
+}
+
+fn read_fixed<const N: usize>(bytes: &[u8], position: &mut usize) -> Result<[u8; N], OnnxError> {
+    let end = *position + N;
+    let value = bytes
+        .get(*position..end)
+        .ok_or_else(|| decode_error("truncated fixed width field"))?;
+    *position = end;
+
+    Ok(value.try_into().unwrap())
+}
+
+pub(super) fn decode_error(message: &str) -> OnnxError {
+    OnnxError::Decode(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_format() {
+        // examples of the protobuf encoding documentation
+        let message = Message::new().int(1, 150).into_bytes();
+        assert_eq!(message, vec![0x08, 0x96, 0x01]);
+
+        let message = Message::new().string(2, "testing").into_bytes();
+        assert_eq!(message, b"\x12\x07testing".to_vec());
+
+        let message = Message::new()
+            .int(1, -1)
+            .float(2, 1.5)
+            .message(3, Message::new().int(1, 300))
+            .into_bytes();
+        let decoded = fields(&message).unwrap();
+        assert_eq!(decoded[0], (1, Value::Varint(u64::MAX)));
+        assert_eq!(decoded[0].1.int().unwrap(), -1);
+        assert_eq!(decoded[1].1.float().unwrap(), 1.5);
+        assert_eq!(decoded[2].1.bytes().unwrap(), &[0x08, 0xac, 0x02]);
+
+        assert!(fields(&[0x12, 0x07, b't']).is_err());
+        assert!(fields(&[0x08, 0x96]).is_err());
+    }
+
+    #[test]
+    fn test_packed_fields() {
+        let packed = Value::Bytes(&[0x03, 0x8e, 0x02, 0x9e, 0xa7, 0x05]);
+        assert_eq!(packed.ints().unwrap(), vec![3, 270, 86942]);
+
+        let bytes: Vec<u8> = [1.5f32, -2.]
+            .iter()
+            .flat_map(|f| f.to_le_bytes().to_vec())
+            .collect();
+        assert_eq!(Value::Bytes(&bytes).floats().unwrap(), vec![1.5, -2.]);
+        assert_eq!(Value::Varint(4).ints().unwrap(), vec![4]);
+    }
+}
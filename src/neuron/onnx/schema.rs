@@ -0,0 +1,69 @@
+//! Field numbers of the ONNX protobuf messages, from `onnx.proto`
+
+pub(super) mod model {
+    pub const IR_VERSION: u32 = 1;
+    pub const PRODUCER_NAME: u32 = 2;
+    pub const PRODUCER_VERSION: u32 = 3;
+    pub const GRAPH: u32 = 7;
+    pub const OPSET_IMPORT: u32 = 8;
+}
+
+pub(super) mod operator_set {
+    pub const VERSION: u32 = 2;
+}
+
+pub(super) mod graph {
+    pub const NODE: u32 = 1;
+    pub const NAME: u32 = 2;
+    pub const INITIALIZER: u32 = 5;
+    pub const INPUT: u32 = 11;
+    pub const OUTPUT: u32 = 12;
+}
+
+pub(super) mod node {
+    pub const INPUT: u32 = 1;
+    pub const OUTPUT: u32 = 2;
+    pub const NAME: u32 = 3;
+    pub const OP_TYPE: u32 = 4;
+    pub const ATTRIBUTE: u32 = 5;
+}
+
+pub(super) mod attribute {
+    pub const NAME: u32 = 1;
+    pub const F: u32 = 2;
+    pub const I: u32 = 3;
+    pub const TYPE: u32 = 20;
+
+    pub const FLOAT: i64 = 1;
+    pub const INT: i64 = 2;
+}
+
+pub(super) mod tensor {
+    pub const DIMS: u32 = 1;
+    pub const DATA_TYPE: u32 = 2;
+    pub const FLOAT_DATA: u32 = 4;
+    pub const NAME: u32 = 8;
+    pub const RAW_DATA: u32 = 9;
+    pub const DOUBLE_DATA: u32 = 10;
+
+    pub const FLOAT: i64 = 1;
+    pub const DOUBLE: i64 = 11;
+}
+
+pub(super) mod value_info {
+    pub const NAME: u32 = 1;
+    pub const TYPE: u32 = 2;
+}
+
+pub(super) mod type_proto {
+    pub const TENSOR_TYPE: u32 = 1;
+    pub const ELEM_TYPE: u32 = 1;
+    pub const SHAPE: u32 = 2;
+    pub const DIM: u32 = 1;
+    pub const DIM_VALUE: u32 = 1;
+    pub const DIM_PARAM: u32 = 2;
+}
+
+/// IR version and default domain opset the exported models conform to
+pub(super) const IR_VERSION: i64 = 7;
+pub(super) const OPSET_VERSION: i64 = 13;
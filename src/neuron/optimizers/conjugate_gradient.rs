@@ -0,0 +1,204 @@
+use std::thread;
+
+use ndarray::prelude::*;
+
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::optimizers::full_batch::{MinimizeReport, Objective};
+use crate::neuron::optimizers::limited_memory_bfgs::norm;
+use crate::neuron::optimizers::line_search::{strong_wolfe, LinePoint};
+use crate::neuron::Float;
+
+/// How each new direction is made conjugate to the previous one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConjugateGradientFormula {
+    FletcherReeves,
+    /// Polak-Ribière, restarting from the gradient when its coefficient is
+    /// negative (PR+)
+    PolakRibiere,
+}
+
+/// Nonlinear conjugate gradient on the mean loss over a full batch
+///
+/// Steps are found by a line search satisfying the strong Wolfe conditions, so
+/// there is no learning rate.
+#[derive(Clone)]
+pub struct ConjugateGradient<F: Float = f32> {
+    loss: Loss<F>,
+    formula: ConjugateGradientFormula,
+    threads: usize,
+}
+
+impl<F: Float> ConjugateGradient<F> {
+    /// Create a `ConjugateGradient` that computes gradients using all available
+    /// cores
+    pub fn new(loss: Loss<F>, formula: ConjugateGradientFormula) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+        Self::with_threads(loss, formula, threads)
+    }
+
+    /// Create a `ConjugateGradient` that splits the batch between `threads` threads
+    pub fn with_threads(loss: Loss<F>, formula: ConjugateGradientFormula, threads: usize) -> Self {
+        assert!(threads > 0, "threads must be positive");
+
+        Self {
+            loss,
+            formula,
+            threads,
+        }
+    }
+
+    pub fn get_loss(&self) -> &Loss<F> {
+        &self.loss
+    }
+
+    /// Minimize the mean loss of the network over the full batch, until the
+    /// gradient's norm is at most `tolerance` or after `max_iterations` steps
+    pub fn minimize<M: Model<F>>(
+        &self,
+        network: &mut M,
+        inputs: &[Array1<F>],
+        expected: &[Array1<F>],
+        max_iterations: usize,
+        tolerance: F,
+    ) -> MinimizeReport<F> {
+        let mut objective = Objective::new(network, &self.loss, inputs, expected, self.threads);
+        let mut parameters = network.parameters();
+        let (mut value, mut gradient) = objective.evaluate(&parameters);
+
+        let mut direction = -gradient.clone();
+        let mut previous: Option<(F, F)> = None;
+
+        let mut iterations = 0;
+        let mut converged = norm(&gradient) <= tolerance;
+        while !converged && iterations < max_iterations {
+            let mut slope = gradient.dot(&direction);
+            if slope >= F::zero() {
+                direction = -gradient.clone();
+                slope = gradient.dot(&direction);
+            }
+
+            // expect the same first order decrease as the previous step
+            let initial_step = match previous {
+                Some((step, previous_slope)) => F::one().min(step * previous_slope / slope),
+                None => F::one().min(F::one() / norm(&gradient)),
+            };
+
+            let start = LinePoint {
+                step: F::zero(),
+                value,
+                gradient: gradient.clone(),
+                slope,
+            };
+            let point = strong_wolfe(
+                |step| objective.evaluate(&(&parameters + &(&direction * step))),
+                &direction,
+                &start,
+                initial_step,
+                F::from(1e-4).unwrap(),
+                F::from(0.1).unwrap(),
+            );
+            let point = match point {
+                Some(point) => point,
+                None => break,
+            };
+
+            // restart from the gradient when consecutive gradients are far from
+            // orthogonal, as the previous direction no longer helps
+            let squared_norm = gradient.dot(&gradient);
+            let overlap = point.gradient.dot(&gradient).abs();
+            let restart = overlap >= F::from(0.2).unwrap() * point.gradient.dot(&point.gradient);
+            let beta = match self.formula {
+                _ if restart => F::zero(),
+                ConjugateGradientFormula::FletcherReeves => {
+                    point.gradient.dot(&point.gradient) / squared_norm
+                }
+                ConjugateGradientFormula::PolakRibiere => {
+                    let difference = &point.gradient - &gradient;
+                    (point.gradient.dot(&difference) / squared_norm).max(F::zero())
+                }
+            };
+
+            parameters.scaled_add(point.step, &direction);
+            direction *= beta;
+            direction -= &point.gradient;
+            previous = Some((point.step, slope));
+            value = point.value;
+            gradient = point.gradient;
+            iterations += 1;
+            converged = norm(&gradient) <= tolerance;
+        }
+
+        let evaluations = objective.evaluations;
+        *network = objective.into_model(&parameters);
+
+        MinimizeReport {
+            iterations,
+            evaluations,
+            loss: value,
+            gradient_norm: norm(&gradient),
+            converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, sigmoid};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::{mse, sse};
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    #[test]
+    fn test_conjugate_gradient_sin_convergence() {
+        let x = Array1::linspace(0.1, 0.9, 100);
+        let inputs: Vec<Array1<f32>> = x.iter().map(|&x| array![x]).collect();
+        let expected: Vec<Array1<f32>> = x.iter().map(|&x: &f32| array![x.sin()]).collect();
+
+        for &formula in &[
+            ConjugateGradientFormula::PolakRibiere,
+            ConjugateGradientFormula::FletcherReeves,
+        ] {
+            let mut network = Network::new(vec![
+                Layer::new(3, 1, dense(), sigmoid()),
+                Layer::new(1, 3, dense(), sigmoid()),
+            ]);
+
+            let optimizer = ConjugateGradient::new(sse(), formula);
+            let report = optimizer.minimize(&mut network, &inputs, &expected, 300, 1e-6);
+            assert!(
+                report.loss <= 0.001,
+                "{:?} failed to converge: {:?}",
+                formula,
+                report
+            );
+        }
+    }
+
+    #[test]
+    fn test_conjugate_gradient_solves_quadratic() {
+        // a linear least squares problem is quadratic in the parameters
+        let mut network: Network<f64> = Network::new(vec![Layer::new(1, 2, dense(), linear())]);
+        let inputs = vec![
+            array![1., 0.],
+            array![0., 2.],
+            array![1., 1.],
+            array![3., -1.],
+        ];
+        let expected = vec![array![1.], array![-1.], array![0.5], array![4.]];
+
+        let optimizer = ConjugateGradient::new(mse(), ConjugateGradientFormula::PolakRibiere);
+        let report = optimizer.minimize(&mut network, &inputs, &expected, 50, 1e-8);
+        assert!(report.converged, "{:?}", report);
+
+        // the least squares solution of the normal equations
+        let solution = array![9. / 7., -11. / 28., -2. / 7.];
+        assert!((network.parameters() - solution)
+            .iter()
+            .all(|d| d.abs() < 1e-6));
+    }
+}
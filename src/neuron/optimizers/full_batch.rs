@@ -0,0 +1,140 @@
+use std::thread;
+
+use ndarray::prelude::*;
+
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Outcome of minimizing a model's loss over a full batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinimizeReport<F: Float = f32> {
+    /// Amount of steps taken
+    pub iterations: usize,
+    /// Amount of loss and gradient evaluations over the batch
+    pub evaluations: usize,
+    /// Mean loss over the batch after the last step
+    pub loss: F,
+    /// Norm of the mean loss's gradient after the last step
+    pub gradient_norm: F,
+    /// Whether the gradient norm reached the tolerance, otherwise the optimizer ran
+    /// out of iterations or the line search couldn't decrease the loss
+    pub converged: bool,
+}
+
+/// Mean loss of a model over a full batch as a function of its flat parameters,
+/// as in `Model::parameters`
+///
+/// Gradients of frozen layers and pruned weights are zero, so steps along them
+/// leave the parameters unchanged.
+pub(super) struct Objective<'a, F: Float, M: Model<F>> {
+    model: M,
+    loss: &'a Loss<F>,
+    inputs: &'a [Array1<F>],
+    expected: &'a [Array1<F>],
+    threads: usize,
+    trainable: Array1<F>,
+    pub(super) evaluations: usize,
+}
+
+impl<'a, F: Float, M: Model<F>> Objective<'a, F, M> {
+    pub(super) fn new(
+        model: &M,
+        loss: &'a Loss<F>,
+        inputs: &'a [Array1<F>],
+        expected: &'a [Array1<F>],
+        threads: usize,
+    ) -> Self {
+        assert_eq!(
+            inputs.len(),
+            expected.len(),
+            "batch inputs and expected must be of same length"
+        );
+        assert!(!inputs.is_empty(), "batch must not be empty");
+
+        let value = |trainable: bool| if trainable { F::one() } else { F::zero() };
+        let mut trainable = Vec::with_capacity(model.parameter_count());
+        for layer in model.layers() {
+            let weights = layer.get_weights().len();
+            match layer.get_mask() {
+                Some(mask) if layer.is_trainable() => {
+                    trainable.extend(mask.iter().map(|&kept| value(kept)))
+                }
+                _ => trainable.extend(std::iter::repeat_n(value(layer.is_trainable()), weights)),
+            }
+            let biases = layer.get_biases().len();
+            trainable.extend(std::iter::repeat_n(value(layer.is_trainable()), biases));
+        }
+
+        Self {
+            model: model.clone(),
+            loss,
+            inputs,
+            expected,
+            threads,
+            trainable: Array1::from(trainable),
+            evaluations: 0,
+        }
+    }
+
+    /// Mean loss and its gradient at the parameters
+    pub(super) fn evaluate(&mut self, parameters: &Array1<F>) -> (F, Array1<F>) {
+        self.evaluations += 1;
+        self.model.set_parameters(parameters);
+
+        let chunk_size = self.inputs.len().div_ceil(self.threads);
+        let (model, loss) = (&self.model, self.loss);
+        let (total_loss, total_gradients) = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .inputs
+                .chunks(chunk_size)
+                .zip(self.expected.chunks(chunk_size))
+                .map(|(chunk_inputs, chunk_expected)| {
+                    scope.spawn(move || chunk_objective(model, loss, chunk_inputs, chunk_expected))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("objective thread panicked"))
+                .reduce(|(total_loss, total_gradients), (loss, gradients)| {
+                    (total_loss + loss, total_gradients + &gradients)
+                })
+                .unwrap()
+        });
+
+        let batch_length = F::from(self.inputs.len()).unwrap();
+
+        (
+            total_loss / batch_length,
+            total_gradients * &self.trainable / batch_length,
+        )
+    }
+
+    /// The model with the given parameters
+    pub(super) fn into_model(mut self, parameters: &Array1<F>) -> M {
+        self.model.set_parameters(parameters);
+
+        self.model
+    }
+}
+
+/// Summed loss and flat gradients of a chunk of the batch
+fn chunk_objective<F: Float, M: Model<F>>(
+    model: &M,
+    loss: &Loss<F>,
+    inputs: &[Array1<F>],
+    expected: &[Array1<F>],
+) -> (F, Array1<F>) {
+    let mut total_loss = F::zero();
+    let mut total_gradients = Array1::zeros(model.parameter_count());
+    for (input, expected) in inputs.iter().zip(expected.iter()) {
+        let (prediction, cache) = model.predict_cached(input);
+        total_loss += loss.loss(&prediction, expected).sum();
+
+        let dl_dprediction = loss.derivative(&prediction, expected);
+        total_gradients += &model.backward(&cache, &dl_dprediction).flatten();
+    }
+
+    (total_loss, total_gradients)
+}
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::thread;
+
+use ndarray::prelude::*;
+
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::optimizers::full_batch::{MinimizeReport, Objective};
+use crate::neuron::optimizers::line_search::{strong_wolfe, LinePoint};
+use crate::neuron::Float;
+
+/// Limited-memory BFGS, a quasi-Newton method estimating the inverse Hessian of the
+/// mean loss over a full batch from the last `history` steps
+///
+/// Steps are found by a line search satisfying the strong Wolfe conditions, so
+/// there is no learning rate.
+#[derive(Clone)]
+pub struct LBFGS<F: Float = f32> {
+    loss: Loss<F>,
+    history: usize,
+    threads: usize,
+}
+
+impl<F: Float> LBFGS<F> {
+    /// Create an `LBFGS` that computes gradients using all available cores
+    pub fn new(loss: Loss<F>, history: usize) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+        Self::with_threads(loss, history, threads)
+    }
+
+    /// Create an `LBFGS` that splits the batch between `threads` threads
+    pub fn with_threads(loss: Loss<F>, history: usize, threads: usize) -> Self {
+        assert!(history > 0, "history must be positive");
+        assert!(threads > 0, "threads must be positive");
+
+        Self {
+            loss,
+            history,
+            threads,
+        }
+    }
+
+    pub fn get_loss(&self) -> &Loss<F> {
+        &self.loss
+    }
+
+    /// Minimize the mean loss of the network over the full batch, until the
+    /// gradient's norm is at most `tolerance` or after `max_iterations` steps
+    pub fn minimize<M: Model<F>>(
+        &self,
+        network: &mut M,
+        inputs: &[Array1<F>],
+        expected: &[Array1<F>],
+        max_iterations: usize,
+        tolerance: F,
+    ) -> MinimizeReport<F> {
+        let mut objective = Objective::new(network, &self.loss, inputs, expected, self.threads);
+        let mut parameters = network.parameters();
+        let (mut value, mut gradient) = objective.evaluate(&parameters);
+
+        // differences of the parameters and gradients of the last steps
+        let mut steps: VecDeque<(Array1<F>, Array1<F>)> = VecDeque::with_capacity(self.history);
+
+        let mut iterations = 0;
+        let mut converged = norm(&gradient) <= tolerance;
+        while !converged && iterations < max_iterations {
+            let mut direction = -inverse_hessian_product(&steps, &gradient);
+            let mut slope = gradient.dot(&direction);
+            if slope >= F::zero() {
+                // the estimate lost positive definiteness, restart from the gradient
+                steps.clear();
+                direction = -gradient.clone();
+                slope = gradient.dot(&direction);
+            }
+
+            // the first direction isn't scaled by any curvature estimate
+            let initial_step = if steps.is_empty() {
+                F::one().min(F::one() / norm(&gradient))
+            } else {
+                F::one()
+            };
+
+            let start = LinePoint {
+                step: F::zero(),
+                value,
+                gradient: gradient.clone(),
+                slope,
+            };
+            let point = strong_wolfe(
+                |step| objective.evaluate(&(&parameters + &(&direction * step))),
+                &direction,
+                &start,
+                initial_step,
+                F::from(1e-4).unwrap(),
+                F::from(0.9).unwrap(),
+            );
+            let point = match point {
+                Some(point) => point,
+                None => break,
+            };
+
+            let s = &direction * point.step;
+            let y = &point.gradient - &gradient;
+            if s.dot(&y) > F::epsilon() * y.dot(&y) {
+                if steps.len() == self.history {
+                    steps.pop_front();
+                }
+                steps.push_back((s.clone(), y));
+            }
+
+            parameters += &s;
+            value = point.value;
+            gradient = point.gradient;
+            iterations += 1;
+            converged = norm(&gradient) <= tolerance;
+        }
+
+        let evaluations = objective.evaluations;
+        *network = objective.into_model(&parameters);
+
+        MinimizeReport {
+            iterations,
+            evaluations,
+            loss: value,
+            gradient_norm: norm(&gradient),
+            converged,
+        }
+    }
+}
+
+/// Product of the estimated inverse Hessian and the gradient, using the two-loop
+/// recursion
+fn inverse_hessian_product<F: Float>(
+    steps: &VecDeque<(Array1<F>, Array1<F>)>,
+    gradient: &Array1<F>,
+) -> Array1<F> {
+    let mut q = gradient.clone();
+    let mut alphas = Vec::with_capacity(steps.len());
+    for (s, y) in steps.iter().rev() {
+        let alpha = s.dot(&q) / y.dot(s);
+        q.scaled_add(-alpha, y);
+        alphas.push(alpha);
+    }
+
+    // scale the initial estimate by the curvature along the last step
+    if let Some((s, y)) = steps.back() {
+        q *= s.dot(y) / y.dot(y);
+    }
+
+    for ((s, y), alpha) in steps.iter().zip(alphas.into_iter().rev()) {
+        let beta = y.dot(&q) / y.dot(s);
+        q.scaled_add(alpha - beta, s);
+    }
+
+    q
+}
+
+pub(super) fn norm<F: Float>(vector: &Array1<F>) -> F {
+    vector.dot(vector).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{linear, sigmoid, tanh};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::{mse, sse};
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    fn sin_batch() -> (Vec<Array1<f32>>, Vec<Array1<f32>>) {
+        let inputs = Array1::linspace(0.1, 0.9, 100);
+
+        (
+            inputs.iter().map(|&x| array![x]).collect(),
+            inputs.iter().map(|&x: &f32| array![x.sin()]).collect(),
+        )
+    }
+
+    #[test]
+    fn test_lbfgs_sin_convergence() {
+        let mut network = Network::new(vec![
+            Layer::new(3, 1, dense(), sigmoid()),
+            Layer::new(1, 3, dense(), sigmoid()),
+        ]);
+        let (inputs, expected) = sin_batch();
+
+        // SGD needs 1000 epochs on the same problem
+        let optimizer = LBFGS::new(sse(), 10);
+        let report = optimizer.minimize(&mut network, &inputs, &expected, 100, 1e-6);
+
+        let cost = inputs
+            .iter()
+            .zip(expected.iter())
+            .map(|(input, expected)| sse().loss(&network.predict(input), expected).sum())
+            .sum::<f32>()
+            / 100.;
+        assert!((cost - report.loss).abs() < 1e-6);
+        assert!(cost <= 0.0001, "optimizer failed to converge: {:?}", report);
+    }
+
+    #[test]
+    fn test_lbfgs_solves_linear_regression() {
+        let mut network: Network<f64> = Network::new(vec![Layer::new(1, 3, dense(), linear())]);
+        network.freeze(0).unwrap();
+        let inputs: Vec<Array1<f64>> = (0..20)
+            .map(|i| {
+                let x = i as f64 / 10.;
+                array![x, x * x, (x * 3.).cos()]
+            })
+            .collect();
+        let expected: Vec<Array1<f64>> = inputs
+            .iter()
+            .map(|x| array![2. * x[0] - x[1] + 0.5 * x[2] + 1.])
+            .collect();
+
+        // frozen layers are left unchanged
+        let frozen = network.parameters();
+        let report = LBFGS::new(mse(), 5).minimize(&mut network, &inputs, &expected, 50, 1e-10);
+        assert_eq!(network.parameters(), frozen);
+        assert!(report.converged);
+        assert_eq!(report.iterations, 0);
+
+        network.unfreeze(0).unwrap();
+        let report =
+            LBFGS::with_threads(mse(), 5, 2).minimize(&mut network, &inputs, &expected, 50, 1e-10);
+        assert!(report.converged, "{:?}", report);
+        assert!((network.parameters() - array![2., -1., 0.5, 1.])
+            .iter()
+            .all(|d| d.abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_gradient_norm_matches_finite_differences() {
+        let network: Network<f64> = Network::new(vec![
+            Layer::new(4, 3, dense(), tanh()),
+            Layer::new(3, 4, dense(), sigmoid()),
+        ]);
+        let inputs: Vec<Array1<f64>> = (0..5)
+            .map(|i| array![i as f64 / 5., 1. - i as f64 / 10., (i as f64).sin()])
+            .collect();
+        let expected: Vec<Array1<f64>> = inputs
+            .iter()
+            .map(|x| array![x[0], x[1] * x[2], 0.5])
+            .collect();
+
+        // no steps, so the report holds the gradient at the initial parameters
+        let mut minimized = network.clone();
+        let report = LBFGS::new(mse(), 5).minimize(&mut minimized, &inputs, &expected, 0, 0.);
+
+        let mean_loss = |parameters: &Array1<f64>| {
+            let mut network = network.clone();
+            network.set_parameters(parameters);
+            inputs
+                .iter()
+                .zip(expected.iter())
+                .map(|(input, expected)| mse().loss(&network.predict(input), expected).sum())
+                .sum::<f64>()
+                / inputs.len() as f64
+        };
+        let epsilon = 1e-6;
+        let parameters = network.parameters();
+        let numeric = Array1::from_shape_fn(parameters.len(), |i| {
+            let mut plus = parameters.clone();
+            plus[i] += epsilon;
+            let mut minus = parameters.clone();
+            minus[i] -= epsilon;
+
+            (mean_loss(&plus) - mean_loss(&minus)) / (2. * epsilon)
+        });
+
+        assert!((report.loss - mean_loss(&parameters)).abs() < 1e-12);
+        assert!(
+            (report.gradient_norm - norm(&numeric)).abs() < 1e-8,
+            "gradient norm {} != numeric gradient norm {}",
+            report.gradient_norm,
+            norm(&numeric)
+        );
+    }
+}
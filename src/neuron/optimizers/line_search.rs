@@ -0,0 +1,171 @@
+use ndarray::prelude::*;
+
+use crate::neuron::Float;
+
+const MAX_ITERATIONS: usize = 25;
+
+/// A point along the search direction
+#[derive(Debug, Clone)]
+pub(super) struct LinePoint<F: Float> {
+    pub(super) step: F,
+    pub(super) value: F,
+    pub(super) gradient: Array1<F>,
+    /// Derivative of the value along the search direction
+    pub(super) slope: F,
+}
+
+/// Find a step along `direction` satisfying the strong Wolfe conditions, with
+/// constants `0 < c1 < c2 < 1`
+///
+/// `objective` returns the value and gradient at a step from the start, and
+/// `start` is the point at step zero. Returns `None` if no step decreases the
+/// value, e.g. when `direction` isn't a descent direction.
+///
+/// Follows algorithms 3.5 and 3.6 of Nocedal & Wright, Numerical Optimization,
+/// zooming in with cubic interpolation.
+pub(super) fn strong_wolfe<F: Float>(
+    mut objective: impl FnMut(F) -> (F, Array1<F>),
+    direction: &Array1<F>,
+    start: &LinePoint<F>,
+    initial_step: F,
+    c1: F,
+    c2: F,
+) -> Option<LinePoint<F>> {
+    if start.slope >= F::zero() {
+        return None;
+    }
+
+    let mut evaluate = |step: F| {
+        let (value, gradient) = objective(step);
+        let slope = gradient.dot(direction);
+
+        LinePoint {
+            step,
+            value,
+            gradient,
+            slope,
+        }
+    };
+
+    let sufficient_decrease =
+        |point: &LinePoint<F>| point.value <= start.value + c1 * point.step * start.slope;
+    let curvature = |point: &LinePoint<F>| point.slope.abs() <= -c2 * start.slope;
+
+    let mut previous = start.clone();
+    let mut step = initial_step;
+    for i in 0..MAX_ITERATIONS {
+        let point = evaluate(step);
+        if !point.value.is_finite() {
+            // stepped too far, e.g. into an overflow, search the smaller steps
+            step = (previous.step + step) / F::from(2.).unwrap();
+            continue;
+        }
+
+        if !sufficient_decrease(&point) || (i > 0 && point.value >= previous.value) {
+            return zoom(&mut evaluate, previous, point, start, c1, c2);
+        }
+
+        if curvature(&point) {
+            return Some(point);
+        }
+
+        if point.slope >= F::zero() {
+            return zoom(&mut evaluate, point, previous, start, c1, c2);
+        }
+
+        step = point.step * F::from(2.).unwrap();
+        previous = point;
+    }
+
+    Some(previous).filter(|point| point.step > F::zero())
+}
+
+/// Shrink the interval between `low`, the lowest point satisfying the sufficient
+/// decrease condition, and `high` until a point satisfies the strong Wolfe
+/// conditions
+fn zoom<F: Float>(
+    evaluate: &mut impl FnMut(F) -> LinePoint<F>,
+    mut low: LinePoint<F>,
+    mut high: LinePoint<F>,
+    start: &LinePoint<F>,
+    c1: F,
+    c2: F,
+) -> Option<LinePoint<F>> {
+    for _ in 0..MAX_ITERATIONS {
+        if (high.step - low.step).abs() <= F::epsilon() * low.step.abs().max(F::one()) {
+            break;
+        }
+
+        let point = evaluate(interpolate(&low, &high));
+        if !point.value.is_finite()
+            || point.value > start.value + c1 * point.step * start.slope
+            || point.value >= low.value
+        {
+            high = point;
+        } else {
+            if point.slope.abs() <= -c2 * start.slope {
+                return Some(point);
+            }
+
+            if point.slope * (high.step - low.step) >= F::zero() {
+                high = low;
+            }
+            low = point;
+        }
+    }
+
+    // the best point found still decreases the value
+    Some(low).filter(|point| point.step > F::zero())
+}
+
+/// Minimizer of the cubic through two points and their slopes, falling back to
+/// bisection when it is undefined or too close to the interval's ends
+fn interpolate<F: Float>(a: &LinePoint<F>, b: &LinePoint<F>) -> F {
+    let two = F::from(2.).unwrap();
+    let three = F::from(3.).unwrap();
+    let (lower, upper) = (a.step.min(b.step), a.step.max(b.step));
+    let margin = (upper - lower) * F::from(0.1).unwrap();
+
+    let d1 = a.slope + b.slope - three * (a.value - b.value) / (a.step - b.step);
+    let discriminant = d1 * d1 - a.slope * b.slope;
+    if discriminant >= F::zero() {
+        let d2 = (b.step - a.step).signum() * discriminant.sqrt();
+        let step =
+            b.step - (b.step - a.step) * (b.slope + d2 - d1) / (b.slope - a.slope + two * d2);
+
+        if step.is_finite() && step >= lower + margin && step <= upper - margin {
+            return step;
+        }
+    }
+
+    (lower + upper) / two
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strong_wolfe_on_quadratic() {
+        // f(x) = (x - 3)^2 from x = 0 along +1
+        let objective = |step: f64| ((step - 3.).powi(2), array![2. * (step - 3.)]);
+        let direction = array![1.];
+        let start = LinePoint {
+            step: 0.,
+            value: 9.,
+            gradient: array![-6.],
+            slope: -6.,
+        };
+
+        for &initial_step in &[0.01, 1., 100.] {
+            let point = strong_wolfe(objective, &direction, &start, initial_step, 1e-4, 0.1)
+                .expect("line search failed");
+
+            assert!(point.value <= 9. + 1e-4 * point.step * -6.);
+            assert!(point.slope.abs() <= 0.6, "{:?}", point);
+        }
+
+        let ascent = LinePoint { slope: 6., ..start };
+        assert!(strong_wolfe(objective, &direction, &ascent, 1., 1e-4, 0.9).is_none());
+    }
+}
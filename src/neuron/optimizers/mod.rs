@@ -1,5 +1,14 @@
+pub use conjugate_gradient::{ConjugateGradient, ConjugateGradientFormula};
+pub use full_batch::MinimizeReport;
+pub use limited_memory_bfgs::LBFGS;
 pub use optimizer::Optimizer;
 pub use stochastic_gradient_descent::SGD;
+pub use weight_averaging::{polyak_update, Averaging, WeightAverage};
 
+mod conjugate_gradient;
+mod full_batch;
+mod limited_memory_bfgs;
+mod line_search;
 mod optimizer;
 mod stochastic_gradient_descent;
+mod weight_averaging;
@@ -1,28 +1,57 @@
+use std::path::Path;
+
 use ndarray::prelude::*;
-use ndarray_stats::QuantileExt;
 
-use crate::neuron::{losses::Loss, networks::Network};
+use crate::neuron::checkpoints::{
+    Checkpoint, CheckpointError, ModelState, OptimizerState, Progress,
+};
+use crate::neuron::datasets::{DataLoader, Dataset};
+use crate::neuron::optimizers::{Averaging, WeightAverage};
+use crate::neuron::pruning::GradualPruning;
+use crate::neuron::validation::evaluate;
+use crate::neuron::Float;
+use crate::neuron::{losses::Loss, networks::Model};
 
-pub trait Optimizer {
+pub trait Optimizer<F: Float = f32> {
     /// Get optimizer Loss
-    fn get_loss(&self) -> &Loss;
+    fn get_loss(&self) -> &Loss<F>;
 
     /// Optimize the network on batch
-    fn optimize_batch(
+    fn optimize_batch<M: Model<F>>(
+        &self,
+        network: &mut M,
+        batch_inputs: &[Array1<F>],
+        batch_expected: &[Array1<F>],
+        learning_rate: F,
+    );
+
+    /// Take a step along gradients computed outside the optimizer, in the order
+    /// of the network's layers, e.g. of a loss that isn't a function of a
+    /// prediction and an expected output
+    fn apply_gradients<M: Model<F>>(
         &self,
-        network: &mut Network,
-        batch_inputs: &[Array1<f32>],
-        batch_expected: &[Array1<f32>],
-        learning_rate: f32,
+        network: &mut M,
+        weights_gradients: &[Array2<F>],
+        biases_gradients: &[Array1<F>],
+        learning_rate: F,
     );
 
+    /// Buffers kept between steps, to checkpoint. Optimizers without state keep no
+    /// buffers.
+    fn state(&self) -> OptimizerState {
+        OptimizerState::default()
+    }
+
+    /// Restore buffers saved by `Optimizer::state`
+    fn restore_state(&mut self, _state: &OptimizerState) {}
+
     /// Optimize the network once
-    fn optimize_once(
+    fn optimize_once<M: Model<F>>(
         &self,
-        network: &mut Network,
-        input: Array1<f32>,
-        expected: Array1<f32>,
-        learning_rate: f32,
+        network: &mut M,
+        input: Array1<F>,
+        expected: Array1<F>,
+        learning_rate: F,
     ) {
         let batch_inputs = vec![input];
         let batch_expected = vec![expected];
@@ -30,79 +59,204 @@ pub trait Optimizer {
         self.optimize_batch(network, &batch_inputs, &batch_expected, learning_rate);
     }
 
-    /// Train the network
-    fn train(
+    /// Train the network, printing its score on the train and validation sets
+    /// after each epoch
+    fn train<M: Model<F>, D: Dataset<F>, V: Dataset<F>>(
         &self,
-        network: &mut Network,
-        train: &(Vec<Array1<f32>>, Vec<Array1<f32>>),
-        test: &(Vec<Array1<f32>>, Vec<Array1<f32>>),
-        learning_rate: f32,
-        batch_size: usize,
+        network: &mut M,
+        train: &DataLoader<D, F>,
+        validation: &V,
+        learning_rate: F,
         epochs: usize,
     ) {
-        let (train_x, train_y) = train;
-        assert_eq!(train_x.len(), train_y.len(), "X and Y lengths must be equal");
-
-        let batches = train_x.len() / batch_size;
+        let batches = train.len();
         for e in 0..epochs {
-            // split data into batches
-            for b in 0..batches {
+            for (b, (batch_inputs, batch_expected)) in train.batches().enumerate() {
                 print!(
                     "batch {}/{} ({:.2}%)                  \r",
                     b,
                     batches,
                     (b as f32 / batches as f32) * 100.
                 );
-                let batch_inputs = &train_x[b..(b + batch_size)];
-                let batch_expected = &train_y[b..(b + batch_size)];
 
-                self.optimize_batch(network, batch_inputs, batch_expected, learning_rate);
+                self.optimize_batch(network, &batch_inputs, &batch_expected, learning_rate);
             }
 
-            print_network_score(&network, e, train, test, self.get_loss());
+            print_network_score(network, e, train.dataset(), validation, self.get_loss());
         }
     }
-}
 
-fn print_network_score(
-    network: &Network,
-    epoch: usize,
-    train: &(Vec<Array1<f32>>, Vec<Array1<f32>>),
-    test: &(Vec<Array1<f32>>, Vec<Array1<f32>>),
-    loss: &Loss,
-) {
-    let (train_x, train_y) = train;
-    let (test_x, test_y) = test;
-    let train_samples = train_x.len();
-    let test_samples = test_x.len();
-    let mut train_loss = 0.;
-    let mut train_mistakes = 0.;
-    for (input, expected) in train_x.iter().zip(train_y.iter()) {
-        let prediction = network.predict(input);
-        if prediction.argmax().unwrap() != expected.argmax().unwrap() {
-            train_mistakes += 1.;
+    /// Train the network as `Optimizer::train` does, saving a checkpoint to `path`
+    /// every `every` batches and after each epoch
+    ///
+    /// If a checkpoint exists at `path` the network, optimizer state, learning rate
+    /// and position are restored from it and training resumes where it stopped.
+    /// Each epoch is shuffled with a seed derived from `seed` and the epoch, so a
+    /// resumed run sees the same batches as an uninterrupted one.
+    #[allow(clippy::too_many_arguments)]
+    fn train_checkpointed<M: Model<F>, D: Dataset<F>, V: Dataset<F>, P: AsRef<Path>>(
+        &mut self,
+        network: &mut M,
+        train: &DataLoader<D, F>,
+        validation: &V,
+        learning_rate: F,
+        epochs: usize,
+        seed: u64,
+        path: P,
+        every: usize,
+    ) -> Result<(), CheckpointError> {
+        assert!(every > 0, "checkpoint interval must be positive");
+
+        let path = path.as_ref();
+        let mut progress = if path.exists() {
+            let checkpoint = Checkpoint::load(path)?;
+            let model = checkpoint
+                .models
+                .first()
+                .ok_or_else(|| CheckpointError::Mismatch("checkpoint has no models".to_string()))?;
+            model.restore(network)?;
+            self.restore_state(&checkpoint.optimizer);
+
+            checkpoint.progress
+        } else {
+            Progress {
+                epoch: 0,
+                step: 0,
+                seed,
+                learning_rate: learning_rate.to_f64(),
+                mutation_rate: None,
+            }
+        };
+        let learning_rate = progress.learning_rate.ok_or_else(|| {
+            CheckpointError::Mismatch("checkpoint has no learning rate".to_string())
+        })?;
+        let learning_rate = F::from(learning_rate).unwrap();
+
+        let save = |network: &M, progress: Progress| {
+            let checkpoint = Checkpoint {
+                models: vec![ModelState::capture(network)],
+                optimizer: self.state(),
+                progress,
+            };
+
+            checkpoint.save(path)
+        };
+
+        while progress.epoch < epochs {
+            let epoch_seed = progress.seed.wrapping_add(progress.epoch as u64);
+            let batches = train.seeded_batches(epoch_seed).skip(progress.step);
+            for (batch_inputs, batch_expected) in batches {
+                self.optimize_batch(network, &batch_inputs, &batch_expected, learning_rate);
+
+                progress.step += 1;
+                if progress.step % every == 0 {
+                    save(network, progress)?;
+                }
+            }
+
+            print_network_score(
+                network,
+                progress.epoch,
+                train.dataset(),
+                validation,
+                self.get_loss(),
+            );
+
+            progress.epoch += 1;
+            progress.step = 0;
+            save(network, progress)?;
         }
 
-        train_loss += loss.loss(&prediction, expected).sum();
+        Ok(())
     }
 
-    let mut test_loss = 0.;
-    let mut test_mistakes = 0.;
-    for (input, expected) in test_x.iter().zip(test_y.iter()) {
-        let prediction = network.predict(input);
-        if prediction.argmax().unwrap() != expected.argmax().unwrap() {
-            test_mistakes += 1.;
+    /// Train the network while averaging its parameters, printing the averaged
+    /// network's score after each epoch, then replace the network with the
+    /// averaged one
+    ///
+    /// Until the average is first updated, e.g. before a stochastic average's
+    /// start epoch, the network's own score is printed.
+    fn train_averaged<M: Model<F>, D: Dataset<F>, V: Dataset<F>>(
+        &self,
+        network: &mut M,
+        train: &DataLoader<D, F>,
+        validation: &V,
+        learning_rate: F,
+        epochs: usize,
+        average: &mut WeightAverage<M, F>,
+    ) {
+        for e in 0..epochs {
+            for (batch_inputs, batch_expected) in train.batches() {
+                self.optimize_batch(network, &batch_inputs, &batch_expected, learning_rate);
+
+                if let Averaging::Exponential { .. } = average.averaging() {
+                    average.update(network);
+                }
+            }
+
+            if let Averaging::Stochastic { start_epoch } = average.averaging() {
+                if e >= start_epoch {
+                    average.update(network);
+                }
+            }
+
+            // the average has no parameters before its first update
+            let scored = if average.updates() > 0 {
+                average.model()
+            } else {
+                &*network
+            };
+            print_network_score(scored, e, train.dataset(), validation, self.get_loss());
         }
 
-        test_loss += loss.loss(&prediction, expected).sum();
+        if average.updates() > 0 {
+            *network = average.model().clone();
+        }
+    }
+
+    /// Train the network while pruning it on the schedule, counting steps in
+    /// batches, printing its score after each epoch
+    ///
+    /// The final sparsity is reached if the training runs for at least the
+    /// schedule's end step.
+    fn train_pruned<M: Model<F>, D: Dataset<F>, V: Dataset<F>>(
+        &self,
+        network: &mut M,
+        train: &DataLoader<D, F>,
+        validation: &V,
+        learning_rate: F,
+        epochs: usize,
+        pruning: &GradualPruning,
+    ) {
+        let mut step = 0;
+        for e in 0..epochs {
+            for (batch_inputs, batch_expected) in train.batches() {
+                pruning.update(network, step);
+                self.optimize_batch(network, &batch_inputs, &batch_expected, learning_rate);
+                step += 1;
+            }
+
+            print_network_score(network, e, train.dataset(), validation, self.get_loss());
+        }
     }
+}
+
+fn print_network_score<F: Float, M: Model<F>, D: Dataset<F>, V: Dataset<F>>(
+    network: &M,
+    epoch: usize,
+    train: &D,
+    validation: &V,
+    loss: &Loss<F>,
+) {
+    let train_score = evaluate(network, train, loss);
+    let validation_score = evaluate(network, validation, loss);
 
     println!(
-        "epoch {} | train loss: {:.4} accuracy: {:.2}% | test loss: {:.4} accuracy: {:.2}%",
+        "epoch {} | train loss: {:.4} accuracy: {:.2}% | validation loss: {:.4} accuracy: {:.2}%",
         epoch,
-        train_loss / (train_samples as f32),
-        (1. - (train_mistakes / (train_samples as f32))) * 100.,
-        test_loss / (test_samples as f32),
-        (1. - (test_mistakes / (test_samples as f32))) * 100.,
+        train_score.loss,
+        train_score.accuracy * F::from(100.).unwrap(),
+        validation_score.loss,
+        validation_score.accuracy * F::from(100.).unwrap(),
     );
 }
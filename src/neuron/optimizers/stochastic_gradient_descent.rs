@@ -1,128 +1,56 @@
+use std::thread;
+
 use ndarray::prelude::*;
 
 use crate::neuron::losses::Loss;
-use crate::neuron::networks::Network;
+use crate::neuron::networks::Model;
 use crate::neuron::optimizers::Optimizer;
+use crate::neuron::Float;
+
+type Gradients<F> = (Vec<Array2<F>>, Vec<Array1<F>>);
 
 #[derive(Clone)]
-pub struct SGD {
-    loss: Loss,
+pub struct SGD<F: Float = f32> {
+    loss: Loss<F>,
+    threads: usize,
 }
 
-impl SGD {
-    pub fn new(loss: Loss) -> Self {
-        Self { loss }
-    }
-
-    fn chain_rule_weights(
-        &self,
-        dl_da: &Array1<f32>,
-        da_dt: &Array1<f32>,
-        dt_dw: &Array1<f32>,
-    ) -> Array2<f32> {
-        let layer_outputs = da_dt.len();
-        let layer_inputs = dt_dw.len();
-
-        // gradient of the loss with respect to the transfer of the current layer
-        // transposed for matrix multiplication
-        let dl_dt = Array2::from_shape_vec((layer_outputs, 1), (dl_da * da_dt).to_vec()).unwrap();
-
-        // convert gradient of the transitions with respect to the weigts into a 2d
-        // array with one row
-        let dt_dw = Array2::from_shape_vec((1, layer_inputs), dt_dw.to_vec()).unwrap();
-
-        // matrix with dimensions layer_outputs X layer_inputs
-        let layer_weights_gradients = dl_dt.dot(&dt_dw);
+impl<F: Float> SGD<F> {
+    /// Create an `SGD` that computes batch gradients using all available cores
+    pub fn new(loss: Loss<F>) -> Self {
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
 
-        layer_weights_gradients
+        Self::with_threads(loss, threads)
     }
 
-    fn chain_rule_biases(&self, dl_da: &Array1<f32>, da_dt: &Array1<f32>) -> Array1<f32> {
-        // derivatives of the transfers with respect to the biases (dt_db) is 1
-        // so da_db = da_dt * dt_db = da_dt * 1 = da_dt
-        let da_db = da_dt;
+    /// Create an `SGD` that splits each batch between `threads` threads
+    pub fn with_threads(loss: Loss<F>, threads: usize) -> Self {
+        assert!(threads > 0, "threads must be positive");
 
-        // chain rule - derivatives of the loss with respect to the biases
-        let layer_biases_gradient = dl_da * da_db;
-
-        layer_biases_gradient
+        Self { loss, threads }
     }
 
-    fn chain_rule_previous_activations(
+    fn get_gradients<M: Model<F>>(
         &self,
-        dl_da: &Array1<f32>,
-        da_dt: &Array1<f32>,
-        dt_dap: &Array2<f32>,
-    ) -> Array1<f32> {
-        // since each node in the previous can affect multiple nodes in this layer
-        // the derivatives are summed over all nodes affected in this layer (Axis(0))
-        let dl_da_sum = dl_da.sum();
-        let da_dt_sum = da_dt.sum();
-        let dt_dap_sum = dt_dap.sum_axis(Axis(0));
-
-        // chain rule - derivatives of loss with respect to previous layer's
-        // activations
-        let previous_layer_activations_gradient = dl_da_sum * da_dt_sum * dt_dap_sum;
-
-        previous_layer_activations_gradient
-    }
+        network: &M,
+        input: &Array1<F>,
+        expected: &Array1<F>,
+    ) -> Gradients<F> {
+        let (prediction, cache) = network.predict_cached(input);
 
-    fn get_gradients(
-        &self,
-        network: &mut Network,
-        input: &Array1<f32>,
-        expected: &Array1<f32>,
-    ) -> (Vec<Array2<f32>>, Vec<Array1<f32>>) {
-        let mut network_weights_gradients = vec![];
-        let mut network_biases_gradients = vec![];
-
-        let prediction = network.predict_cached(input);
-
-        // derivatives of the loss with respect to the last layers activation
-        let mut dl_da = Box::new(self.loss.derivative(&prediction, expected));
-
-        for layer in network.get_layers().iter().rev() {
-            // derivatives of the activations with respect to the transfers
-            // NOTE: unwrap is safe since we called `predict_cached`
-            let da_dt = layer.apply_derivation(layer.get_transfer().unwrap());
-
-            // derivatives of the transfers with respect to the weights - these are
-            // the activations of the previous layer, which is also the input to the
-            // current layer
-            // NOTE: unwrap is safe since we called `predict_cached`
-            let dt_dw = layer.get_input().unwrap();
-
-            // derivatives of the transfers with respect to the previous layer's
-            // activations - these are all the weights from each node in the
-            // previous layer
-            let dt_dap = layer.get_weights();
-
-            // derivatives of the losses with respect to the biases
-            let dl_db = self.chain_rule_biases(&dl_da, &da_dt);
-            network_biases_gradients.insert(0, dl_db);
-
-            // derivatives of the losses with respect to the weights
-            let dl_dw = self.chain_rule_weights(&dl_da, &da_dt, &dt_dw);
-            network_weights_gradients.insert(0, dl_dw);
-
-            // derivatives of the losses with respect to the previous layers activations
-            let dl_dap = self.chain_rule_previous_activations(&dl_da, &da_dt, &dt_dap);
-
-            // BACK PROPAGATION: set the loss with respect to the current layer's
-            // activations as the the loss with respect to the *previous* layer's
-            // activations, propagating the loss to the previous layers
-            dl_da = Box::new(dl_dap);
-        }
+        // derivatives of the loss with respect to the prediction
+        let dl_dprediction = self.loss.derivative(&prediction, expected);
+        let gradients = network.backward(&cache, &dl_dprediction);
 
-        (network_weights_gradients, network_biases_gradients)
+        (gradients.weights, gradients.biases)
     }
 
-    fn get_batch_gradients(
+    fn get_batch_gradients<M: Model<F>>(
         &self,
-        network: &mut Network,
-        batch_inputs: &[Array1<f32>],
-        batch_expected: &[Array1<f32>],
-    ) -> (Vec<Array2<f32>>, Vec<Array1<f32>>) {
+        network: &M,
+        batch_inputs: &[Array1<F>],
+        batch_expected: &[Array1<F>],
+    ) -> Gradients<F> {
         assert_eq!(
             batch_inputs.len(),
             batch_expected.len(),
@@ -134,11 +62,49 @@ impl SGD {
             return (vec![], vec![]);
         }
 
-        // calculate batch gradients
-        let batch_length = batch_inputs.len() as f32;
-        batch_inputs
+        // split the batch into a chunk per thread, the layer caches are created per
+        // sample so the threads can share the network
+        let batch_length = F::from(batch_inputs.len()).unwrap();
+        let chunk_size = batch_inputs.len().div_ceil(self.threads);
+        if chunk_size == batch_inputs.len() {
+            return self.get_chunk_gradients(network, batch_inputs, batch_expected, batch_length);
+        }
+
+        thread::scope(|scope| {
+            let handles: Vec<_> = batch_inputs
+                .chunks(chunk_size)
+                .zip(batch_expected.chunks(chunk_size))
+                .map(|(chunk_inputs, chunk_expected)| {
+                    scope.spawn(move || {
+                        self.get_chunk_gradients(
+                            network,
+                            chunk_inputs,
+                            chunk_expected,
+                            batch_length,
+                        )
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("gradient thread panicked"))
+                .reduce(add_gradients)
+                .unwrap()
+        })
+    }
+
+    /// Sum the gradients of the chunk's samples, each divided by the batch length
+    fn get_chunk_gradients<M: Model<F>>(
+        &self,
+        network: &M,
+        chunk_inputs: &[Array1<F>],
+        chunk_expected: &[Array1<F>],
+        batch_length: F,
+    ) -> Gradients<F> {
+        chunk_inputs
             .iter()
-            .zip(batch_expected.iter())
+            .zip(chunk_expected.iter())
             .map(|(input, expected)| {
                 let (weight_gradients, bias_gradients) =
                     self.get_gradients(network, input, expected);
@@ -154,64 +120,77 @@ impl SGD {
                         .collect(),
                 )
             })
-            .reduce(
-                |(total_weights_gradients, total_biases_gradients): (
-                    Vec<Array2<f32>>,
-                    Vec<Array1<f32>>,
-                ),
-                 (weights_gradients, biases_gradients)| {
-                    (
-                        total_weights_gradients
-                            .iter()
-                            .zip(weights_gradients.iter())
-                            .map(|(total_layer_weights_gradients, layer_weights_gradients)| {
-                                total_layer_weights_gradients + layer_weights_gradients
-                            })
-                            .collect(),
-                        total_biases_gradients
-                            .iter()
-                            .zip(biases_gradients.iter())
-                            .map(|(total_layer_biases_gradients, layer_biases_gradients)| {
-                                total_layer_biases_gradients + layer_biases_gradients
-                            })
-                            .collect(),
-                    )
-                },
-            )
+            .reduce(add_gradients)
             .unwrap()
     }
 }
 
-impl Optimizer for SGD {
-    fn get_loss(&self) -> &Loss {
+fn add_gradients<F: Float>(
+    (total_weights_gradients, total_biases_gradients): Gradients<F>,
+    (weights_gradients, biases_gradients): Gradients<F>,
+) -> Gradients<F> {
+    (
+        total_weights_gradients
+            .iter()
+            .zip(weights_gradients.iter())
+            .map(|(total_layer_weights_gradients, layer_weights_gradients)| {
+                total_layer_weights_gradients + layer_weights_gradients
+            })
+            .collect(),
+        total_biases_gradients
+            .iter()
+            .zip(biases_gradients.iter())
+            .map(|(total_layer_biases_gradients, layer_biases_gradients)| {
+                total_layer_biases_gradients + layer_biases_gradients
+            })
+            .collect(),
+    )
+}
+
+impl<F: Float> Optimizer<F> for SGD<F> {
+    fn get_loss(&self) -> &Loss<F> {
         &self.loss
     }
 
-    fn optimize_batch(
+    fn optimize_batch<M: Model<F>>(
         &self,
-        network: &mut Network,
-        batch_inputs: &[Array1<f32>],
-        batch_expected: &[Array1<f32>],
-        learning_rate: f32,
+        network: &mut M,
+        batch_inputs: &[Array1<F>],
+        batch_expected: &[Array1<F>],
+        learning_rate: F,
     ) {
         let (weights_gradients, biases_gradients) =
             self.get_batch_gradients(network, batch_inputs, batch_expected);
 
-        for (weights, gradients) in network.get_weights_mut().iter_mut().zip(weights_gradients) {
-            **weights = weights.clone() - gradients * learning_rate;
-        }
+        self.apply_gradients(
+            network,
+            &weights_gradients,
+            &biases_gradients,
+            learning_rate,
+        );
+    }
 
-        for (biases, gradients) in network.get_biases_mut().iter_mut().zip(biases_gradients) {
-            **biases = biases.clone() - gradients * learning_rate;
+    fn apply_gradients<M: Model<F>>(
+        &self,
+        network: &mut M,
+        weights_gradients: &[Array2<F>],
+        biases_gradients: &[Array1<F>],
+        learning_rate: F,
+    ) {
+        let layers = network.layers_mut().into_iter();
+        for ((layer, weights_gradients), biases_gradients) in
+            layers.zip(weights_gradients).zip(biases_gradients)
+        {
+            layer.update(weights_gradients, biases_gradients, learning_rate);
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::neuron::activations::{sigmoid, softplus, leaky_relu, relu};
+    use crate::neuron::activations::{leaky_relu, relu, sigmoid, softplus};
     use crate::neuron::layers::Layer;
-    use crate::neuron::losses::{sse, mse};
+    use crate::neuron::losses::{mse, sse};
     use crate::neuron::networks::Network;
     use crate::neuron::transfers::dense;
 
@@ -239,8 +218,8 @@ mod tests {
         for e in 0..1_000 {
             let mut cost = 0.;
             for (input, expected) in batch_inputs.iter().zip(batch_expected.iter()) {
-                let prediction = network.predict(&input);
-                cost += optimizer.get_loss().loss(&prediction, &expected).sum();
+                let prediction = network.predict(input);
+                cost += optimizer.get_loss().loss(&prediction, expected).sum();
             }
 
             if e & 100 == 0 {
@@ -252,13 +231,9 @@ mod tests {
 
         let mut total_cost = 0.;
         for (input, expected) in batch_inputs.iter().zip(batch_expected.iter()) {
-            let prediction = network.predict(&input);
-            let cost = optimizer.get_loss().loss(&prediction, &expected).sum();
-            eprintln!(
-                "prediction: {} expected: {}",
-                prediction.to_string(),
-                expected.to_string()
-            );
+            let prediction = network.predict(input);
+            let cost = optimizer.get_loss().loss(&prediction, expected).sum();
+            eprintln!("prediction: {} expected: {}", prediction, expected);
             total_cost += cost / 100.;
         }
 
@@ -269,6 +244,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sgd_multi_threaded_batch_gradients() {
+        let network = Network::new(vec![
+            Layer::new(4, 2, dense(), sigmoid()),
+            Layer::new(3, 4, dense(), relu()),
+        ]);
+
+        let batch_inputs: Vec<Array1<f32>> = (0..13).map(|i| array![i as f32, 1.]).collect();
+        let batch_expected: Vec<Array1<f32>> = (0..13).map(|i| array![i as f32, 0., 1.]).collect();
+
+        let (single_weights, single_biases) = SGD::with_threads(mse(), 1).get_batch_gradients(
+            &network,
+            &batch_inputs,
+            &batch_expected,
+        );
+        let (multi_weights, multi_biases) = SGD::with_threads(mse(), 4).get_batch_gradients(
+            &network,
+            &batch_inputs,
+            &batch_expected,
+        );
+
+        for (single, multi) in single_weights.iter().zip(multi_weights.iter()) {
+            assert!((single - multi).iter().all(|d| d.abs() < 1e-6));
+        }
+        for (single, multi) in single_biases.iter().zip(multi_biases.iter()) {
+            assert!((single - multi).iter().all(|d| d.abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_sgd_f64_gradients_match_finite_differences() {
+        let network: Network<f64> = Network::new(vec![
+            Layer::new(4, 3, dense(), sigmoid()),
+            Layer::new(4, 4, dense(), softplus()),
+            Layer::new(2, 4, dense(), sigmoid()),
+        ]);
+        let optimizer = SGD::new(sse());
+        let input = array![0.5, -1., 2.];
+        let expected = array![1., 0.];
+
+        let (weights_gradients, biases_gradients) =
+            optimizer.get_gradients(&network, &input, &expected);
+
+        let epsilon = 1e-6;
+        let loss = |network: &Network<f64>| {
+            let prediction = network.predict(&input);
+            optimizer.get_loss().loss(&prediction, &expected).sum()
+        };
+        let assert_close = |numeric: f64, analytic: f64| {
+            assert!(
+                (numeric - analytic).abs() < 1e-8,
+                "numeric gradient {} != analytic gradient {}",
+                numeric,
+                analytic
+            );
+        };
+
+        for (l, layer_gradients) in weights_gradients.iter().enumerate() {
+            for (index, &gradient) in layer_gradients.indexed_iter() {
+                let mut plus = network.clone();
+                plus.get_weights_mut()[l][index] += epsilon;
+                let mut minus = network.clone();
+                minus.get_weights_mut()[l][index] -= epsilon;
+
+                assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+            }
+        }
+
+        for (l, layer_gradients) in biases_gradients.iter().enumerate() {
+            for (index, &gradient) in layer_gradients.indexed_iter() {
+                let mut plus = network.clone();
+                plus.get_biases_mut()[l][index] += epsilon;
+                let mut minus = network.clone();
+                minus.get_biases_mut()[l][index] -= epsilon;
+
+                assert_close((loss(&plus) - loss(&minus)) / (2. * epsilon), gradient);
+            }
+        }
+    }
+
     #[test]
     fn test_sgd_optimize_once_convergence() {
         let mut network = Network::new(vec![
@@ -292,11 +347,7 @@ mod tests {
         let prediction = network.predict(&input);
         let cost = optimizer.get_loss().loss(&prediction, &expected).sum();
 
-        eprintln!(
-            "prediction: {} expected: {}",
-            prediction.to_string(),
-            expected.to_string()
-        );
+        eprintln!("prediction: {} expected: {}", prediction, expected);
 
         assert!(
             cost < 0.0001,
@@ -0,0 +1,153 @@
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// How a `WeightAverage` weighs the parameters it is updated with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Averaging<F: Float = f32> {
+    /// Exponential moving average, keeping `decay` of the average on each update.
+    /// Updated after every batch when training.
+    Exponential { decay: F },
+    /// Stochastic weight averaging, the mean of all updates. Updated at the end of
+    /// each epoch from `start_epoch` on when training.
+    Stochastic { start_epoch: usize },
+}
+
+/// A running average of a model's parameters, kept in a copy of the model
+#[derive(Debug, Clone)]
+pub struct WeightAverage<M, F: Float = f32> {
+    averaging: Averaging<F>,
+    average: M,
+    updates: usize,
+}
+
+impl<F: Float, M: Model<F>> WeightAverage<M, F> {
+    /// Start averaging from a copy of the model
+    pub fn new(model: &M, averaging: Averaging<F>) -> Self {
+        if let Averaging::Exponential { decay } = averaging {
+            assert!(
+                decay >= F::zero() && decay <= F::one(),
+                "decay must be between 0 and 1"
+            );
+        }
+
+        Self {
+            averaging,
+            average: model.clone(),
+            updates: 0,
+        }
+    }
+
+    pub fn averaging(&self) -> Averaging<F> {
+        self.averaging
+    }
+
+    /// Amount of updates so far
+    pub fn updates(&self) -> usize {
+        self.updates
+    }
+
+    /// The model with the averaged parameters
+    pub fn model(&self) -> &M {
+        &self.average
+    }
+
+    pub fn into_model(self) -> M {
+        self.average
+    }
+
+    /// Add the model's current parameters to the average
+    ///
+    /// The first stochastic update replaces the starting copy, so the average
+    /// only covers the updates.
+    pub fn update(&mut self, model: &M) {
+        let tau = match self.averaging {
+            Averaging::Exponential { decay } => F::one() - decay,
+            Averaging::Stochastic { .. } => F::one() / F::from(self.updates + 1).unwrap(),
+        };
+
+        polyak_update(&mut self.average, model, tau);
+        self.updates += 1;
+    }
+}
+
+/// Move the target's parameters `tau` of the way towards the source's, as in
+/// `target = (1 - tau) * target + tau * source`
+///
+/// Used to slowly track a trained network, e.g. by the target network of a
+/// reinforcement learning agent.
+pub fn polyak_update<F: Float, M: Model<F>>(target: &mut M, source: &M, tau: F) {
+    let mut parameters = target.parameters();
+    parameters *= F::one() - tau;
+    parameters.scaled_add(tau, &source.parameters());
+
+    target.set_parameters(&parameters);
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+
+    use crate::neuron::activations::{linear, sigmoid};
+    use crate::neuron::datasets::{DataLoader, InMemoryDataset};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    fn network(parameters: Array1<f32>) -> Network {
+        let mut network = Network::new(vec![Layer::new(1, 2, dense(), linear())]);
+        network.set_parameters(&parameters);
+
+        network
+    }
+
+    #[test]
+    fn test_polyak_update() {
+        let mut target = network(array![0., 0., 0.]);
+        polyak_update(&mut target, &network(array![1., 2., -4.]), 0.25);
+        assert_eq!(target.parameters(), array![0.25, 0.5, -1.]);
+    }
+
+    #[test]
+    fn test_weight_averages() {
+        let snapshots = vec![array![1., 2., 3.], array![3., 0., 3.], array![2., 1., 0.]];
+
+        let mut swa = WeightAverage::new(
+            &network(array![100., 100., 100.]),
+            Averaging::Stochastic { start_epoch: 0 },
+        );
+        let mut ema = WeightAverage::new(
+            &network(array![0., 0., 0.]),
+            Averaging::Exponential { decay: 0.5 },
+        );
+        for parameters in snapshots {
+            swa.update(&network(parameters.clone()));
+            ema.update(&network(parameters));
+        }
+
+        assert_eq!(swa.updates(), 3);
+        assert!((swa.model().parameters() - array![2., 1., 2.])
+            .iter()
+            .all(|d| d.abs() < 1e-6));
+        assert_eq!(ema.model().parameters(), array![1.875, 0.75, 1.125]);
+    }
+
+    #[test]
+    fn test_train_averaged() {
+        let inputs: Vec<Array1<f32>> = (0..20).map(|i| array![i as f32 / 20., 1.]).collect();
+        let expected: Vec<Array1<f32>> = inputs.iter().map(|x| array![x[0] * 0.5]).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+        let loader = DataLoader::new(dataset.clone(), 5, true, false);
+
+        let mut network = Network::new(vec![Layer::new(1, 2, dense(), sigmoid())]);
+        let mut average = WeightAverage::new(&network, Averaging::Stochastic { start_epoch: 3 });
+        SGD::new(mse()).train_averaged(&mut network, &loader, &dataset, 0.5, 5, &mut average);
+
+        // averaged over the last two epochs, and swapped into the network
+        assert_eq!(average.updates(), 2);
+        assert_eq!(network.parameters(), average.model().parameters());
+    }
+}
@@ -0,0 +1,10 @@
+use ndarray::ArrayView1;
+
+/// Get the sorted unique non-NaN values
+pub fn categories(values: ArrayView1<f32>) -> Vec<f32> {
+    let mut categories: Vec<f32> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+    categories.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    categories.dedup();
+
+    categories
+}
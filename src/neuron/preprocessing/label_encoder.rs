@@ -0,0 +1,100 @@
+use ndarray::{Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::categories::categories;
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Replaces each categorical feature with the index of its category
+///
+/// Labels that were not seen during `fit` are encoded as `LabelEncoder::UNKNOWN`,
+/// which `inverse_transform` decodes to NaN.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LabelEncoder {
+    classes: Vec<Vec<f32>>,
+}
+
+impl LabelEncoder {
+    /// Encoding of labels that were not seen during `fit`
+    pub const UNKNOWN: f32 = -1.;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Classes of each feature, sorted
+    pub fn classes(&self) -> &[Vec<f32>] {
+        &self.classes
+    }
+
+    pub fn inverse_transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.classes.len());
+
+        let mut decoded = data.clone();
+        for (mut column, classes) in decoded.axis_iter_mut(Axis(1)).zip(self.classes.iter()) {
+            column.map_inplace(|index| {
+                *index = if *index == Self::UNKNOWN {
+                    f32::NAN
+                } else {
+                    classes[*index as usize]
+                }
+            });
+        }
+
+        decoded
+    }
+}
+
+impl Transformer for LabelEncoder {
+    fn fit(&mut self, data: &Array2<f32>) {
+        self.classes = data.axis_iter(Axis(1)).map(categories).collect();
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.classes.len());
+
+        let mut encoded = data.clone();
+        for (mut column, classes) in encoded.axis_iter_mut(Axis(1)).zip(self.classes.iter()) {
+            column.map_inplace(|value| {
+                *value = match classes.iter().position(|c| c == value) {
+                    Some(class) => class as f32,
+                    None => Self::UNKNOWN,
+                };
+            });
+        }
+
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_label_encoder_round_trip() {
+        let data = arr2(&[[3., -1.], [1., 7.], [3., 7.]]);
+
+        let mut encoder = LabelEncoder::new();
+        let encoded = encoder.fit_transform(&data);
+
+        assert_eq!(encoder.classes(), &[vec![1., 3.], vec![-1., 7.]]);
+        assert_eq!(encoded, arr2(&[[1., 0.], [0., 1.], [1., 1.]]));
+        assert_eq!(encoder.inverse_transform(&encoded), data);
+    }
+
+    #[test]
+    fn test_label_encoder_unknown_label() {
+        let mut encoder = LabelEncoder::new();
+        encoder.fit(&arr2(&[[0.], [1.]]));
+
+        let encoded = encoder.transform(&arr2(&[[1.], [5.]]));
+        assert_eq!(encoded, arr2(&[[1.], [LabelEncoder::UNKNOWN]]));
+
+        let decoded = encoder.inverse_transform(&encoded);
+        assert_eq!(decoded[(0, 0)], 1.);
+        assert!(decoded[(1, 0)].is_nan());
+    }
+}
@@ -0,0 +1,79 @@
+use ndarray::{Array1, Array2, Axis, Zip};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Scales each feature to be between `min` and `max`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinMaxScaler {
+    min: f32,
+    max: f32,
+    data_min: Array1<f32>,
+    data_range: Array1<f32>,
+}
+
+impl MinMaxScaler {
+    pub fn new(min: f32, max: f32) -> Self {
+        assert!(min < max, "min must be smaller than max");
+
+        Self {
+            min,
+            max,
+            data_min: Array1::zeros(0),
+            data_range: Array1::zeros(0),
+        }
+    }
+
+    pub fn inverse_transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.data_min.len());
+
+        (data - self.min) / (self.max - self.min) * &self.data_range + &self.data_min
+    }
+}
+
+impl Default for MinMaxScaler {
+    fn default() -> Self {
+        Self::new(0., 1.)
+    }
+}
+
+impl Transformer for MinMaxScaler {
+    fn fit(&mut self, data: &Array2<f32>) {
+        assert!(data.nrows() > 0, "can't fit on empty data");
+
+        self.data_min = data.fold_axis(Axis(0), f32::INFINITY, |&a, &b| a.min(b));
+        let data_max = data.fold_axis(Axis(0), f32::NEG_INFINITY, |&a, &b| a.max(b));
+
+        // constant features are left unscaled
+        self.data_range = Zip::from(&data_max)
+            .and(&self.data_min)
+            .apply_collect(|&max, &min| if max == min { 1. } else { max - min });
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.data_min.len());
+
+        (data - &self.data_min) / &self.data_range * (self.max - self.min) + self.min
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use crate::neuron::preprocessing::transformer::assert_close;
+
+    use super::*;
+
+    #[test]
+    fn test_min_max_scaler_round_trip() {
+        let data = arr2(&[[1., 5.], [3., 5.], [-3., 5.]]);
+
+        let mut scaler = MinMaxScaler::new(-1., 1.);
+        let scaled = scaler.fit_transform(&data);
+
+        assert_close(&scaled, &arr2(&[[1. / 3., -1.], [1., -1.], [-1., -1.]]));
+        assert_close(&scaler.inverse_transform(&scaled), &data);
+    }
+}
@@ -0,0 +1,19 @@
+pub use label_encoder::LabelEncoder;
+pub use min_max_scaler::MinMaxScaler;
+pub use one_hot_encoder::OneHotEncoder;
+pub use polynomial_features::PolynomialFeatures;
+pub use robust_scaler::RobustScaler;
+pub use simple_imputer::{ImputeStrategy, SimpleImputer};
+pub use standard_scaler::StandardScaler;
+pub use transformer::Transformer;
+
+mod categories;
+mod label_encoder;
+mod min_max_scaler;
+mod one_hot_encoder;
+mod polynomial_features;
+mod quantile;
+mod robust_scaler;
+mod simple_imputer;
+mod standard_scaler;
+mod transformer;
@@ -0,0 +1,109 @@
+use ndarray::{Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::categories::categories;
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Replaces each categorical feature with one feature per category, set to 1 for
+/// the sample's category and 0 for the rest
+///
+/// Categories that were not seen during `fit` are encoded as all zeros.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OneHotEncoder {
+    categories: Vec<Vec<f32>>,
+}
+
+impl OneHotEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Categories of each feature, sorted
+    pub fn categories(&self) -> &[Vec<f32>] {
+        &self.categories
+    }
+
+    /// Decode each block of one-hot features to the category with the highest value
+    pub fn inverse_transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        let encoded_features = self.categories.iter().map(|c| c.len()).sum();
+        assert_fitted_features(data, encoded_features);
+
+        let mut decoded = Array2::zeros((data.nrows(), self.categories.len()));
+        for (sample, mut decoded_sample) in data.outer_iter().zip(decoded.outer_iter_mut()) {
+            let mut offset = 0;
+            for (feature, categories) in self.categories.iter().enumerate() {
+                let block = sample.slice(ndarray::s![offset..offset + categories.len()]);
+                let (best, _) = block.iter().enumerate().fold(
+                    (0, f32::NEG_INFINITY),
+                    |(best, best_value), (i, &value)| {
+                        if value > best_value {
+                            (i, value)
+                        } else {
+                            (best, best_value)
+                        }
+                    },
+                );
+
+                decoded_sample[feature] = categories[best];
+                offset += categories.len();
+            }
+        }
+
+        decoded
+    }
+}
+
+impl Transformer for OneHotEncoder {
+    fn fit(&mut self, data: &Array2<f32>) {
+        self.categories = data.axis_iter(Axis(1)).map(categories).collect();
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.categories.len());
+
+        let encoded_features = self.categories.iter().map(|c| c.len()).sum();
+        let mut encoded = Array2::zeros((data.nrows(), encoded_features));
+        for (sample, mut encoded_sample) in data.outer_iter().zip(encoded.outer_iter_mut()) {
+            let mut offset = 0;
+            for (value, categories) in sample.iter().zip(self.categories.iter()) {
+                if let Some(category) = categories.iter().position(|c| c == value) {
+                    encoded_sample[offset + category] = 1.;
+                }
+
+                offset += categories.len();
+            }
+        }
+
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_one_hot_encoder() {
+        let data = arr2(&[[2., 10.], [0., 20.], [2., 20.]]);
+
+        let mut encoder = OneHotEncoder::new();
+        let encoded = encoder.fit_transform(&data);
+
+        assert_eq!(
+            encoded,
+            arr2(&[[0., 1., 1., 0.], [1., 0., 0., 1.], [0., 1., 0., 1.]])
+        );
+        assert_eq!(encoder.inverse_transform(&encoded), data);
+    }
+
+    #[test]
+    fn test_one_hot_encoder_unknown_category() {
+        let mut encoder = OneHotEncoder::new();
+        encoder.fit(&arr2(&[[0.], [1.]]));
+
+        assert_eq!(encoder.transform(&arr2(&[[5.]])), arr2(&[[0., 0.]]));
+    }
+}
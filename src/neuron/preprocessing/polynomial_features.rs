@@ -0,0 +1,102 @@
+use ndarray::Array2;
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Generates all products of the features up to a degree, e.g. for degree 2
+/// `[a, b]` becomes `[1, a, b, a^2, ab, b^2]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolynomialFeatures {
+    degree: usize,
+    include_bias: bool,
+    features: usize,
+    combinations: Vec<Vec<usize>>,
+}
+
+impl PolynomialFeatures {
+    pub fn new(degree: usize, include_bias: bool) -> Self {
+        assert!(degree > 0, "degree must be positive");
+
+        Self {
+            degree,
+            include_bias,
+            features: 0,
+            combinations: vec![],
+        }
+    }
+
+    /// Indices of the input features multiplied to create each output feature
+    pub fn combinations(&self) -> &[Vec<usize>] {
+        &self.combinations
+    }
+}
+
+impl Transformer for PolynomialFeatures {
+    fn fit(&mut self, data: &Array2<f32>) {
+        self.features = data.ncols();
+        self.combinations = if self.include_bias {
+            vec![vec![]]
+        } else {
+            vec![]
+        };
+
+        // each combination of a degree extends a combination of the previous degree
+        // with a feature that is not smaller than its last feature, so no product
+        // is generated twice
+        let mut previous_degree: Vec<Vec<usize>> = vec![vec![]];
+        for _ in 0..self.degree {
+            let mut current_degree = vec![];
+            for combination in previous_degree.iter() {
+                let first_feature = combination.last().copied().unwrap_or(0);
+                for feature in first_feature..self.features {
+                    let mut extended = combination.clone();
+                    extended.push(feature);
+                    current_degree.push(extended);
+                }
+            }
+
+            self.combinations.extend(current_degree.iter().cloned());
+            previous_degree = current_degree;
+        }
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.features);
+
+        let mut transformed = Array2::zeros((data.nrows(), self.combinations.len()));
+        for (sample, mut transformed_sample) in data.outer_iter().zip(transformed.outer_iter_mut())
+        {
+            for (value, combination) in transformed_sample.iter_mut().zip(self.combinations.iter())
+            {
+                *value = combination.iter().map(|&feature| sample[feature]).product();
+            }
+        }
+
+        transformed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_polynomial_features() {
+        let data = arr2(&[[2., 3.]]);
+
+        let mut with_bias = PolynomialFeatures::new(2, true);
+        assert_eq!(
+            with_bias.fit_transform(&data),
+            arr2(&[[1., 2., 3., 4., 6., 9.]])
+        );
+
+        let mut without_bias = PolynomialFeatures::new(3, false);
+        assert_eq!(
+            without_bias.fit_transform(&data),
+            arr2(&[[2., 3., 4., 6., 9., 8., 12., 18., 27.]])
+        );
+    }
+}
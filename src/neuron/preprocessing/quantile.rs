@@ -0,0 +1,19 @@
+use ndarray::ArrayView1;
+
+/// Get the `q` quantile of the non-NaN values, linearly interpolating between
+/// the closest values. NaN if there are no such values.
+pub fn quantile(values: ArrayView1<f32>, q: f32) -> f32 {
+    let mut sorted: Vec<f32> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+    if sorted.is_empty() {
+        return f32::NAN;
+    }
+
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let position = q * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    let fraction = position - lower as f32;
+
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
@@ -0,0 +1,75 @@
+use ndarray::{Array1, Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::quantile::quantile;
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Scales each feature by removing its median and dividing by its interquartile
+/// range, which makes it robust to outliers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RobustScaler {
+    center: Array1<f32>,
+    scale: Array1<f32>,
+}
+
+impl RobustScaler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn inverse_transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.center.len());
+
+        data * &self.scale + &self.center
+    }
+}
+
+impl Transformer for RobustScaler {
+    fn fit(&mut self, data: &Array2<f32>) {
+        assert!(data.nrows() > 0, "can't fit on empty data");
+
+        self.center = data.map_axis(Axis(0), |column| quantile(column, 0.5));
+
+        // constant features are left unscaled
+        self.scale = data.map_axis(Axis(0), |column| {
+            let iqr = quantile(column, 0.75) - quantile(column, 0.25);
+            if iqr == 0. {
+                1.
+            } else {
+                iqr
+            }
+        });
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.center.len());
+
+        (data - &self.center) / &self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use crate::neuron::preprocessing::transformer::assert_close;
+
+    use super::*;
+
+    #[test]
+    fn test_robust_scaler_round_trip() {
+        let data = arr2(&[[1., 2.], [2., 2.], [3., 2.], [4., 2.], [100., 2.]]);
+
+        let mut scaler = RobustScaler::new();
+        let scaled = scaler.fit_transform(&data);
+
+        // the median is 3 and the interquartile range 2, the outlier doesn't
+        // affect either
+        assert_close(
+            &scaled,
+            &arr2(&[[-1., 0.], [-0.5, 0.], [0., 0.], [0.5, 0.], [48.5, 0.]]),
+        );
+        assert_close(&scaler.inverse_transform(&scaled), &data);
+    }
+}
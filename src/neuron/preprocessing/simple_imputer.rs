@@ -0,0 +1,92 @@
+use ndarray::{Array1, Array2, Axis, Zip};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::quantile::quantile;
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Statistic used to replace missing values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ImputeStrategy {
+    Mean,
+    Median,
+}
+
+/// Replaces missing (NaN) values of each feature with a statistic of the feature's
+/// present values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleImputer {
+    strategy: ImputeStrategy,
+    statistics: Array1<f32>,
+}
+
+impl SimpleImputer {
+    pub fn new(strategy: ImputeStrategy) -> Self {
+        Self {
+            strategy,
+            statistics: Array1::zeros(0),
+        }
+    }
+
+    /// Value that replaces missing values of each feature
+    pub fn statistics(&self) -> &Array1<f32> {
+        &self.statistics
+    }
+}
+
+impl Transformer for SimpleImputer {
+    fn fit(&mut self, data: &Array2<f32>) {
+        self.statistics = data.map_axis(Axis(0), |column| match self.strategy {
+            ImputeStrategy::Mean => {
+                let (sum, count) = column
+                    .iter()
+                    .filter(|x| !x.is_nan())
+                    .fold((0., 0.), |(sum, count), &x| (sum + x, count + 1.));
+
+                sum / count
+            }
+            ImputeStrategy::Median => quantile(column, 0.5),
+        });
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.statistics.len());
+
+        let mut imputed = data.clone();
+        for mut sample in imputed.outer_iter_mut() {
+            Zip::from(&mut sample)
+                .and(&self.statistics)
+                .apply(|value, &statistic| {
+                    if value.is_nan() {
+                        *value = statistic;
+                    }
+                });
+        }
+
+        imputed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_simple_imputer() {
+        let data = arr2(&[[1., f32::NAN], [f32::NAN, 4.], [2., 5.], [6., 30.]]);
+
+        let mut mean_imputer = SimpleImputer::new(ImputeStrategy::Mean);
+        assert_eq!(
+            mean_imputer.fit_transform(&data),
+            arr2(&[[1., 13.], [3., 4.], [2., 5.], [6., 30.]])
+        );
+
+        let mut median_imputer = SimpleImputer::new(ImputeStrategy::Median);
+        assert_eq!(
+            median_imputer.fit_transform(&data),
+            arr2(&[[1., 5.], [2., 4.], [2., 5.], [6., 30.]])
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use ndarray::{Array1, Array2, Axis};
+use serde::{Deserialize, Serialize};
+
+use crate::neuron::preprocessing::transformer::assert_fitted_features;
+use crate::neuron::preprocessing::Transformer;
+
+/// Scales each feature to have zero mean and unit variance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StandardScaler {
+    mean: Array1<f32>,
+    scale: Array1<f32>,
+}
+
+impl StandardScaler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mean(&self) -> &Array1<f32> {
+        &self.mean
+    }
+
+    pub fn scale(&self) -> &Array1<f32> {
+        &self.scale
+    }
+
+    pub fn inverse_transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.mean.len());
+
+        data * &self.scale + &self.mean
+    }
+}
+
+impl Transformer for StandardScaler {
+    fn fit(&mut self, data: &Array2<f32>) {
+        self.mean = data.mean_axis(Axis(0)).expect("can't fit on empty data");
+
+        // constant features are left unscaled
+        self.scale = data
+            .std_axis(Axis(0), 0.)
+            .map(|&std| if std == 0. { 1. } else { std });
+    }
+
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32> {
+        assert_fitted_features(data, self.mean.len());
+
+        (data - &self.mean) / &self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use crate::neuron::preprocessing::transformer::assert_close;
+
+    use super::*;
+
+    #[test]
+    fn test_standard_scaler() {
+        let data = arr2(&[[1., 5.], [3., 5.], [5., 5.]]);
+
+        let mut scaler = StandardScaler::new();
+        let scaled = scaler.fit_transform(&data);
+
+        let std = (8f32 / 3.).sqrt();
+        assert_close(&scaled, &arr2(&[[-2. / std, 0.], [0., 0.], [2. / std, 0.]]));
+        assert_close(&scaler.inverse_transform(&scaled), &data);
+    }
+
+    #[test]
+    fn test_standard_scaler_serialization() {
+        let data = arr2(&[[1., 2.], [3., -4.]]);
+        let mut scaler = StandardScaler::new();
+        scaler.fit(&data);
+
+        let json = serde_json::to_string(&scaler).unwrap();
+        let loaded: StandardScaler = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.transform(&data), scaler.transform(&data));
+    }
+
+    #[test]
+    #[should_panic(expected = "data has 3 features but the transformer was fitted on 2")]
+    fn test_standard_scaler_feature_mismatch() {
+        let mut scaler = StandardScaler::new();
+        scaler.fit(&arr2(&[[1., 2.]]));
+        scaler.transform(&arr2(&[[1., 2., 3.]]));
+    }
+}
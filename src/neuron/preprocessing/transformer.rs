@@ -0,0 +1,36 @@
+use ndarray::Array2;
+
+/// Learns parameters from data, then applies them to transform data
+///
+/// Rows are samples and columns are features.
+pub trait Transformer {
+    /// Learn the transformation parameters from the data
+    fn fit(&mut self, data: &Array2<f32>);
+
+    /// Transform the data using the fitted parameters
+    fn transform(&self, data: &Array2<f32>) -> Array2<f32>;
+
+    /// Fit the transformer on the data, then transform it
+    fn fit_transform(&mut self, data: &Array2<f32>) -> Array2<f32> {
+        self.fit(data);
+        self.transform(data)
+    }
+}
+
+pub(super) fn assert_fitted_features(data: &Array2<f32>, features: usize) {
+    assert_eq!(
+        data.ncols(),
+        features,
+        "data has {} features but the transformer was fitted on {}",
+        data.ncols(),
+        features
+    );
+}
+
+#[cfg(test)]
+pub(super) fn assert_close(actual: &Array2<f32>, expected: &Array2<f32>) {
+    assert_eq!(actual.dim(), expected.dim());
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() < 1e-5, "{} != {}", actual, expected);
+    }
+}
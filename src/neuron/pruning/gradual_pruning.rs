@@ -0,0 +1,145 @@
+use crate::neuron::networks::Model;
+use crate::neuron::pruning::{prune, PruningScope};
+use crate::neuron::Float;
+
+/// Magnitude pruning during training, raising the sparsity from an initial to a
+/// final sparsity on the cubic schedule of Zhu & Gupta (2017), which prunes
+/// quickly at first and slowly as fewer weights are left
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradualPruning {
+    initial_sparsity: f64,
+    final_sparsity: f64,
+    start_step: usize,
+    end_step: usize,
+    frequency: usize,
+    scope: PruningScope,
+}
+
+impl GradualPruning {
+    /// Prune every `frequency` training steps from `start_step` until `end_step`,
+    /// where the final sparsity is reached
+    pub fn new(
+        initial_sparsity: f64,
+        final_sparsity: f64,
+        start_step: usize,
+        end_step: usize,
+        frequency: usize,
+        scope: PruningScope,
+    ) -> Self {
+        assert!(
+            0. <= initial_sparsity && initial_sparsity <= final_sparsity && final_sparsity <= 1.,
+            "sparsities must be ordered and between 0 and 1"
+        );
+        assert!(
+            start_step <= end_step,
+            "start step must not be after end step"
+        );
+        assert!(frequency > 0, "frequency must be positive");
+
+        Self {
+            initial_sparsity,
+            final_sparsity,
+            start_step,
+            end_step,
+            frequency,
+            scope,
+        }
+    }
+
+    pub fn scope(&self) -> PruningScope {
+        self.scope
+    }
+
+    /// Target sparsity at the training step, zero before the schedule starts
+    pub fn sparsity(&self, step: usize) -> f64 {
+        if step < self.start_step {
+            return 0.;
+        }
+        if step >= self.end_step {
+            return self.final_sparsity;
+        }
+
+        let progress = (step - self.start_step) as f64 / (self.end_step - self.start_step) as f64;
+
+        self.final_sparsity
+            + (self.initial_sparsity - self.final_sparsity) * (1. - progress).powi(3)
+    }
+
+    /// Prune the model if the step is a pruning step of the schedule, returning
+    /// whether it was pruned
+    pub fn update<F: Float, M: Model<F>>(&self, model: &mut M, step: usize) -> bool {
+        let scheduled = step >= self.start_step
+            && step <= self.end_step
+            && ((step - self.start_step).is_multiple_of(self.frequency) || step == self.end_step);
+        if scheduled {
+            prune(model, self.sparsity(step), self.scope);
+        }
+
+        scheduled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+
+    use crate::neuron::activations::{linear, relu};
+    use crate::neuron::datasets::{DataLoader, InMemoryDataset};
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::pruning::PruningReport;
+
+    use super::*;
+
+    #[test]
+    fn test_schedule() {
+        let schedule = GradualPruning::new(0.1, 0.9, 10, 20, 3, PruningScope::Global);
+
+        assert_eq!(schedule.sparsity(0), 0.);
+        assert!((schedule.sparsity(10) - 0.1).abs() < 1e-12);
+        assert!((schedule.sparsity(15) - (0.9 - 0.8 * 0.125)).abs() < 1e-12);
+        assert_eq!(schedule.sparsity(20), 0.9);
+        assert_eq!(schedule.sparsity(100), 0.9);
+
+        let steps: Vec<usize> = (0..30)
+            .filter(|&step| {
+                let mut network: Network = Network::builder(2).dense(2, linear()).build().unwrap();
+                schedule.update(&mut network, step)
+            })
+            .collect();
+        assert_eq!(steps, vec![10, 13, 16, 19, 20]);
+    }
+
+    #[test]
+    fn test_train_pruned() {
+        let inputs: Vec<Array1<f32>> = (0..32)
+            .map(|i| array![i as f32 / 32., 1. - i as f32 / 32.])
+            .collect();
+        let expected: Vec<Array1<f32>> = inputs.iter().map(|x| array![x[0] * 2.]).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+        let loader = DataLoader::new(dataset.clone(), 4, true, false);
+
+        let mut network: Network = Network::builder(2)
+            .dense(16, relu())
+            .dense(1, linear())
+            .build()
+            .unwrap();
+        let optimizer = SGD::with_threads(mse(), 1);
+        let pruning = GradualPruning::new(0., 0.75, 0, 24, 4, PruningScope::PerLayer);
+        optimizer.train_pruned(&mut network, &loader, &dataset, 0.1, 5, &pruning);
+
+        let report = PruningReport::new(&network, &dataset, &mse());
+        assert_eq!(report.layers[0].pruned, 24);
+        assert_eq!(report.layers[1].pruned, 12);
+        assert_eq!(report.sparsity(), 0.75);
+
+        // masked weights stayed zero through the steps after the last pruning
+        for layer in network.get_layers() {
+            let mask = layer.get_mask().unwrap();
+            for (w, &kept) in layer.get_weights().iter().zip(mask.iter()) {
+                assert!(kept || *w == 0.);
+            }
+        }
+    }
+}
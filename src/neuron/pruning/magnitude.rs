@@ -0,0 +1,128 @@
+use std::cmp::Ordering;
+
+use ndarray::Array2;
+
+use crate::neuron::layers::Layer;
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Which weights are ranked against each other when pruning by magnitude
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PruningScope {
+    /// Rank the weights of all layers together, so layers with smaller weights
+    /// are pruned more
+    Global,
+    /// Prune the same ratio of every layer's weights
+    PerLayer,
+}
+
+/// Prune the smallest weights by magnitude until `sparsity` of the weights are
+/// pruned, masking them so they stay zero through training
+///
+/// Already pruned weights stay pruned and count towards the sparsity. Biases
+/// aren't pruned.
+pub fn prune<F: Float, M: Model<F>>(model: &mut M, sparsity: f64, scope: PruningScope) {
+    assert!(
+        (0. ..=1.).contains(&sparsity),
+        "sparsity must be between 0 and 1"
+    );
+
+    let mut layers = model.layers_mut();
+    match scope {
+        PruningScope::Global => prune_layers(&mut layers, sparsity),
+        PruningScope::PerLayer => {
+            for layer in layers {
+                prune_layers(&mut [layer], sparsity);
+            }
+        }
+    }
+}
+
+fn prune_layers<F: Float>(layers: &mut [&mut Layer<F>], sparsity: f64) {
+    // magnitudes of kept weights, pruned weights have none so they are ranked first
+    let mut ranking: Vec<(Option<F>, usize, usize)> = vec![];
+    for (l, layer) in layers.iter().enumerate() {
+        let kept: Box<dyn Iterator<Item = bool>> = match layer.get_mask() {
+            Some(mask) => Box::new(mask.iter().copied()),
+            None => Box::new(std::iter::repeat(true)),
+        };
+
+        for (i, (w, kept)) in layer.get_weights().iter().zip(kept).enumerate() {
+            ranking.push((if kept { Some(w.abs()) } else { None }, l, i));
+        }
+    }
+    ranking.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let mut masks: Vec<Array2<bool>> = layers
+        .iter()
+        .map(|layer| Array2::from_elem(layer.get_weights().dim(), true))
+        .collect();
+    let pruned = ((sparsity * ranking.len() as f64).round() as usize).min(ranking.len());
+    for &(_, l, i) in &ranking[..pruned] {
+        masks[l].as_slice_mut().unwrap()[i] = false;
+    }
+
+    for (layer, mask) in layers.iter_mut().zip(masks) {
+        layer.set_mask(Some(mask));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::linear;
+    use crate::neuron::networks::Network;
+    use crate::neuron::transfers::dense;
+
+    use super::*;
+
+    fn network() -> Network<f64> {
+        Network::new(vec![
+            Layer::from_parameters(
+                2,
+                2,
+                array![[0.1, -0.2], [0.3, 0.4]],
+                array![1., 1.],
+                dense(),
+                linear(),
+            ),
+            Layer::from_parameters(
+                2,
+                2,
+                array![[5., -6.], [-7., 8.]],
+                array![1., 1.],
+                dense(),
+                linear(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_global_pruning() {
+        let mut network = network();
+        prune(&mut network, 0.5, PruningScope::Global);
+
+        assert_eq!(network.get_weights()[0], &Array2::<f64>::zeros((2, 2)));
+        assert_eq!(network.get_weights()[1], &array![[5., -6.], [-7., 8.]]);
+        assert_eq!(network.get_biases()[0], &array![1., 1.]);
+
+        // pruned weights count towards the sparsity
+        prune(&mut network, 0.625, PruningScope::Global);
+        assert_eq!(network.get_weights()[0], &Array2::<f64>::zeros((2, 2)));
+        assert_eq!(network.get_weights()[1], &array![[0., -6.], [-7., 8.]]);
+    }
+
+    #[test]
+    fn test_per_layer_pruning() {
+        let mut network = network();
+        prune(&mut network, 0.5, PruningScope::PerLayer);
+
+        assert_eq!(network.get_weights()[0], &array![[0., 0.], [0.3, 0.4]]);
+        assert_eq!(network.get_weights()[1], &array![[0., 0.], [-7., 8.]]);
+        assert_eq!(
+            network.get_layers()[1].get_mask().unwrap(),
+            &array![[false, false], [true, true]]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub use gradual_pruning::GradualPruning;
+pub use magnitude::{prune, PruningScope};
+pub use pruning_error::PruningError;
+pub use pruning_report::{LayerSparsity, PruningReport};
+pub use sparse_layer::SparseLayer;
+pub use sparse_network::SparseNetwork;
+
+mod gradual_pruning;
+mod magnitude;
+mod pruning_error;
+mod pruning_report;
+mod sparse_layer;
+mod sparse_network;
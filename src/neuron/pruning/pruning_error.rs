@@ -0,0 +1,23 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors returned when converting a pruned `Network` to sparse storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PruningError {
+    /// Only dense layers can be stored sparsely
+    UnsupportedLayer { layer: usize, transfer: String },
+}
+
+impl Display for PruningError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PruningError::UnsupportedLayer { layer, transfer } => write!(
+                f,
+                "layer {} has a {} transfer, expected dense",
+                layer, transfer
+            ),
+        }
+    }
+}
+
+impl Error for PruningError {}
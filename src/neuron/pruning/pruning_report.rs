@@ -0,0 +1,179 @@
+use std::fmt::{Display, Formatter};
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::{format_bytes, Network};
+use crate::neuron::pruning::sparse_layer::sparse_memory;
+use crate::neuron::validation::{evaluate, Score};
+use crate::neuron::Float;
+
+/// Amount of zero weights of a layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayerSparsity {
+    pub weights: usize,
+    pub pruned: usize,
+}
+
+impl LayerSparsity {
+    /// Ratio of the weights that are zero, 0 for a layer without weights
+    pub fn sparsity(&self) -> f64 {
+        ratio(self.pruned, self.weights)
+    }
+}
+
+fn ratio(pruned: usize, weights: usize) -> f64 {
+    if weights == 0 {
+        0.
+    } else {
+        pruned as f64 / weights as f64
+    }
+}
+
+/// Sparsity and score of a pruned `Network`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PruningReport<F: Float = f32> {
+    pub layers: Vec<LayerSparsity>,
+    pub score: Score<F>,
+    /// Memory used by the network's parameters, in bytes
+    pub dense_memory: usize,
+    /// Memory used by the parameters of a `SparseNetwork` of the network, in
+    /// bytes
+    pub sparse_memory: usize,
+}
+
+impl<F: Float> PruningReport<F> {
+    /// Count the network's zero weights and score it on every sample of the
+    /// dataset
+    pub fn new<D: Dataset<F>>(network: &Network<F>, dataset: &D, loss: &Loss<F>) -> Self {
+        let layers: Vec<LayerSparsity> = network
+            .get_layers()
+            .iter()
+            .map(|layer| LayerSparsity {
+                weights: layer.get_weights().len(),
+                pruned: layer
+                    .get_weights()
+                    .iter()
+                    .filter(|&&w| w == F::zero())
+                    .count(),
+            })
+            .collect();
+
+        let sparse_memory = layers
+            .iter()
+            .zip(network.get_layers())
+            .map(|(sparsity, layer)| {
+                sparse_memory::<F>(sparsity.weights - sparsity.pruned, layer.output_size())
+            })
+            .sum();
+
+        Self {
+            layers,
+            score: evaluate(network, dataset, loss),
+            dense_memory: network.summary().memory(),
+            sparse_memory,
+        }
+    }
+
+    /// Ratio of the weights of all layers that are zero, 0 for a network without
+    /// weights
+    pub fn sparsity(&self) -> f64 {
+        let weights: usize = self.layers.iter().map(|l| l.weights).sum();
+        let pruned: usize = self.layers.iter().map(|l| l.pruned).sum();
+
+        ratio(pruned, weights)
+    }
+}
+
+impl<F: Float> Display for PruningReport<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header = format!(
+            "{:<6} {:>10} {:>10} {:>10}",
+            "layer", "weights", "pruned", "sparsity"
+        );
+        let separator = "-".repeat(header.len());
+
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", separator)?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<6} {:>10} {:>10} {:>9.2}%",
+                i,
+                layer.weights,
+                layer.pruned,
+                layer.sparsity() * 100.
+            )?;
+        }
+        writeln!(f, "{}", separator)?;
+        writeln!(f, "sparsity:          {:.2}%", self.sparsity() * 100.)?;
+        writeln!(
+            f,
+            "loss:              {:.6}  accuracy: {:.4}",
+            self.score.loss, self.score.accuracy
+        )?;
+        write!(
+            f,
+            "parameters memory: {} dense, {} sparse",
+            format_bytes(self.dense_memory),
+            format_bytes(self.sparse_memory)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+
+    use crate::neuron::activations::{relu, sigmoid};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::losses::mse;
+    use crate::neuron::pruning::{prune, PruningScope, SparseNetwork};
+
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut network: Network<f64> = Network::builder(4)
+            .dense(20, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap();
+        let inputs: Vec<Array1<f64>> = (0..10)
+            .map(|i| Array1::from_elem(4, i as f64 / 10.))
+            .collect();
+        let expected = inputs.iter().map(|x| network.predict(x)).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+
+        prune(&mut network, 0.5, PruningScope::PerLayer);
+        let report = PruningReport::new(&network, &dataset, &mse());
+
+        assert_eq!(
+            report.layers,
+            vec![
+                LayerSparsity {
+                    weights: 80,
+                    pruned: 40
+                },
+                LayerSparsity {
+                    weights: 40,
+                    pruned: 20
+                }
+            ]
+        );
+        assert_eq!(report.sparsity(), 0.5);
+        let empty = LayerSparsity {
+            weights: 0,
+            pruned: 0,
+        };
+        assert_eq!(empty.sparsity(), 0.);
+        assert!(report.score.loss < 1e-3);
+        assert_eq!(
+            report.sparse_memory,
+            SparseNetwork::new(&network).unwrap().memory()
+        );
+
+        let table = report.to_string();
+        assert!(table.starts_with("layer"));
+        assert!(table.contains("50.00%"));
+    }
+}
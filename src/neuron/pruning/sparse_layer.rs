@@ -0,0 +1,99 @@
+use std::mem::size_of;
+
+use ndarray::Array1;
+
+use crate::neuron::activations::Activation;
+use crate::neuron::layers::Layer;
+use crate::neuron::Float;
+
+/// Dense layer with its nonzero weights in compressed sparse row format, so
+/// inference skips pruned weights
+#[derive(Debug, Clone)]
+pub struct SparseLayer<F: Float = f32> {
+    inputs: usize,
+    /// Nonzero weights, row by row
+    values: Vec<F>,
+    /// Input of each nonzero weight
+    columns: Vec<u32>,
+    /// Start of each row in `values`, and the amount of values
+    row_offsets: Vec<usize>,
+    biases: Array1<F>,
+    activation_fn: Activation<F>,
+}
+
+impl<F: Float> SparseLayer<F> {
+    /// Store a dense layer's nonzero weights
+    ///
+    /// Panics if the layer isn't dense.
+    pub fn new(layer: &Layer<F>) -> Self {
+        assert_eq!(
+            layer.get_transfer().name(),
+            "dense",
+            "only dense layers can be stored sparsely"
+        );
+
+        let mut values = vec![];
+        let mut columns = vec![];
+        let mut row_offsets = vec![0];
+        for row in layer.get_weights().outer_iter() {
+            for (column, &w) in row.iter().enumerate() {
+                if w != F::zero() {
+                    values.push(w);
+                    columns.push(column as u32);
+                }
+            }
+            row_offsets.push(values.len());
+        }
+
+        Self {
+            inputs: layer.input_size(),
+            values,
+            columns,
+            row_offsets,
+            biases: layer.get_biases().clone(),
+            activation_fn: *layer.get_activation(),
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.inputs
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    /// Amount of stored weights
+    pub fn nonzeros(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn get_activation(&self) -> &Activation<F> {
+        &self.activation_fn
+    }
+
+    /// Memory used by the stored weights, their indices and the biases, in bytes
+    pub fn memory(&self) -> usize {
+        sparse_memory::<F>(self.nonzeros(), self.output_size())
+    }
+
+    pub fn forward(&self, input: &Array1<F>) -> Array1<F> {
+        let mut transfer = self.biases.clone();
+        for (r, t) in transfer.iter_mut().enumerate() {
+            let row = self.row_offsets[r]..self.row_offsets[r + 1];
+            for (&w, &column) in self.values[row.clone()].iter().zip(&self.columns[row]) {
+                *t += w * input[column as usize];
+            }
+        }
+
+        self.activation_fn.activate(&transfer)
+    }
+}
+
+/// Memory of a sparse layer with the amount of nonzero weights and outputs, in
+/// bytes
+pub(super) fn sparse_memory<F: Float>(nonzeros: usize, outputs: usize) -> usize {
+    nonzeros * (size_of::<F>() + size_of::<u32>())
+        + (outputs + 1) * size_of::<usize>()
+        + outputs * size_of::<F>()
+}
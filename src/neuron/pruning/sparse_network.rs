@@ -0,0 +1,104 @@
+use ndarray::Array1;
+
+use crate::neuron::networks::Network;
+use crate::neuron::pruning::{PruningError, SparseLayer};
+use crate::neuron::Float;
+
+/// A pruned `Network` of dense layers with sparse weights, for inference
+#[derive(Debug, Clone)]
+pub struct SparseNetwork<F: Float = f32> {
+    layers: Vec<SparseLayer<F>>,
+}
+
+impl<F: Float> SparseNetwork<F> {
+    pub fn new(network: &Network<F>) -> Result<Self, PruningError> {
+        let mut layers = Vec::with_capacity(network.len());
+        for (i, layer) in network.get_layers().iter().enumerate() {
+            if layer.get_transfer().name() != "dense" {
+                return Err(PruningError::UnsupportedLayer {
+                    layer: i,
+                    transfer: layer.get_transfer().name().to_string(),
+                });
+            }
+
+            layers.push(SparseLayer::new(layer));
+        }
+
+        Ok(Self { layers })
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn get_layers(&self) -> &Vec<SparseLayer<F>> {
+        &self.layers
+    }
+
+    /// Memory used by the sparse parameters, in bytes
+    pub fn memory(&self) -> usize {
+        self.layers.iter().map(|layer| layer.memory()).sum()
+    }
+
+    pub fn predict(&self, input: &Array1<F>) -> Array1<F> {
+        self.layers
+            .iter()
+            .fold(input.clone(), |input, layer| layer.forward(&input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::neuron::activations::{relu, sigmoid, tanh};
+    use crate::neuron::pruning::{prune, PruningScope};
+    use crate::neuron::transfers::{conv1d, Padding};
+
+    use super::*;
+
+    #[test]
+    fn test_sparse_predictions_match() {
+        let mut network: Network<f64> = Network::builder(8)
+            .dense(32, relu())
+            .dense(16, tanh())
+            .dense(4, sigmoid())
+            .build()
+            .unwrap();
+        for weights in network.get_weights_mut() {
+            weights.mapv_inplace(|w| w * 100.);
+        }
+        prune(&mut network, 0.9, PruningScope::Global);
+
+        let sparse = SparseNetwork::new(&network).unwrap();
+        let nonzeros: usize = sparse.get_layers().iter().map(|l| l.nonzeros()).sum();
+        assert_eq!(nonzeros, 83);
+        assert!(sparse.memory() * 2 < network.summary().memory());
+
+        for i in 0..10 {
+            let input = Array1::from_shape_fn(8, |j| ((i * 8 + j) as f64 * 0.7).sin());
+            let error = (network.predict(&input) - sparse.predict(&input))
+                .mapv(f64::abs)
+                .fold(0., |max: f64, &e| max.max(e));
+            assert!(error < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_layer() {
+        let conv: Network = Network::builder(4)
+            .layer(4, conv1d(1, 2, 3, 1, 1, Padding::Valid), relu())
+            .dense(2, relu())
+            .build()
+            .unwrap();
+        assert_eq!(
+            SparseNetwork::new(&conv).unwrap_err(),
+            PruningError::UnsupportedLayer {
+                layer: 0,
+                transfer: "conv1d".to_string()
+            }
+        );
+    }
+}
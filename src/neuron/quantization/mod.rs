@@ -0,0 +1,11 @@
+pub use quantization_error::QuantizationError;
+pub use quantization_params::QuantizationParams;
+pub use quantization_report::{LayerQuantization, QuantizationReport};
+pub use quantized_layer::QuantizedLayer;
+pub use quantized_network::QuantizedNetwork;
+
+mod quantization_error;
+mod quantization_params;
+mod quantization_report;
+mod quantized_layer;
+mod quantized_network;
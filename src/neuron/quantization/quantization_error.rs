@@ -0,0 +1,28 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors returned when quantizing a `Network`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuantizationError {
+    /// The calibration dataset has no samples to measure activation ranges on
+    NoCalibrationData,
+    /// Only dense layers can be quantized
+    UnsupportedLayer { layer: usize, transfer: String },
+}
+
+impl Display for QuantizationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuantizationError::NoCalibrationData => write!(f, "calibration dataset is empty"),
+            QuantizationError::UnsupportedLayer { layer, transfer } => {
+                write!(
+                    f,
+                    "layer {} has a {} transfer, expected dense",
+                    layer, transfer
+                )
+            }
+        }
+    }
+}
+
+impl Error for QuantizationError {}
@@ -0,0 +1,71 @@
+/// Affine mapping between floats and `i8`s, `x ≈ scale * (q - zero_point)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantizationParams {
+    pub scale: f32,
+    pub zero_point: i8,
+}
+
+impl QuantizationParams {
+    /// Map the range onto all 256 `i8` values
+    ///
+    /// The range is extended to contain 0, so 0 is quantized exactly, e.g.
+    /// activations after a relu.
+    pub fn from_range(min: f32, max: f32) -> Self {
+        let min = min.min(0.);
+        let max = max.max(0.);
+        let scale = if max > min { (max - min) / 255. } else { 1. };
+        let zero_point = (-128. - min / scale).round().clamp(-128., 127.) as i8;
+
+        Self { scale, zero_point }
+    }
+
+    /// Map `[-max_abs, max_abs]` onto `[-127, 127]`, with a zero point of 0
+    pub fn symmetric(max_abs: f32) -> Self {
+        let scale = if max_abs > 0. { max_abs / 127. } else { 1. };
+
+        Self {
+            scale,
+            zero_point: 0,
+        }
+    }
+
+    /// Nearest `i8` of the value, saturating outside the range
+    pub fn quantize(&self, x: f32) -> i8 {
+        (x / self.scale + self.zero_point as f32)
+            .round()
+            .clamp(-128., 127.) as i8
+    }
+
+    pub fn dequantize(&self, q: i8) -> f32 {
+        self.scale * (q as i32 - self.zero_point as i32) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize_range() {
+        let params = QuantizationParams::from_range(-1., 3.);
+        assert_eq!(params.quantize(-1.), -128);
+        assert_eq!(params.quantize(3.), 127);
+        assert_eq!(params.dequantize(params.quantize(0.)), 0.);
+        assert_eq!(params.quantize(100.), 127);
+
+        for i in 0..=40 {
+            let x = -1. + i as f32 * 0.1;
+            assert!((params.dequantize(params.quantize(x)) - x).abs() <= params.scale / 2. + 1e-6);
+        }
+
+        // ranges without negative values still contain 0
+        let params = QuantizationParams::from_range(2., 4.);
+        assert_eq!(params.zero_point, -128);
+        assert_eq!(params.dequantize(-128), 0.);
+
+        let params = QuantizationParams::symmetric(0.5);
+        assert_eq!(params.quantize(-0.5), -127);
+        assert_eq!(params.quantize(0.5), 127);
+        assert_eq!(QuantizationParams::symmetric(0.).scale, 1.);
+    }
+}
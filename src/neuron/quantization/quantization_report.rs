@@ -0,0 +1,223 @@
+use std::fmt::{Display, Formatter};
+
+use ndarray_stats::QuantileExt;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::{format_bytes, Network};
+use crate::neuron::quantization::{QuantizationParams, QuantizedNetwork};
+use crate::neuron::validation::Score;
+use crate::neuron::Float;
+
+/// Calibrated parameters and weights error of a quantized layer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayerQuantization {
+    pub input: QuantizationParams,
+    pub weights_scale: f32,
+    /// Largest absolute difference between a weight and its dequantized value
+    pub weights_error: f32,
+}
+
+/// Accuracy of a `QuantizedNetwork` compared to the float `Network` it was
+/// quantized from
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuantizationReport<F: Float = f32> {
+    pub layers: Vec<LayerQuantization>,
+    pub float_score: Score<F>,
+    pub quantized_score: Score<F>,
+    /// Mean absolute difference between the float and quantized outputs
+    pub mean_absolute_error: F,
+    pub max_absolute_error: F,
+    /// Ratio of samples where the float and quantized predictions have the same
+    /// highest output
+    pub agreement: F,
+    pub float_memory: usize,
+    pub quantized_memory: usize,
+}
+
+impl<F: Float> QuantizationReport<F> {
+    /// Compare the predictions of both networks on every sample of the dataset
+    pub fn new<D: Dataset<F>>(
+        network: &Network<F>,
+        quantized: &QuantizedNetwork<F>,
+        dataset: &D,
+        loss: &Loss<F>,
+    ) -> Self {
+        let layers = network
+            .get_layers()
+            .iter()
+            .zip(quantized.get_layers())
+            .map(|(layer, quantized)| {
+                let weights_error = layer
+                    .get_weights()
+                    .iter()
+                    .zip(quantized.dequantized_weights().iter())
+                    .fold(0f32, |max, (w, q)| max.max((w.to_f32().unwrap() - q).abs()));
+
+                LayerQuantization {
+                    input: quantized.get_input_params(),
+                    weights_scale: quantized.get_weights_scale(),
+                    weights_error,
+                }
+            })
+            .collect();
+
+        let samples = dataset.len();
+        let mut float_loss = F::zero();
+        let mut quantized_loss = F::zero();
+        let mut float_mistakes = 0;
+        let mut quantized_mistakes = 0;
+        let mut disagreements = 0;
+        let mut total_error = F::zero();
+        let mut max_absolute_error = F::zero();
+        let mut outputs = 0;
+        for i in 0..samples {
+            let (input, expected) = dataset.get(i);
+            let float_prediction = network.predict(&input);
+            let quantized_prediction = quantized.predict(&input);
+
+            let expected_class = expected.argmax().unwrap();
+            let float_class = float_prediction.argmax().unwrap();
+            let quantized_class = quantized_prediction.argmax().unwrap();
+            if float_class != expected_class {
+                float_mistakes += 1;
+            }
+            if quantized_class != expected_class {
+                quantized_mistakes += 1;
+            }
+            if float_class != quantized_class {
+                disagreements += 1;
+            }
+
+            float_loss += loss.loss(&float_prediction, &expected).sum();
+            quantized_loss += loss.loss(&quantized_prediction, &expected).sum();
+
+            for (&a, &b) in float_prediction.iter().zip(quantized_prediction.iter()) {
+                let error = (a - b).abs();
+                total_error += error;
+                max_absolute_error = max_absolute_error.max(error);
+            }
+            outputs += float_prediction.len();
+        }
+
+        let ratio = |count: usize, total: usize| F::from(count).unwrap() / F::from(total).unwrap();
+
+        Self {
+            layers,
+            float_score: Score {
+                loss: float_loss / F::from(samples).unwrap(),
+                accuracy: F::one() - ratio(float_mistakes, samples),
+            },
+            quantized_score: Score {
+                loss: quantized_loss / F::from(samples).unwrap(),
+                accuracy: F::one() - ratio(quantized_mistakes, samples),
+            },
+            mean_absolute_error: total_error / F::from(outputs).unwrap(),
+            max_absolute_error,
+            agreement: F::one() - ratio(disagreements, samples),
+            float_memory: network.summary().memory(),
+            quantized_memory: quantized.memory(),
+        }
+    }
+}
+
+impl<F: Float> Display for QuantizationReport<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let header = format!(
+            "{:<6} {:>12} {:>11} {:>14} {:>14}",
+            "layer", "input scale", "zero point", "weights scale", "weights error"
+        );
+        let separator = "-".repeat(header.len());
+
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", separator)?;
+        for (i, layer) in self.layers.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<6} {:>12.6} {:>11} {:>14.6} {:>14.6}",
+                i,
+                layer.input.scale,
+                layer.input.zero_point,
+                layer.weights_scale,
+                layer.weights_error
+            )?;
+        }
+        writeln!(f, "{}", separator)?;
+        writeln!(
+            f,
+            "float loss:          {:.6}  accuracy: {:.4}",
+            self.float_score.loss, self.float_score.accuracy
+        )?;
+        writeln!(
+            f,
+            "quantized loss:      {:.6}  accuracy: {:.4}",
+            self.quantized_score.loss, self.quantized_score.accuracy
+        )?;
+        writeln!(
+            f,
+            "output error:        mean {:.6}  max {:.6}",
+            self.mean_absolute_error, self.max_absolute_error
+        )?;
+        writeln!(f, "prediction agreement: {:.4}", self.agreement)?;
+        write!(
+            f,
+            "parameters memory:   {} -> {}",
+            format_bytes(self.float_memory),
+            format_bytes(self.quantized_memory)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use crate::neuron::activations::{relu, sigmoid};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::losses::mse;
+
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let mut network: Network<f64> = Network::builder(2)
+            .dense(8, relu())
+            .dense(2, sigmoid())
+            .build()
+            .unwrap();
+        for weights in network.get_weights_mut() {
+            weights.mapv_inplace(|w| w * 100.);
+        }
+
+        let inputs: Vec<Array1<f64>> = (0..50)
+            .map(|i| array![i as f64 / 25. - 1., (i as f64 * 0.3).sin()])
+            .collect();
+        let expected = inputs.iter().map(|x| network.predict(x)).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+
+        let quantized = QuantizedNetwork::quantize(&network, &dataset).unwrap();
+        let report = QuantizationReport::new(&network, &quantized, &dataset, &mse());
+
+        assert_eq!(report.layers.len(), 2);
+        for (layer, quantization) in network.get_layers().iter().zip(report.layers.iter()) {
+            assert!(quantization.weights_error <= quantization.weights_scale / 2. + 1e-6);
+            let max_abs = layer
+                .get_weights()
+                .iter()
+                .fold(0., |m: f64, w| m.max(w.abs()));
+            assert!((quantization.weights_scale as f64 - max_abs / 127.).abs() < 1e-6);
+        }
+
+        assert_eq!(report.float_score.loss, 0.);
+        assert_eq!(report.float_score.accuracy, 1.);
+        assert!(report.quantized_score.loss < 1e-3);
+        assert!(report.mean_absolute_error <= report.max_absolute_error);
+        assert!(report.max_absolute_error < 0.05);
+        assert_eq!(report.agreement, report.quantized_score.accuracy);
+        assert!(report.quantized_memory < report.float_memory);
+
+        let table = report.to_string();
+        assert!(table.starts_with("layer"));
+        assert!(table.contains("prediction agreement"));
+    }
+}
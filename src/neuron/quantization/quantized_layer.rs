@@ -0,0 +1,111 @@
+use std::mem::size_of;
+
+use ndarray::{Array1, Array2};
+
+use crate::neuron::activations::Activation;
+use crate::neuron::layers::Layer;
+use crate::neuron::quantization::QuantizationParams;
+use crate::neuron::Float;
+
+/// Dense layer with `i8` weights, for integer inference
+///
+/// Inputs are quantized with the layer's calibrated input parameters, multiplied
+/// by the weights with `i32` accumulators, and the transfers dequantized before
+/// the layer's activation.
+#[derive(Debug, Clone)]
+pub struct QuantizedLayer<F: Float = f32> {
+    input: QuantizationParams,
+    weights: Array2<i8>,
+    weights_scale: f32,
+    /// Biases in units of `input.scale * weights_scale`
+    biases: Array1<i32>,
+    activation_fn: Activation<F>,
+}
+
+impl<F: Float> QuantizedLayer<F> {
+    /// Quantize a dense layer's weights symmetrically with a single scale, given
+    /// the calibrated parameters of its inputs
+    pub fn new(layer: &Layer<F>, input: QuantizationParams) -> Self {
+        let weights = layer.get_weights().mapv(|w| w.to_f32().unwrap());
+        let max_abs = weights.iter().fold(0f32, |max, w| max.max(w.abs()));
+        let weights_params = QuantizationParams::symmetric(max_abs);
+
+        let biases_scale = input.scale * weights_params.scale;
+        let biases = layer
+            .get_biases()
+            .mapv(|b| (b.to_f32().unwrap() / biases_scale).round() as i32);
+
+        Self {
+            input,
+            weights: weights.mapv(|w| weights_params.quantize(w)),
+            weights_scale: weights_params.scale,
+            biases,
+            activation_fn: *layer.get_activation(),
+        }
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.weights.ncols()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.weights.nrows()
+    }
+
+    pub fn get_input_params(&self) -> QuantizationParams {
+        self.input
+    }
+
+    pub fn get_weights(&self) -> &Array2<i8> {
+        &self.weights
+    }
+
+    pub fn get_weights_scale(&self) -> f32 {
+        self.weights_scale
+    }
+
+    pub fn get_biases(&self) -> &Array1<i32> {
+        &self.biases
+    }
+
+    pub fn get_activation(&self) -> &Activation<F> {
+        &self.activation_fn
+    }
+
+    /// Weights as floats, to measure the quantization error
+    pub fn dequantized_weights(&self) -> Array2<f32> {
+        self.weights.mapv(|w| w as f32 * self.weights_scale)
+    }
+
+    /// Memory used by the quantized parameters and their scales, in bytes
+    pub fn memory(&self) -> usize {
+        self.weights.len() * size_of::<i8>()
+            + self.biases.len() * size_of::<i32>()
+            + size_of::<QuantizationParams>()
+            + size_of::<f32>()
+    }
+
+    /// Integer `weights · (input - zero_point) + biases`
+    pub fn transfer(&self, input: &Array1<i8>) -> Array1<i32> {
+        let zero_point = self.input.zero_point as i32;
+
+        let mut accumulators = self.biases.clone();
+        for (row, accumulator) in self.weights.outer_iter().zip(accumulators.iter_mut()) {
+            for (&w, &x) in row.iter().zip(input.iter()) {
+                *accumulator += w as i32 * (x as i32 - zero_point);
+            }
+        }
+
+        accumulators
+    }
+
+    pub fn forward(&self, input: &Array1<F>) -> Array1<F> {
+        let quantized = input.mapv(|x| self.input.quantize(x.to_f32().unwrap()));
+        let scale = self.input.scale * self.weights_scale;
+        let transfer = self
+            .transfer(&quantized)
+            .mapv(|acc| F::from(acc as f32 * scale).unwrap());
+
+        self.activation_fn.activate(&transfer)
+    }
+}
@@ -0,0 +1,186 @@
+use ndarray::Array1;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::networks::Network;
+use crate::neuron::quantization::{QuantizationError, QuantizationParams, QuantizedLayer};
+use crate::neuron::Float;
+
+/// Post-training int8 quantization of a `Network` of dense layers, for inference
+#[derive(Debug, Clone)]
+pub struct QuantizedNetwork<F: Float = f32> {
+    layers: Vec<QuantizedLayer<F>>,
+}
+
+impl<F: Float> QuantizedNetwork<F> {
+    /// Quantize the network, calibrating each layer's input parameters on the
+    /// range of its inputs over the calibration dataset's samples
+    ///
+    /// The calibration samples should resemble the inputs seen in inference,
+    /// e.g. a few hundred training samples. Only their inputs are used.
+    pub fn quantize<D: Dataset<F>>(
+        network: &Network<F>,
+        calibration: &D,
+    ) -> Result<Self, QuantizationError> {
+        if calibration.is_empty() {
+            return Err(QuantizationError::NoCalibrationData);
+        }
+
+        for (i, layer) in network.get_layers().iter().enumerate() {
+            if layer.get_transfer().name() != "dense" {
+                return Err(QuantizationError::UnsupportedLayer {
+                    layer: i,
+                    transfer: layer.get_transfer().name().to_string(),
+                });
+            }
+        }
+
+        let mut ranges = vec![(f32::INFINITY, f32::NEG_INFINITY); network.len()];
+        for i in 0..calibration.len() {
+            let (mut input, _) = calibration.get(i);
+            for (layer, range) in network.get_layers().iter().zip(ranges.iter_mut()) {
+                for x in input.iter() {
+                    let x = x.to_f32().unwrap();
+                    range.0 = range.0.min(x);
+                    range.1 = range.1.max(x);
+                }
+
+                input = layer.forward(&input);
+            }
+        }
+
+        let layers = network
+            .get_layers()
+            .iter()
+            .zip(ranges)
+            .map(|(layer, (min, max))| {
+                QuantizedLayer::new(layer, QuantizationParams::from_range(min, max))
+            })
+            .collect();
+
+        Ok(Self { layers })
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn get_layers(&self) -> &Vec<QuantizedLayer<F>> {
+        &self.layers
+    }
+
+    /// Memory used by the quantized parameters, in bytes
+    pub fn memory(&self) -> usize {
+        self.layers.iter().map(|layer| layer.memory()).sum()
+    }
+
+    pub fn predict(&self, input: &Array1<F>) -> Array1<F> {
+        self.layers
+            .iter()
+            .fold(input.clone(), |input, layer| layer.forward(&input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, relu, sigmoid};
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::layers::Layer;
+    use crate::neuron::transfers::{conv1d, dense, Padding};
+
+    use super::*;
+
+    fn dataset(network: &Network, samples: usize) -> InMemoryDataset {
+        let inputs: Vec<Array1<f32>> = (0..samples)
+            .map(|i| {
+                let t = i as f32 / samples as f32;
+                array![t * 2. - 1., (t * 7.).sin(), (t * 3.).cos() * 0.5, 1. - t]
+            })
+            .collect();
+        let expected = inputs.iter().map(|x| network.predict(x)).collect();
+
+        InMemoryDataset::new(inputs, expected)
+    }
+
+    #[test]
+    fn test_integer_layer() {
+        let layer: Layer = Layer::from_parameters(
+            2,
+            2,
+            array![[1., -0.5], [0.25, 0.]],
+            array![0.5, -1.],
+            dense(),
+            linear(),
+        );
+        let input = QuantizationParams::from_range(-1., 1.);
+        let quantized = QuantizedLayer::new(&layer, input);
+
+        assert_eq!(quantized.get_weights(), &array![[127, -64], [32, 0]]);
+        assert_eq!(quantized.get_weights_scale(), 1. / 127.);
+
+        let x = array![0.5, -1.];
+        let q = x.mapv(|x| input.quantize(x));
+        let zero_point = input.zero_point as i32;
+        let expected = array![
+            quantized.get_biases()[0] + 127 * (q[0] as i32 - zero_point)
+                - 64 * (q[1] as i32 - zero_point),
+            quantized.get_biases()[1] + 32 * (q[0] as i32 - zero_point)
+        ];
+        assert_eq!(quantized.transfer(&q), expected);
+
+        let prediction = quantized.forward(&x);
+        assert!((prediction[0] - 1.5).abs() < 0.02);
+        assert!((prediction[1] + 0.875).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_quantized_predictions_match() {
+        let mut network: Network = Network::builder(4)
+            .dense(16, relu())
+            .dense(8, relu())
+            .dense(3, sigmoid())
+            .build()
+            .unwrap();
+        // weights large enough for the activations to matter
+        for weights in network.get_weights_mut() {
+            weights.mapv_inplace(|w| w * 100.);
+        }
+        let calibration = dataset(&network, 200);
+
+        let quantized = QuantizedNetwork::quantize(&network, &calibration).unwrap();
+        assert_eq!(quantized.len(), 3);
+        assert!(quantized.memory() * 2 < network.summary().memory());
+
+        for i in (0..calibration.len()).step_by(7) {
+            let (input, _) = calibration.get(i);
+            let error = (network.predict(&input) - quantized.predict(&input))
+                .mapv(f32::abs)
+                .fold(0f32, |max, &e| max.max(e));
+            assert!(error < 0.05, "sample {} has error {}", i, error);
+        }
+    }
+
+    #[test]
+    fn test_quantize_errors() {
+        let network: Network = Network::builder(4).dense(2, relu()).build().unwrap();
+        let empty = InMemoryDataset::new(vec![], vec![]);
+        assert_eq!(
+            QuantizedNetwork::quantize(&network, &empty).unwrap_err(),
+            QuantizationError::NoCalibrationData
+        );
+
+        let conv: Network = Network::builder(4)
+            .layer(4, conv1d(1, 2, 3, 1, 1, Padding::Valid), relu())
+            .build()
+            .unwrap();
+        assert!(matches!(
+            QuantizedNetwork::quantize(&conv, &dataset(&network, 4)),
+            Err(QuantizationError::UnsupportedLayer { layer: 0, .. })
+        ));
+    }
+}
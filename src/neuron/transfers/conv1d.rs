@@ -0,0 +1,270 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// How a convolution pads its input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Padding {
+    /// No padding, the kernel only visits positions fully inside the input
+    Valid,
+    /// Pad both sides so the output length is the input length divided by the
+    /// stride, rounded up
+    Same,
+    /// Pad only the start, so each output only depends on inputs at or before its
+    /// position
+    Causal,
+}
+
+/// 1D convolution over inputs of `in_channels` channels
+///
+/// Inputs and outputs are flattened channel by channel, a layer with `inputs`
+/// inputs convolves `inputs / in_channels` long channels. The weights have a row
+/// per filter, holding its kernel for each input channel in turn, and the biases
+/// have a value per filter.
+#[derive(Debug, Clone, Copy)]
+pub struct Conv1D {
+    in_channels: usize,
+    filters: usize,
+    kernel_size: usize,
+    stride: usize,
+    dilation: usize,
+    padding: Padding,
+}
+
+impl Conv1D {
+    pub fn new(
+        in_channels: usize,
+        filters: usize,
+        kernel_size: usize,
+        stride: usize,
+        dilation: usize,
+        padding: Padding,
+    ) -> Self {
+        assert!(
+            in_channels > 0 && filters > 0 && kernel_size > 0 && stride > 0 && dilation > 0,
+            "channels, filters, kernel size, stride and dilation must be positive"
+        );
+
+        Self {
+            in_channels,
+            filters,
+            kernel_size,
+            stride,
+            dilation,
+            padding,
+        }
+    }
+
+    /// Padding before the start of each channel, and the output length, of
+    /// channels of length `length`
+    fn geometry(&self, length: usize) -> (usize, usize) {
+        let receptive_field = self.dilation * (self.kernel_size - 1) + 1;
+        let (before, after) = match self.padding {
+            Padding::Valid => (0, 0),
+            Padding::Causal => (receptive_field - 1, 0),
+            Padding::Same => {
+                let output_length = length.div_ceil(self.stride);
+                let total = ((output_length.max(1) - 1) * self.stride + receptive_field)
+                    .saturating_sub(length);
+                (total / 2, total - total / 2)
+            }
+        };
+
+        let padded = length + before + after;
+        let output_length = if padded >= receptive_field {
+            (padded - receptive_field) / self.stride + 1
+        } else {
+            0
+        };
+
+        (before, output_length)
+    }
+
+    /// Visit every (filter, output position, input channel, kernel offset) whose
+    /// kernel position is inside the input, with the flat output, weight column
+    /// and input indices
+    fn for_each_tap(&self, inputs: usize, mut visit: impl FnMut(usize, usize, usize, usize)) {
+        let length = inputs / self.in_channels;
+        let (before, output_length) = self.geometry(length);
+        for filter in 0..self.filters {
+            for position in 0..output_length {
+                let output = filter * output_length + position;
+                for channel in 0..self.in_channels {
+                    for offset in 0..self.kernel_size {
+                        let padded_position = position * self.stride + offset * self.dilation;
+                        if padded_position < before || padded_position - before >= length {
+                            continue;
+                        }
+
+                        let column = channel * self.kernel_size + offset;
+                        let input = channel * length + padded_position - before;
+                        visit(filter, output, column, input);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<F: Float> TransferFn<F> for Conv1D {
+    fn name(&self) -> &'static str {
+        "conv1d"
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (self.filters, self.in_channels * self.kernel_size)
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        self.filters
+    }
+
+    /// Inputs must split into the input channels, long enough for an output
+    fn accepts_inputs(&self, inputs: usize) -> bool {
+        inputs.is_multiple_of(self.in_channels) && self.geometry(inputs / self.in_channels).1 > 0
+    }
+
+    fn output_size(&self, inputs: usize) -> Option<usize> {
+        Some(self.filters * self.geometry(inputs / self.in_channels).1)
+    }
+
+    fn transfer(&self, weights: &Array2<F>, biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        let outputs = TransferFn::<F>::output_size(self, input.len()).unwrap();
+        let output_length = outputs / self.filters;
+
+        let mut output = Array1::from_shape_fn(outputs, |i| biases[i / output_length]);
+        self.for_each_tap(input.len(), |filter, o, column, i| {
+            output[o] += weights[(filter, column)] * input[i];
+        });
+
+        output
+    }
+
+    fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        let output_length = dl_dt.len() / self.filters;
+
+        let mut gradients = LayerGradients {
+            weights: Array2::zeros(weights.dim()),
+            biases: Array1::from_shape_fn(self.filters, |filter| {
+                (0..output_length)
+                    .map(|position| dl_dt[filter * output_length + position])
+                    .fold(F::zero(), |total, x| total + x)
+            }),
+            input: Array1::zeros(input.len()),
+        };
+        self.for_each_tap(input.len(), |filter, o, column, i| {
+            gradients.weights[(filter, column)] += dl_dt[o] * input[i];
+            gradients.input[i] += dl_dt[o] * weights[(filter, column)];
+        });
+
+        gradients
+    }
+}
+
+/// 1D convolution, see `Conv1D`
+pub fn conv1d<F: Float>(
+    in_channels: usize,
+    filters: usize,
+    kernel_size: usize,
+    stride: usize,
+    dilation: usize,
+    padding: Padding,
+) -> Transfer<F> {
+    Transfer::new(Conv1D::new(
+        in_channels,
+        filters,
+        kernel_size,
+        stride,
+        dilation,
+        padding,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, tanh};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::transfers::gradient_check::assert_gradients_match;
+
+    use super::*;
+
+    #[test]
+    fn test_conv1d_output_sizes() {
+        let size = |padding, stride, dilation| {
+            TransferFn::<f32>::output_size(&Conv1D::new(2, 3, 3, stride, dilation, padding), 20)
+                .unwrap()
+        };
+
+        // 2 channels of length 10, 3 filters
+        assert_eq!(size(Padding::Valid, 1, 1), 3 * 8);
+        assert_eq!(size(Padding::Valid, 2, 1), 3 * 4);
+        assert_eq!(size(Padding::Valid, 1, 2), 3 * 6);
+        assert_eq!(size(Padding::Same, 1, 2), 3 * 10);
+        assert_eq!(size(Padding::Same, 3, 1), 3 * 4);
+        assert_eq!(size(Padding::Causal, 1, 4), 3 * 10);
+        let transfer = Conv1D::new(2, 3, 3, 1, 1, Padding::Valid);
+        assert!(!TransferFn::<f32>::accepts_inputs(&transfer, 21));
+        assert!(!TransferFn::<f32>::accepts_inputs(&transfer, 4));
+        assert!(TransferFn::<f32>::accepts_inputs(&transfer, 6));
+    }
+
+    #[test]
+    fn test_conv1d_transfer() {
+        // a single channel moving sum of 2, with a bias of 1
+        let layer = Layer::from_parameters(
+            3,
+            4,
+            array![[1., 1.]],
+            array![1.],
+            conv1d(1, 1, 2, 1, 1, Padding::Valid),
+            linear(),
+        );
+
+        assert_eq!(layer.forward(&array![1., 2., 3., 4.]), array![4., 6., 8.]);
+    }
+
+    #[test]
+    fn test_conv1d_causal_outputs_only_depend_on_the_past() {
+        let layer: Layer<f64> = Layer::new(12, 12, conv1d(2, 2, 3, 1, 2, Padding::Causal), tanh());
+
+        let input = Array1::linspace(-1., 1., 12);
+        let mut changed = input.clone();
+        // change the last position of the first channel
+        changed[5] += 1.;
+
+        let output = layer.forward(&input);
+        let changed_output = layer.forward(&changed);
+        for filter in 0..2 {
+            for position in 0..6 {
+                let i = filter * 6 + position;
+                assert_eq!(output[i] == changed_output[i], position < 5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_conv1d_gradients() {
+        let input = Array1::linspace(-1., 1., 14);
+        for &(stride, dilation, padding) in &[
+            (1, 1, Padding::Valid),
+            (2, 1, Padding::Same),
+            (1, 2, Padding::Causal),
+            (3, 2, Padding::Same),
+        ] {
+            let transfer = conv1d(2, 3, 3, stride, dilation, padding);
+            let outputs = transfer.output_size(14).unwrap();
+            let layer: Layer<f64> = Layer::new(outputs, 14, transfer, tanh());
+
+            assert_gradients_match(&layer, &input);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// Transposed 2D convolution over inputs of `in_channels` channels of shape
+/// `input_shape`, the gradient of a strided convolution, which grows the
+/// spatial dimensions
+///
+/// Inputs and outputs are flattened channel by channel, each channel row by
+/// row. Each input value adds its channel's kernel, scaled by the value, to the
+/// output at `stride` times its position, and `padding` is removed from each
+/// side of the result. The weights have a row per filter, holding its kernel
+/// for each input channel in turn, and the biases have a value per filter.
+#[derive(Debug, Clone, Copy)]
+pub struct ConvTranspose2D {
+    in_channels: usize,
+    input_shape: (usize, usize),
+    filters: usize,
+    kernel_size: (usize, usize),
+    stride: (usize, usize),
+    padding: (usize, usize),
+}
+
+impl ConvTranspose2D {
+    pub fn new(
+        in_channels: usize,
+        input_shape: (usize, usize),
+        filters: usize,
+        kernel_size: (usize, usize),
+        stride: (usize, usize),
+        padding: (usize, usize),
+    ) -> Self {
+        assert!(
+            in_channels > 0 && filters > 0,
+            "channels and filters must be positive"
+        );
+        assert!(
+            input_shape.0 > 0 && input_shape.1 > 0,
+            "input shape must be positive"
+        );
+        assert!(
+            kernel_size.0 > 0 && kernel_size.1 > 0 && stride.0 > 0 && stride.1 > 0,
+            "kernel size and stride must be positive"
+        );
+
+        let transposed = Self {
+            in_channels,
+            input_shape,
+            filters,
+            kernel_size,
+            stride,
+            padding,
+        };
+        let (height, width) = transposed.output_shape();
+        assert!(
+            height > 0 && width > 0,
+            "padding must leave a non empty output"
+        );
+
+        transposed
+    }
+
+    /// Shape of each output channel
+    pub fn output_shape(&self) -> (usize, usize) {
+        let size = |input: usize, kernel: usize, stride: usize, padding: usize| {
+            ((input - 1) * stride + kernel).saturating_sub(2 * padding)
+        };
+
+        (
+            size(
+                self.input_shape.0,
+                self.kernel_size.0,
+                self.stride.0,
+                self.padding.0,
+            ),
+            size(
+                self.input_shape.1,
+                self.kernel_size.1,
+                self.stride.1,
+                self.padding.1,
+            ),
+        )
+    }
+
+    /// Visit every (input channel, input position, filter, kernel offset) that
+    /// lands inside the output, with the filter, flat output, weight column and
+    /// flat input indices
+    fn for_each_tap(&self, mut visit: impl FnMut(usize, usize, usize, usize)) {
+        let (height, width) = self.input_shape;
+        let (kernel_height, kernel_width) = self.kernel_size;
+        let (output_height, output_width) = self.output_shape();
+        for channel in 0..self.in_channels {
+            for y in 0..height {
+                for x in 0..width {
+                    let input = (channel * height + y) * width + x;
+                    for filter in 0..self.filters {
+                        for i in 0..kernel_height {
+                            for j in 0..kernel_width {
+                                let output_y = (y * self.stride.0 + i).checked_sub(self.padding.0);
+                                let output_x = (x * self.stride.1 + j).checked_sub(self.padding.1);
+                                let (output_y, output_x) = match (output_y, output_x) {
+                                    (Some(output_y), Some(output_x))
+                                        if output_y < output_height && output_x < output_width =>
+                                    {
+                                        (output_y, output_x)
+                                    }
+                                    _ => continue,
+                                };
+
+                                let output =
+                                    (filter * output_height + output_y) * output_width + output_x;
+                                let column = (channel * kernel_height + i) * kernel_width + j;
+                                visit(filter, output, column, input);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<F: Float> TransferFn<F> for ConvTranspose2D {
+    fn name(&self) -> &'static str {
+        "conv_transpose2d"
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (
+            self.filters,
+            self.in_channels * self.kernel_size.0 * self.kernel_size.1,
+        )
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        self.filters
+    }
+
+    /// Inputs must match the input channels and shape
+    fn accepts_inputs(&self, inputs: usize) -> bool {
+        inputs == self.in_channels * self.input_shape.0 * self.input_shape.1
+    }
+
+    fn output_size(&self, _inputs: usize) -> Option<usize> {
+        let (height, width) = self.output_shape();
+
+        Some(self.filters * height * width)
+    }
+
+    fn transfer(&self, weights: &Array2<F>, biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        let (height, width) = self.output_shape();
+
+        let mut output = Array1::from_shape_fn(self.filters * height * width, |i| {
+            biases[i / (height * width)]
+        });
+        self.for_each_tap(|filter, o, column, i| {
+            output[o] += weights[(filter, column)] * input[i];
+        });
+
+        output
+    }
+
+    fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        let (height, width) = self.output_shape();
+        let size = height * width;
+
+        let mut gradients = LayerGradients {
+            weights: Array2::zeros(weights.dim()),
+            biases: Array1::from_shape_fn(self.filters, |filter| {
+                (0..size)
+                    .map(|position| dl_dt[filter * size + position])
+                    .fold(F::zero(), |total, x| total + x)
+            }),
+            input: Array1::zeros(input.len()),
+        };
+        self.for_each_tap(|filter, o, column, i| {
+            gradients.weights[(filter, column)] += dl_dt[o] * input[i];
+            gradients.input[i] += dl_dt[o] * weights[(filter, column)];
+        });
+
+        gradients
+    }
+}
+
+/// Transposed 2D convolution, see `ConvTranspose2D`
+pub fn conv_transpose2d<F: Float>(
+    in_channels: usize,
+    input_shape: (usize, usize),
+    filters: usize,
+    kernel_size: (usize, usize),
+    stride: (usize, usize),
+    padding: (usize, usize),
+) -> Transfer<F> {
+    Transfer::new(ConvTranspose2D::new(
+        in_channels,
+        input_shape,
+        filters,
+        kernel_size,
+        stride,
+        padding,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, tanh};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::transfers::gradient_check::assert_gradients_match;
+
+    use super::*;
+
+    fn ones_kernel_layer(stride: (usize, usize)) -> Layer {
+        let transfer = conv_transpose2d(1, (2, 2), 1, (2, 2), stride, (0, 0));
+        let outputs = transfer.output_size(4).unwrap();
+
+        Layer::from_parameters(
+            outputs,
+            4,
+            Array2::ones((1, 4)),
+            array![0.],
+            transfer,
+            linear(),
+        )
+    }
+
+    #[test]
+    fn test_conv_transpose2d_transfer() {
+        let input = array![1., 2., 3., 4.];
+
+        // each input is spread over its own 2x2 block
+        let layer = ones_kernel_layer((2, 2));
+        assert_eq!(
+            layer.forward(&input),
+            array![1., 1., 2., 2., 1., 1., 2., 2., 3., 3., 4., 4., 3., 3., 4., 4.]
+        );
+
+        // overlapping blocks are summed
+        let layer = ones_kernel_layer((1, 1));
+        assert_eq!(
+            layer.forward(&input),
+            array![1., 3., 2., 4., 10., 6., 3., 7., 4.]
+        );
+    }
+
+    #[test]
+    fn test_conv_transpose2d_gradients() {
+        let input = Array1::linspace(-1., 1., 12);
+        for &(kernel_size, stride, padding) in &[
+            ((2, 2), (2, 2), (0, 0)),
+            ((3, 3), (2, 2), (1, 1)),
+            ((3, 2), (1, 2), (1, 0)),
+        ] {
+            let transfer = conv_transpose2d(2, (2, 3), 2, kernel_size, stride, padding);
+            let outputs = transfer.output_size(12).unwrap();
+            let layer: Layer<f64> = Layer::new(outputs, 12, transfer, tanh());
+
+            assert_gradients_match(&layer, &input);
+        }
+    }
+}
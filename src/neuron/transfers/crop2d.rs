@@ -0,0 +1,231 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// Crops `(top, bottom)` rows and `(left, right)` columns from each of `channels`
+/// channels of shape `input_shape`
+///
+/// Inputs and outputs are flattened channel by channel, each channel row by
+/// row, so the channels of same shaped feature maps can be concatenated with
+/// `GraphBuilder::concat`, e.g. for the skip connections of a decoder. Cropping
+/// has no weights or biases.
+#[derive(Debug, Clone, Copy)]
+pub struct Crop2D {
+    channels: usize,
+    input_shape: (usize, usize),
+    rows: (usize, usize),
+    columns: (usize, usize),
+}
+
+impl Crop2D {
+    pub fn new(
+        channels: usize,
+        input_shape: (usize, usize),
+        rows: (usize, usize),
+        columns: (usize, usize),
+    ) -> Self {
+        assert!(channels > 0, "channels must be positive");
+        assert!(
+            rows.0 + rows.1 < input_shape.0 && columns.0 + columns.1 < input_shape.1,
+            "cropping must leave a non empty output"
+        );
+
+        Self {
+            channels,
+            input_shape,
+            rows,
+            columns,
+        }
+    }
+
+    /// Crop evenly from both sides, with the extra row or column from the end, to
+    /// `output_shape`
+    pub fn center(
+        channels: usize,
+        input_shape: (usize, usize),
+        output_shape: (usize, usize),
+    ) -> Self {
+        assert!(
+            output_shape.0 <= input_shape.0 && output_shape.1 <= input_shape.1,
+            "output shape must fit in the input shape"
+        );
+
+        let split = |total: usize| (total / 2, total - total / 2);
+
+        Self::new(
+            channels,
+            input_shape,
+            split(input_shape.0 - output_shape.0),
+            split(input_shape.1 - output_shape.1),
+        )
+    }
+
+    /// Shape of each output channel
+    pub fn output_shape(&self) -> (usize, usize) {
+        (
+            self.input_shape.0 - self.rows.0 - self.rows.1,
+            self.input_shape.1 - self.columns.0 - self.columns.1,
+        )
+    }
+
+    /// Flat input index of each output
+    fn sources(&self) -> impl Iterator<Item = usize> + '_ {
+        let (height, width) = self.input_shape;
+        let (output_height, output_width) = self.output_shape();
+
+        (0..self.channels).flat_map(move |channel| {
+            (0..output_height).flat_map(move |y| {
+                (0..output_width)
+                    .map(move |x| (channel * height + y + self.rows.0) * width + x + self.columns.0)
+            })
+        })
+    }
+}
+
+impl<F: Float> TransferFn<F> for Crop2D {
+    fn name(&self) -> &'static str {
+        "crop2d"
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        0
+    }
+
+    /// Inputs must match the channels and input shape
+    fn accepts_inputs(&self, inputs: usize) -> bool {
+        inputs == self.channels * self.input_shape.0 * self.input_shape.1
+    }
+
+    fn output_size(&self, _inputs: usize) -> Option<usize> {
+        let (height, width) = self.output_shape();
+
+        Some(self.channels * height * width)
+    }
+
+    fn transfer(&self, _weights: &Array2<F>, _biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        self.sources().map(|i| input[i]).collect()
+    }
+
+    fn backward(
+        &self,
+        _weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        let mut input_gradients = Array1::zeros(input.len());
+        for (o, i) in self.sources().enumerate() {
+            input_gradients[i] = dl_dt[o];
+        }
+
+        LayerGradients {
+            weights: Array2::zeros((0, 0)),
+            biases: Array1::zeros(0),
+            input: input_gradients,
+        }
+    }
+}
+
+/// 2D cropping, see `Crop2D::new`
+pub fn crop2d<F: Float>(
+    channels: usize,
+    input_shape: (usize, usize),
+    rows: (usize, usize),
+    columns: (usize, usize),
+) -> Transfer<F> {
+    Transfer::new(Crop2D::new(channels, input_shape, rows, columns))
+}
+
+/// 2D cropping to the center `output_shape`, see `Crop2D::center`
+pub fn center_crop2d<F: Float>(
+    channels: usize,
+    input_shape: (usize, usize),
+    output_shape: (usize, usize),
+) -> Transfer<F> {
+    Transfer::new(Crop2D::center(channels, input_shape, output_shape))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use crate::neuron::activations::{linear, tanh};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::networks::{Graph, Model, NetworkError};
+    use crate::neuron::transfers::gradient_check::assert_gradients_match;
+    use crate::neuron::transfers::{conv_transpose2d, upsample2d, Interpolation};
+
+    use super::*;
+
+    #[test]
+    fn test_crop2d_transfer_and_gradients() {
+        // 2 channels of 3x4
+        let input: Array1<f64> = Array1::linspace(0., 23., 24);
+        let layer = Layer::new(4, 24, center_crop2d(2, (3, 4), (1, 2)), linear());
+        assert_eq!(layer.forward(&input), array![5., 6., 17., 18.]);
+
+        let layer = Layer::new(4, 24, crop2d(2, (3, 4), (1, 0), (0, 3)), linear());
+        assert_eq!(layer.forward(&input), array![4., 8., 16., 20.]);
+
+        assert_gradients_match(&layer, &input);
+    }
+
+    #[test]
+    fn test_decoder_skip_connection() {
+        // a 1 channel 5x5 feature map is cropped to 4x4 and concatenated with
+        // the 2 channel 4x4 upsampled and transposed convolutions of a 2x2 code
+        let mut builder = Graph::<f64>::builder();
+        let features = builder.input("features", 25);
+        let code = builder.input("code", 4);
+        let skip = builder.transfer(features, center_crop2d(1, (5, 5), (4, 4)), linear());
+        let upsampled = builder.transfer(
+            code,
+            upsample2d(1, (2, 2), (2, 2), Interpolation::Bilinear),
+            linear(),
+        );
+        let transposed = builder.transfer(
+            code,
+            conv_transpose2d(1, (2, 2), 1, (2, 2), (2, 2), (0, 0)),
+            tanh(),
+        );
+        let decoded = builder.concat(&[skip, upsampled, transposed]);
+        builder.output("decoded", decoded);
+        let graph = builder.build().unwrap();
+        assert_eq!(graph.output_size(), 3 * 16);
+
+        let input = Array1::linspace(-1., 1., 29);
+        let (prediction, cache) = graph.predict_cached(&input);
+        assert_eq!(prediction.len(), 48);
+        // the extra row and column are cropped from the end of the feature map
+        assert_eq!(prediction[5], input[6]);
+        assert_eq!(prediction[15], input[18]);
+
+        let gradients = graph.backward(&cache, &Array1::ones(48));
+        // only the cropped positions of the feature map get a gradient
+        let cropped = (0..25).filter(|&i| gradients.input[i] != 0.).count();
+        assert_eq!(cropped, 16);
+        assert!(gradients.input.iter().skip(25).all(|&g| g != 0.));
+
+        // the feature map isn't 2x2, so the upsampling doesn't accept it
+        let mut builder = Graph::<f64>::builder();
+        let features = builder.input("features", 25);
+        let upsampled = builder.transfer(
+            features,
+            upsample2d(1, (2, 2), (2, 2), Interpolation::Nearest),
+            linear(),
+        );
+        builder.output("upsampled", upsampled);
+        assert_eq!(
+            builder.build().unwrap_err(),
+            NetworkError::InvalidInputSize {
+                layer: 1,
+                inputs: 25
+            }
+        );
+    }
+}
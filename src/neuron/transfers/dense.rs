@@ -1,14 +1,51 @@
-use crate::neuron::transfers::Transfer;
-use ndarray::{Array1, Array2};
-
-pub fn dense_transfer(
-    weights: &Array2<f32>,
-    biases: &Array1<f32>,
-    input: &Array1<f32>,
-) -> Array1<f32> {
+use ndarray::{Array1, Array2, Axis};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+pub fn dense_transfer<F: Float>(
+    weights: &Array2<F>,
+    biases: &Array1<F>,
+    input: &Array1<F>,
+) -> Array1<F> {
     weights.dot(input) + biases
 }
 
-pub fn dense() -> Transfer {
-    Transfer::new(dense_transfer)
+/// Fully connected transfer, `weights · input + biases`
+#[derive(Debug, Clone, Copy)]
+pub struct Dense;
+
+impl<F: Float> TransferFn<F> for Dense {
+    fn name(&self) -> &'static str {
+        "dense"
+    }
+
+    fn transfer(&self, weights: &Array2<F>, biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        dense_transfer(weights, biases, input)
+    }
+
+    fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        // each weight connects one input to one transfer, so its derivative is
+        // the transfer's derivative times the input
+        let dl_dt_column = dl_dt.view().insert_axis(Axis(1));
+        let input_row = input.view().insert_axis(Axis(0));
+
+        LayerGradients {
+            weights: dl_dt_column.dot(&input_row),
+            // the derivatives of the transfers with respect to the biases are 1
+            biases: dl_dt.clone(),
+            // each input affects every transfer through its weights
+            input: weights.t().dot(dl_dt),
+        }
+    }
+}
+
+pub fn dense<F: Float>() -> Transfer<F> {
+    Transfer::new(Dense)
 }
@@ -0,0 +1,274 @@
+use ndarray::{s, Array1, Array2, Axis};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// Maps each input, an integer index into a vocabulary, to a trainable vector
+///
+/// The weights have a row per index in the vocabulary, and the output is the
+/// rows of the input indices concatenated in order. A layer with `inputs`
+/// inputs has `inputs * dimensions` outputs and no biases.
+///
+/// Only the update is sparse, leaving the rows without a gradient untouched. The
+/// weights' gradients are dense `vocabulary x dimensions` matrices that are zero
+/// outside the rows of the input indices, so computing and summing them still
+/// costs `vocabulary * dimensions` per sample.
+#[derive(Debug, Clone, Copy)]
+pub struct Embedding<F: Float = f32> {
+    vocabulary: usize,
+    dimensions: usize,
+    padding_index: Option<usize>,
+    max_norm: Option<F>,
+}
+
+impl<F: Float> Embedding<F> {
+    /// Create a new `Embedding`
+    ///
+    /// The row of `padding_index` is kept at zero and never updated. If
+    /// `max_norm` is set, rows with a larger norm are rescaled to `max_norm`
+    /// after each update.
+    pub fn new(
+        vocabulary: usize,
+        dimensions: usize,
+        padding_index: Option<usize>,
+        max_norm: Option<F>,
+    ) -> Self {
+        assert!(
+            vocabulary > 0 && dimensions > 0,
+            "vocabulary and dimensions must be positive"
+        );
+        assert!(
+            padding_index.is_none_or(|padding_index| padding_index < vocabulary),
+            "padding index must be in the vocabulary"
+        );
+        assert!(
+            max_norm.is_none_or(|max_norm| max_norm > F::zero()),
+            "max norm must be positive"
+        );
+
+        Self {
+            vocabulary,
+            dimensions,
+            padding_index,
+            max_norm,
+        }
+    }
+
+    fn index(&self, value: F) -> usize {
+        value
+            .to_usize()
+            .filter(|&index| index < self.vocabulary && value.fract() == F::zero())
+            .unwrap_or_else(|| {
+                panic!(
+                    "embedding input {} is not an index in a vocabulary of {}",
+                    value, self.vocabulary
+                )
+            })
+    }
+}
+
+impl<F: Float> TransferFn<F> for Embedding<F> {
+    fn name(&self) -> &'static str {
+        "embedding"
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (self.vocabulary, self.dimensions)
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        0
+    }
+
+    fn output_size(&self, inputs: usize) -> Option<usize> {
+        Some(inputs * self.dimensions)
+    }
+
+    fn transfer(&self, weights: &Array2<F>, _biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        let mut output = Array2::zeros((input.len(), self.dimensions));
+        for (mut row, &value) in output.outer_iter_mut().zip(input.iter()) {
+            let index = self.index(value);
+            if Some(index) != self.padding_index {
+                row.assign(&weights.row(index));
+            }
+        }
+
+        Array1::from(output.into_raw_vec())
+    }
+
+    fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        // only the rows of the input indices get a gradient, the rest stay zero
+        let mut weights_gradients = Array2::zeros(weights.dim());
+        for (position, &value) in input.iter().enumerate() {
+            let index = self.index(value);
+            if Some(index) == self.padding_index {
+                continue;
+            }
+
+            let start = position * self.dimensions;
+            let mut row = weights_gradients.row_mut(index);
+            row += &dl_dt.slice(s![start..start + self.dimensions]);
+        }
+
+        LayerGradients {
+            weights: weights_gradients,
+            biases: Array1::zeros(0),
+            // indices are not differentiable
+            input: Array1::zeros(input.len()),
+        }
+    }
+
+    /// Update only the rows that have a gradient, leaving the rest untouched
+    fn update(
+        &self,
+        weights: &mut Array2<F>,
+        _biases: &mut Array1<F>,
+        weights_gradients: &Array2<F>,
+        _biases_gradients: &Array1<F>,
+        learning_rate: F,
+    ) {
+        for (mut row, gradients) in weights.outer_iter_mut().zip(weights_gradients.outer_iter()) {
+            if gradients.iter().any(|&g| g != F::zero()) {
+                row.scaled_add(-learning_rate, &gradients);
+            }
+        }
+    }
+
+    fn constrain(&self, weights: &mut Array2<F>) {
+        if let Some(padding_index) = self.padding_index {
+            weights.row_mut(padding_index).fill(F::zero());
+        }
+
+        if let Some(max_norm) = self.max_norm {
+            for mut row in weights.axis_iter_mut(Axis(0)) {
+                let norm = row.dot(&row).sqrt();
+                if norm > max_norm {
+                    row *= max_norm / norm;
+                }
+            }
+        }
+    }
+}
+
+/// Embedding of a vocabulary of `vocabulary` indices into `dimensions` dimensions,
+/// see `Embedding::new`
+pub fn embedding<F: Float>(
+    vocabulary: usize,
+    dimensions: usize,
+    padding_index: Option<usize>,
+    max_norm: Option<F>,
+) -> Transfer<F> {
+    Transfer::new(Embedding::new(
+        vocabulary,
+        dimensions,
+        padding_index,
+        max_norm,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use crate::neuron::activations::{linear, sigmoid};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+
+    use super::*;
+
+    fn pretrained_layer(padding_index: Option<usize>, max_norm: Option<f32>) -> Layer {
+        let weights = array![[1., 1.], [2., 0.], [0., 3.], [4., 4.]];
+
+        Layer::from_parameters(
+            6,
+            3,
+            weights,
+            Array1::zeros(0),
+            embedding(4, 2, padding_index, max_norm),
+            linear(),
+        )
+    }
+
+    #[test]
+    fn test_embedding_lookup() {
+        let layer = pretrained_layer(Some(0), None);
+        assert_eq!(layer.get_weights().row(0), array![0., 0.]);
+        assert_eq!(
+            layer.forward(&array![2., 0., 1.]),
+            array![0., 3., 0., 0., 2., 0.]
+        );
+    }
+
+    #[test]
+    fn test_embedding_sparse_update_and_max_norm() {
+        let mut layer = pretrained_layer(Some(0), Some(5.));
+        // the norm of row 3 is larger than the max norm
+        assert!((layer.get_weights().row(3).dot(&layer.get_weights().row(3)) - 25.).abs() < 1e-5);
+
+        let cache = layer.forward_cached(&array![1., 0., 1.]);
+        let gradients = layer.backward(&cache, &array![1., 1., 1., 1., 0.5, -1.]);
+        assert_eq!(
+            gradients.weights,
+            array![[0., 0.], [1.5, 0.], [0., 0.], [0., 0.]]
+        );
+        assert_eq!(gradients.input, array![0., 0., 0.]);
+
+        let before = layer.get_weights().clone();
+        layer.update(&gradients.weights, &gradients.biases, 1.);
+        assert_eq!(layer.get_weights().row(1), array![0.5, 0.]);
+        assert_eq!(layer.get_weights().row(2), before.row(2));
+        assert_eq!(layer.get_weights().row(0), array![0., 0.]);
+    }
+
+    #[test]
+    fn test_embedding_network_learns_token_targets() {
+        // the target of each sequence is whether it contains token 3
+        let sequences = vec![
+            array![1., 2., 0.],
+            array![3., 1., 0.],
+            array![2., 2., 3.],
+            array![1., 1., 2.],
+            array![0., 3., 0.],
+            array![2., 1., 1.],
+        ];
+        let targets: Vec<Array1<f32>> = sequences
+            .iter()
+            .map(|s| array![if s.iter().any(|&t| t == 3.) { 1. } else { 0. }])
+            .collect();
+
+        let mut network = Network::builder(3)
+            .transfer(embedding(4, 4, Some(0), None), linear())
+            .dense(1, sigmoid())
+            .build()
+            .unwrap();
+        assert_eq!(network.shape(), vec![3, 12, 1]);
+
+        let optimizer = SGD::new(mse());
+        for _ in 0..2_000 {
+            optimizer.optimize_batch(&mut network, &sequences, &targets, 1.);
+        }
+
+        for (sequence, target) in sequences.iter().zip(targets.iter()) {
+            let prediction = network.predict(sequence);
+            assert!(
+                (prediction[0] - target[0]).abs() < 0.2,
+                "prediction {} for {} != {}",
+                prediction,
+                sequence,
+                target
+            );
+        }
+        assert_eq!(
+            network.get_layers()[0].get_weights().row(0),
+            array![0., 0., 0., 0.]
+        );
+    }
+}
@@ -0,0 +1,55 @@
+use ndarray::Array1;
+
+use crate::neuron::layers::Layer;
+
+/// Assert that a layer's back propagated gradients match finite differences of
+/// the loss `sum(output * coefficients)`
+pub(super) fn assert_gradients_match(layer: &Layer<f64>, input: &Array1<f64>) {
+    let coefficients = Array1::linspace(-1., 1., layer.output_size());
+    let loss =
+        |layer: &Layer<f64>, input: &Array1<f64>| (layer.forward(input) * &coefficients).sum();
+
+    let cache = layer.forward_cached(input);
+    let gradients = layer.backward(&cache, &coefficients);
+
+    let epsilon = 1e-6;
+    let assert_close = |numeric: f64, analytic: f64, name: &str| {
+        assert!(
+            (numeric - analytic).abs() < 1e-6,
+            "numeric {} gradient {} != analytic gradient {}",
+            name,
+            numeric,
+            analytic
+        );
+    };
+
+    for (index, &gradient) in gradients.weights.indexed_iter() {
+        let mut plus = layer.clone();
+        plus.get_weights_mut()[index] += epsilon;
+        let mut minus = layer.clone();
+        minus.get_weights_mut()[index] -= epsilon;
+
+        let numeric = (loss(&plus, input) - loss(&minus, input)) / (2. * epsilon);
+        assert_close(numeric, gradient, "weights");
+    }
+
+    for (index, &gradient) in gradients.biases.indexed_iter() {
+        let mut plus = layer.clone();
+        plus.get_biases_mut()[index] += epsilon;
+        let mut minus = layer.clone();
+        minus.get_biases_mut()[index] -= epsilon;
+
+        let numeric = (loss(&plus, input) - loss(&minus, input)) / (2. * epsilon);
+        assert_close(numeric, gradient, "biases");
+    }
+
+    for (index, &gradient) in gradients.input.indexed_iter() {
+        let mut plus = input.clone();
+        plus[index] += epsilon;
+        let mut minus = input.clone();
+        minus[index] -= epsilon;
+
+        let numeric = (loss(layer, &plus) - loss(layer, &minus)) / (2. * epsilon);
+        assert_close(numeric, gradient, "input");
+    }
+}
@@ -1,5 +1,19 @@
-pub use dense::{dense, dense_transfer};
-pub use transfer::Transfer;
+pub use conv1d::{conv1d, Conv1D, Padding};
+pub use conv_transpose2d::{conv_transpose2d, ConvTranspose2D};
+pub use crop2d::{center_crop2d, crop2d, Crop2D};
+pub use dense::{dense, dense_transfer, Dense};
+pub use embedding::{embedding, Embedding};
+pub use pooling1d::{avg_pool1d, max_pool1d, Pooling1D};
+pub use transfer::{Transfer, TransferFn};
+pub use upsample2d::{upsample2d, Interpolation, Upsample2D};
 
+mod conv1d;
+mod conv_transpose2d;
+mod crop2d;
 mod dense;
+mod embedding;
+#[cfg(test)]
+mod gradient_check;
+mod pooling1d;
 mod transfer;
+mod upsample2d;
@@ -0,0 +1,241 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// How a 1D pooling combines each window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pool {
+    Max,
+    Average,
+}
+
+/// 1D pooling over inputs of `channels` channels, flattened channel by channel
+///
+/// Each channel is pooled separately over windows of `pool_size` positions,
+/// `stride` positions apart. Pooling has no weights or biases.
+#[derive(Debug, Clone, Copy)]
+pub struct Pooling1D {
+    pool: Pool,
+    channels: usize,
+    pool_size: usize,
+    stride: usize,
+}
+
+impl Pooling1D {
+    fn new(pool: Pool, channels: usize, pool_size: usize, stride: usize) -> Self {
+        assert!(
+            channels > 0 && pool_size > 0 && stride > 0,
+            "channels, pool size and stride must be positive"
+        );
+
+        Self {
+            pool,
+            channels,
+            pool_size,
+            stride,
+        }
+    }
+
+    /// Max pooling, see `Pooling1D`
+    pub fn max(channels: usize, pool_size: usize, stride: usize) -> Self {
+        Self::new(Pool::Max, channels, pool_size, stride)
+    }
+
+    /// Average pooling, see `Pooling1D`
+    pub fn average(channels: usize, pool_size: usize, stride: usize) -> Self {
+        Self::new(Pool::Average, channels, pool_size, stride)
+    }
+
+    fn output_length(&self, length: usize) -> usize {
+        if length >= self.pool_size {
+            (length - self.pool_size) / self.stride + 1
+        } else {
+            0
+        }
+    }
+
+    /// Visit every window with its flat output index and the flat input indices
+    /// it covers
+    fn for_each_window(&self, inputs: usize, mut visit: impl FnMut(usize, Vec<usize>)) {
+        let length = inputs / self.channels;
+        let output_length = self.output_length(length);
+        for channel in 0..self.channels {
+            for position in 0..output_length {
+                let start = channel * length + position * self.stride;
+                visit(
+                    channel * output_length + position,
+                    (start..start + self.pool_size).collect(),
+                );
+            }
+        }
+    }
+
+    /// Index of the largest input in a window, the first one if there are ties
+    fn argmax<F: Float>(input: &Array1<F>, window: &[usize]) -> usize {
+        window.iter().copied().fold(
+            window[0],
+            |max, i| if input[i] > input[max] { i } else { max },
+        )
+    }
+}
+
+impl<F: Float> TransferFn<F> for Pooling1D {
+    fn name(&self) -> &'static str {
+        match self.pool {
+            Pool::Max => "max_pool1d",
+            Pool::Average => "avg_pool1d",
+        }
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        0
+    }
+
+    /// Inputs must split into the channels, at least a window long
+    fn accepts_inputs(&self, inputs: usize) -> bool {
+        inputs.is_multiple_of(self.channels) && inputs / self.channels >= self.pool_size
+    }
+
+    fn output_size(&self, inputs: usize) -> Option<usize> {
+        Some(self.channels * self.output_length(inputs / self.channels))
+    }
+
+    fn transfer(&self, _weights: &Array2<F>, _biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        let mut output = Array1::zeros(TransferFn::<F>::output_size(self, input.len()).unwrap());
+        let size = F::from(self.pool_size).unwrap();
+        self.for_each_window(input.len(), |o, window| {
+            output[o] = match self.pool {
+                Pool::Max => input[Self::argmax(input, &window)],
+                Pool::Average => window.iter().fold(F::zero(), |total, &i| total + input[i]) / size,
+            };
+        });
+
+        output
+    }
+
+    fn backward(
+        &self,
+        _weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        let mut input_gradients = Array1::zeros(input.len());
+        let size = F::from(self.pool_size).unwrap();
+        self.for_each_window(input.len(), |o, window| match self.pool {
+            // only the largest input of each window affects the output
+            Pool::Max => input_gradients[Self::argmax(input, &window)] += dl_dt[o],
+            Pool::Average => {
+                for i in window {
+                    input_gradients[i] += dl_dt[o] / size;
+                }
+            }
+        });
+
+        LayerGradients {
+            weights: Array2::zeros((0, 0)),
+            biases: Array1::zeros(0),
+            input: input_gradients,
+        }
+    }
+}
+
+/// 1D max pooling, see `Pooling1D`
+pub fn max_pool1d<F: Float>(channels: usize, pool_size: usize, stride: usize) -> Transfer<F> {
+    Transfer::new(Pooling1D::max(channels, pool_size, stride))
+}
+
+/// 1D average pooling, see `Pooling1D`
+pub fn avg_pool1d<F: Float>(channels: usize, pool_size: usize, stride: usize) -> Transfer<F> {
+    Transfer::new(Pooling1D::average(channels, pool_size, stride))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::{linear, sigmoid, tanh};
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::transfers::gradient_check::assert_gradients_match;
+    use crate::neuron::transfers::{conv1d, Padding};
+
+    use super::*;
+
+    fn pooling_layer(transfer: Transfer<f64>, inputs: usize) -> Layer<f64> {
+        let outputs = transfer.output_size(inputs).unwrap();
+
+        Layer::new(outputs, inputs, transfer, linear())
+    }
+
+    #[test]
+    fn test_pooling1d_transfer() {
+        // 2 channels of length 5
+        let input = array![1., 3., 2., 0., 5., -1., -2., 4., 4., 0.];
+
+        let layer = pooling_layer(max_pool1d(2, 2, 2), 10);
+        assert_eq!(layer.forward(&input), array![3., 2., -1., 4.]);
+
+        let layer = pooling_layer(avg_pool1d(2, 3, 1), 10);
+        assert_eq!(
+            layer.forward(&input),
+            array![2., 5. / 3., 7. / 3., 1. / 3., 2., 8. / 3.]
+        );
+    }
+
+    #[test]
+    fn test_pooling1d_gradients() {
+        // distinct values, so the max is differentiable
+        let input = array![0.3, -0.5, 0.9, 0.1, -0.2, 0.7, -0.8, 0.4, 0.6, -0.1, 0.2, 0.5];
+        for transfer in [
+            max_pool1d(2, 2, 2),
+            max_pool1d(3, 3, 1),
+            avg_pool1d(2, 3, 2),
+            avg_pool1d(1, 4, 3),
+        ] {
+            assert_gradients_match(&pooling_layer(transfer, 12), &input);
+        }
+    }
+
+    #[test]
+    fn test_temporal_convolution_network_learns_peak_position() {
+        // the target of each sequence is whether its peak is in the second half
+        let sequences: Vec<Array1<f32>> = (0..8)
+            .map(|peak| Array1::from_shape_fn(8, |i| if i == peak { 1. } else { 0. }))
+            .collect();
+        let targets: Vec<Array1<f32>> = (0..8)
+            .map(|peak| array![if peak >= 4 { 1. } else { 0. }])
+            .collect();
+
+        let mut network = Network::builder(8)
+            .transfer(conv1d(1, 4, 3, 1, 2, Padding::Causal), tanh())
+            .transfer(max_pool1d(4, 4, 4), linear())
+            .dense(1, sigmoid())
+            .build()
+            .unwrap();
+        assert_eq!(network.shape(), vec![8, 32, 8, 1]);
+
+        let optimizer = SGD::new(mse());
+        for _ in 0..3_000 {
+            optimizer.optimize_batch(&mut network, &sequences, &targets, 1.);
+        }
+
+        for (sequence, target) in sequences.iter().zip(targets.iter()) {
+            let prediction = network.predict(sequence);
+            assert!(
+                (prediction[0] - target[0]).abs() < 0.3,
+                "prediction {} for {} != {}",
+                prediction,
+                sequence,
+                target
+            );
+        }
+    }
+}
@@ -1,31 +1,142 @@
 use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
 
 use ndarray::{Array1, Array2};
 
-pub type TransferFn = fn(&Array2<f32>, &Array1<f32>, &Array1<f32>) -> Array1<f32>;
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::Float;
 
-#[derive(Copy, Clone)]
-pub struct Transfer {
-    transfer_fn: TransferFn,
+/// The function a `Layer` applies to its input, weights and biases before its
+/// activation
+pub trait TransferFn<F: Float = f32>: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Shape of the weights of a layer with `inputs` inputs and `outputs` outputs
+    fn weights_shape(&self, inputs: usize, outputs: usize) -> (usize, usize) {
+        (outputs, inputs)
+    }
+
+    /// Size of the biases of a layer with `inputs` inputs and `outputs` outputs
+    fn biases_size(&self, _inputs: usize, outputs: usize) -> usize {
+        outputs
+    }
+
+    /// Whether a layer can have `inputs` inputs, e.g. inputs that split into the
+    /// transfer's channels
+    fn accepts_inputs(&self, _inputs: usize) -> bool {
+        true
+    }
+
+    /// Output size of a layer with `inputs` inputs, `None` if the transfer works
+    /// with any output size. Only called with inputs the transfer accepts.
+    fn output_size(&self, _inputs: usize) -> Option<usize> {
+        None
+    }
+
+    fn transfer(&self, weights: &Array2<F>, biases: &Array1<F>, input: &Array1<F>) -> Array1<F>;
+
+    /// Back propagate the derivatives of the loss with respect to the transfers
+    fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F>;
+
+    /// Take a gradient descent step
+    fn update(
+        &self,
+        weights: &mut Array2<F>,
+        biases: &mut Array1<F>,
+        weights_gradients: &Array2<F>,
+        biases_gradients: &Array1<F>,
+        learning_rate: F,
+    ) {
+        weights.scaled_add(-learning_rate, weights_gradients);
+        biases.scaled_add(-learning_rate, biases_gradients);
+    }
+
+    /// Restrict the weights after they are initialized or updated
+    fn constrain(&self, _weights: &mut Array2<F>) {}
+}
+
+#[derive(Clone)]
+pub struct Transfer<F: Float = f32> {
+    transfer_fn: Arc<dyn TransferFn<F>>,
 }
 
-impl Debug for Transfer {
+impl<F: Float> Debug for Transfer<F> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Transfer").finish()
+        f.debug_struct("Transfer")
+            .field("name", &self.name())
+            .finish()
     }
 }
 
-impl Transfer {
-    pub fn new(transfer_fn: TransferFn) -> Self {
-        Self { transfer_fn }
+impl<F: Float> Transfer<F> {
+    pub fn new<T: TransferFn<F> + 'static>(transfer_fn: T) -> Self {
+        Self {
+            transfer_fn: Arc::new(transfer_fn),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.transfer_fn.name()
+    }
+
+    pub fn weights_shape(&self, inputs: usize, outputs: usize) -> (usize, usize) {
+        self.transfer_fn.weights_shape(inputs, outputs)
+    }
+
+    pub fn biases_size(&self, inputs: usize, outputs: usize) -> usize {
+        self.transfer_fn.biases_size(inputs, outputs)
+    }
+
+    pub fn accepts_inputs(&self, inputs: usize) -> bool {
+        self.transfer_fn.accepts_inputs(inputs)
+    }
+
+    pub fn output_size(&self, inputs: usize) -> Option<usize> {
+        self.transfer_fn.output_size(inputs)
     }
 
     pub fn transfer(
         &self,
-        weights: &Array2<f32>,
-        biases: &Array1<f32>,
-        inputs: &Array1<f32>,
-    ) -> Array1<f32> {
-        (self.transfer_fn)(weights, biases, inputs)
+        weights: &Array2<F>,
+        biases: &Array1<F>,
+        inputs: &Array1<F>,
+    ) -> Array1<F> {
+        self.transfer_fn.transfer(weights, biases, inputs)
+    }
+
+    pub fn backward(
+        &self,
+        weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        self.transfer_fn.backward(weights, input, dl_dt)
+    }
+
+    pub fn update(
+        &self,
+        weights: &mut Array2<F>,
+        biases: &mut Array1<F>,
+        weights_gradients: &Array2<F>,
+        biases_gradients: &Array1<F>,
+        learning_rate: F,
+    ) {
+        self.transfer_fn.update(
+            weights,
+            biases,
+            weights_gradients,
+            biases_gradients,
+            learning_rate,
+        );
+        self.transfer_fn.constrain(weights);
+    }
+
+    pub fn constrain(&self, weights: &mut Array2<F>) {
+        self.transfer_fn.constrain(weights)
     }
 }
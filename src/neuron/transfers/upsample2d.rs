@@ -0,0 +1,215 @@
+use ndarray::{Array1, Array2};
+
+use crate::neuron::layers::LayerGradients;
+use crate::neuron::transfers::{Transfer, TransferFn};
+use crate::neuron::Float;
+
+/// How an `Upsample2D` computes the values between input positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Repeat each input value
+    Nearest,
+    /// Interpolate linearly between the 4 nearest input values, treating each
+    /// value as the center of its cell
+    Bilinear,
+}
+
+/// Upsamples each of `channels` channels of shape `input_shape` by integer
+/// `scale` factors
+///
+/// Inputs and outputs are flattened channel by channel, each channel row by
+/// row. Upsampling has no weights or biases.
+#[derive(Debug, Clone, Copy)]
+pub struct Upsample2D {
+    channels: usize,
+    input_shape: (usize, usize),
+    scale: (usize, usize),
+    interpolation: Interpolation,
+}
+
+impl Upsample2D {
+    pub fn new(
+        channels: usize,
+        input_shape: (usize, usize),
+        scale: (usize, usize),
+        interpolation: Interpolation,
+    ) -> Self {
+        assert!(
+            channels > 0 && input_shape.0 > 0 && input_shape.1 > 0,
+            "channels and input shape must be positive"
+        );
+        assert!(scale.0 > 0 && scale.1 > 0, "scale must be positive");
+
+        Self {
+            channels,
+            input_shape,
+            scale,
+            interpolation,
+        }
+    }
+
+    /// Shape of each output channel
+    pub fn output_shape(&self) -> (usize, usize) {
+        (
+            self.input_shape.0 * self.scale.0,
+            self.input_shape.1 * self.scale.1,
+        )
+    }
+
+    /// Input positions, and their interpolation weights, of output position
+    /// `output` along an axis of `size` inputs upsampled by `scale`
+    fn sources<F: Float>(&self, output: usize, size: usize, scale: usize) -> Vec<(usize, F)> {
+        match self.interpolation {
+            Interpolation::Nearest => vec![(output / scale, F::one())],
+            Interpolation::Bilinear => {
+                let half = F::from(0.5).unwrap();
+                let position = ((F::from(output).unwrap() + half) / F::from(scale).unwrap() - half)
+                    .max(F::zero());
+                let low = position.floor().to_usize().unwrap().min(size - 1);
+                let high = (low + 1).min(size - 1);
+                let fraction = position - F::from(low).unwrap();
+
+                vec![(low, F::one() - fraction), (high, fraction)]
+            }
+        }
+    }
+
+    /// Visit every (output, input) pair with the weight of the input in the
+    /// output, by flat indices
+    fn for_each_tap<F: Float>(&self, mut visit: impl FnMut(usize, usize, F)) {
+        let (height, width) = self.input_shape;
+        let (output_height, output_width) = self.output_shape();
+        for channel in 0..self.channels {
+            for y in 0..output_height {
+                let rows = self.sources::<F>(y, height, self.scale.0);
+                for x in 0..output_width {
+                    let output = (channel * output_height + y) * output_width + x;
+                    for &(source_x, weight_x) in &self.sources::<F>(x, width, self.scale.1) {
+                        for &(source_y, weight_y) in &rows {
+                            let input = (channel * height + source_y) * width + source_x;
+                            visit(output, input, weight_y * weight_x);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<F: Float> TransferFn<F> for Upsample2D {
+    fn name(&self) -> &'static str {
+        match self.interpolation {
+            Interpolation::Nearest => "upsample2d_nearest",
+            Interpolation::Bilinear => "upsample2d_bilinear",
+        }
+    }
+
+    fn weights_shape(&self, _inputs: usize, _outputs: usize) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn biases_size(&self, _inputs: usize, _outputs: usize) -> usize {
+        0
+    }
+
+    /// Inputs must match the channels and input shape
+    fn accepts_inputs(&self, inputs: usize) -> bool {
+        inputs == self.channels * self.input_shape.0 * self.input_shape.1
+    }
+
+    fn output_size(&self, _inputs: usize) -> Option<usize> {
+        let (height, width) = self.output_shape();
+
+        Some(self.channels * height * width)
+    }
+
+    fn transfer(&self, _weights: &Array2<F>, _biases: &Array1<F>, input: &Array1<F>) -> Array1<F> {
+        let (height, width) = self.output_shape();
+
+        let mut output = Array1::zeros(self.channels * height * width);
+        self.for_each_tap(|o, i, weight: F| output[o] += weight * input[i]);
+
+        output
+    }
+
+    fn backward(
+        &self,
+        _weights: &Array2<F>,
+        input: &Array1<F>,
+        dl_dt: &Array1<F>,
+    ) -> LayerGradients<F> {
+        let mut input_gradients = Array1::zeros(input.len());
+        self.for_each_tap(|o, i, weight: F| input_gradients[i] += weight * dl_dt[o]);
+
+        LayerGradients {
+            weights: Array2::zeros((0, 0)),
+            biases: Array1::zeros(0),
+            input: input_gradients,
+        }
+    }
+}
+
+/// 2D upsampling, see `Upsample2D`
+pub fn upsample2d<F: Float>(
+    channels: usize,
+    input_shape: (usize, usize),
+    scale: (usize, usize),
+    interpolation: Interpolation,
+) -> Transfer<F> {
+    Transfer::new(Upsample2D::new(channels, input_shape, scale, interpolation))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::activations::linear;
+    use crate::neuron::layers::Layer;
+    use crate::neuron::transfers::gradient_check::assert_gradients_match;
+
+    use super::*;
+
+    fn upsample_layer(
+        channels: usize,
+        input_shape: (usize, usize),
+        scale: (usize, usize),
+        interpolation: Interpolation,
+    ) -> Layer<f64> {
+        let transfer = upsample2d(channels, input_shape, scale, interpolation);
+        let inputs = channels * input_shape.0 * input_shape.1;
+
+        Layer::new(
+            transfer.output_size(inputs).unwrap(),
+            inputs,
+            transfer,
+            linear(),
+        )
+    }
+
+    #[test]
+    fn test_upsample2d_transfer() {
+        let input = array![1., 2., 3., 4.];
+
+        let layer = upsample_layer(1, (2, 2), (2, 1), Interpolation::Nearest);
+        assert_eq!(
+            layer.forward(&input),
+            array![1., 2., 1., 2., 3., 4., 3., 4.]
+        );
+
+        let layer = upsample_layer(1, (2, 2), (2, 2), Interpolation::Bilinear);
+        assert_eq!(
+            layer.forward(&input),
+            array![
+                1., 1.25, 1.75, 2., 1.5, 1.75, 2.25, 2.5, 2.5, 2.75, 3.25, 3.5, 3., 3.25, 3.75, 4.
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upsample2d_gradients() {
+        let input = Array1::linspace(-1., 1., 12);
+        for &interpolation in &[Interpolation::Nearest, Interpolation::Bilinear] {
+            assert_gradients_match(&upsample_layer(2, (2, 3), (3, 2), interpolation), &input);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub use parameter_space::{Configuration, Domain, ParameterSpace, Scale, Value};
+pub use search::{grid_search, hyperband, random_search, successive_halving};
+pub use search_results::{SearchResults, Trial};
+
+mod parameter_space;
+mod search;
+mod search_results;
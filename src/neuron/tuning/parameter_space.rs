@@ -0,0 +1,317 @@
+use std::fmt::{Display, Formatter};
+
+use ndarray_rand::rand::Rng;
+
+/// A hyperparameter value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Real(f64),
+    Integer(i64),
+    Text(String),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Real(value) => write!(f, "{:.4e}", value),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+/// How values are spread over a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    /// Evenly spread orders of magnitude, e.g. for learning rates
+    Log,
+}
+
+/// Values a hyperparameter can take
+#[derive(Debug, Clone, PartialEq)]
+pub enum Domain {
+    Choice(Vec<Value>),
+    Integer { low: i64, high: i64, scale: Scale },
+    Real { low: f64, high: f64, scale: Scale },
+}
+
+impl Domain {
+    fn sample<R: Rng>(&self, rng: &mut R) -> Value {
+        match self {
+            Domain::Choice(values) => values[rng.gen_range(0..values.len())].clone(),
+            Domain::Integer { low, high, scale } => {
+                // sample the real range widened by half a step, so the ends are as
+                // likely as the other values
+                let value = sample_real(*low as f64 - 0.5, *high as f64 + 0.5, *scale, rng);
+                Value::Integer((value.round() as i64).max(*low).min(*high))
+            }
+            Domain::Real { low, high, scale } => Value::Real(sample_real(*low, *high, *scale, rng)),
+        }
+    }
+
+    /// `resolution` values spread over the domain, or all of its values if there
+    /// are fewer
+    fn grid(&self, resolution: usize) -> Vec<Value> {
+        match self {
+            Domain::Choice(values) => values.clone(),
+            Domain::Integer { low, high, scale } => {
+                if ((high - low) as usize) < resolution {
+                    return (*low..=*high).map(Value::Integer).collect();
+                }
+
+                let mut values: Vec<Value> = spread(*low as f64, *high as f64, *scale, resolution)
+                    .into_iter()
+                    .map(|value| Value::Integer(value.round() as i64))
+                    .collect();
+                values.dedup();
+
+                values
+            }
+            Domain::Real { low, high, scale } => spread(*low, *high, *scale, resolution)
+                .into_iter()
+                .map(Value::Real)
+                .collect(),
+        }
+    }
+}
+
+fn sample_real<R: Rng>(low: f64, high: f64, scale: Scale, rng: &mut R) -> f64 {
+    match scale {
+        Scale::Linear => rng.gen_range(low..=high),
+        Scale::Log => rng.gen_range(low.ln()..=high.ln()).exp(),
+    }
+}
+
+fn spread(low: f64, high: f64, scale: Scale, count: usize) -> Vec<f64> {
+    if count == 1 {
+        return vec![low];
+    }
+
+    let (start, end) = match scale {
+        Scale::Linear => (low, high),
+        Scale::Log => (low.ln(), high.ln()),
+    };
+    (0..count)
+        .map(|i| start + (end - start) * i as f64 / (count - 1) as f64)
+        .map(|value| match scale {
+            Scale::Linear => value,
+            Scale::Log => value.exp(),
+        })
+        .collect()
+}
+
+/// Named hyperparameters and their domains, searched in order of definition
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterSpace {
+    parameters: Vec<(String, Domain)>,
+}
+
+impl ParameterSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a hyperparameter
+    ///
+    /// Panics if the name is already defined or the domain is empty.
+    pub fn parameter(mut self, name: &str, domain: Domain) -> Self {
+        assert!(
+            self.parameters.iter().all(|(n, _)| n != name),
+            "parameter {} defined twice",
+            name
+        );
+        match &domain {
+            Domain::Choice(values) => assert!(!values.is_empty(), "no choices for {}", name),
+            Domain::Integer { low, high, scale } => {
+                assert!(low <= high, "empty range for {}", name);
+                assert!(*scale == Scale::Linear || *low > 0, "log range of {}", name);
+            }
+            Domain::Real { low, high, scale } => {
+                assert!(low <= high, "empty range for {}", name);
+                assert!(
+                    *scale == Scale::Linear || *low > 0.,
+                    "log range of {}",
+                    name
+                );
+            }
+        }
+
+        self.parameters.push((name.to_string(), domain));
+        self
+    }
+
+    pub fn choice(self, name: &str, values: Vec<Value>) -> Self {
+        self.parameter(name, Domain::Choice(values))
+    }
+
+    pub fn integer(self, name: &str, low: i64, high: i64, scale: Scale) -> Self {
+        self.parameter(name, Domain::Integer { low, high, scale })
+    }
+
+    pub fn real(self, name: &str, low: f64, high: f64, scale: Scale) -> Self {
+        self.parameter(name, Domain::Real { low, high, scale })
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.parameters
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// A configuration with a random value of each hyperparameter
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Configuration {
+        Configuration {
+            values: self
+                .parameters
+                .iter()
+                .map(|(name, domain)| (name.clone(), domain.sample(rng)))
+                .collect(),
+        }
+    }
+
+    /// Every combination of `resolution` values of each range and all choices
+    pub fn grid(&self, resolution: usize) -> Vec<Configuration> {
+        assert!(resolution > 0, "resolution must be positive");
+
+        let mut configurations = vec![Configuration { values: vec![] }];
+        for (name, domain) in &self.parameters {
+            let values = domain.grid(resolution);
+            configurations = configurations
+                .into_iter()
+                .flat_map(|configuration| {
+                    values.iter().map(move |value| {
+                        let mut configuration = configuration.clone();
+                        configuration.values.push((name.clone(), value.clone()));
+                        configuration
+                    })
+                })
+                .collect();
+        }
+
+        configurations
+    }
+}
+
+/// A value for each hyperparameter of a `ParameterSpace`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Configuration {
+    values: Vec<(String, Value)>,
+}
+
+impl Configuration {
+    pub fn values(&self) -> &[(String, Value)] {
+        &self.values
+    }
+
+    /// Panics if there is no such hyperparameter
+    pub fn get(&self, name: &str) -> &Value {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+            .unwrap_or_else(|| panic!("no parameter {}", name))
+    }
+
+    /// Panics if the hyperparameter isn't a number
+    pub fn real(&self, name: &str) -> f64 {
+        match self.get(name) {
+            Value::Real(value) => *value,
+            Value::Integer(value) => *value as f64,
+            Value::Text(_) => panic!("parameter {} isn't a number", name),
+        }
+    }
+
+    /// Panics if the hyperparameter isn't an integer
+    pub fn integer(&self, name: &str) -> i64 {
+        match self.get(name) {
+            Value::Integer(value) => *value,
+            _ => panic!("parameter {} isn't an integer", name),
+        }
+    }
+
+    /// Panics if the hyperparameter isn't a non-negative integer
+    pub fn usize(&self, name: &str) -> usize {
+        let value = self.integer(name);
+        assert!(value >= 0, "parameter {} is negative", name);
+
+        value as usize
+    }
+
+    /// Panics if the hyperparameter isn't text
+    pub fn text(&self, name: &str) -> &str {
+        match self.get(name) {
+            Value::Text(value) => value,
+            _ => panic!("parameter {} isn't text", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray_rand::rand::thread_rng;
+
+    use super::*;
+
+    fn space() -> ParameterSpace {
+        ParameterSpace::new()
+            .real("learning_rate", 1e-3, 1e-1, Scale::Log)
+            .integer("batch_size", 8, 64, Scale::Log)
+            .integer("hidden", 1, 3, Scale::Linear)
+            .choice("activation", vec!["relu".into(), "sigmoid".into()])
+    }
+
+    #[test]
+    fn test_grid() {
+        let grid = space().grid(3);
+        assert_eq!(grid.len(), 3 * 3 * 3 * 2);
+
+        let learning_rates: Vec<f64> = grid.iter().map(|c| c.real("learning_rate")).collect();
+        assert!((learning_rates[0] - 1e-3).abs() < 1e-12);
+        assert!((learning_rates[grid.len() - 1] - 1e-1).abs() < 1e-12);
+        assert!((learning_rates[grid.len() / 2] - 1e-2).abs() < 1e-12);
+
+        // the last parameter changes fastest
+        let batch_sizes: Vec<usize> = grid[..6].iter().map(|c| c.usize("batch_size")).collect();
+        assert_eq!(&batch_sizes[..], &[8; 6]);
+        assert_eq!(grid[6].usize("batch_size"), 23);
+        assert_eq!(grid[12].usize("batch_size"), 64);
+        assert_eq!(grid[1].text("activation"), "sigmoid");
+        assert_eq!(grid[2].integer("hidden"), 2);
+    }
+
+    #[test]
+    fn test_samples_are_in_domain() {
+        let space = space();
+        let mut rng = thread_rng();
+        let mut hidden = [0; 3];
+        for _ in 0..300 {
+            let configuration = space.sample(&mut rng);
+            assert!((1e-3..=1e-1).contains(&configuration.real("learning_rate")));
+            assert!((8..=64).contains(&configuration.usize("batch_size")));
+            assert!(["relu", "sigmoid"].contains(&configuration.text("activation")));
+            hidden[configuration.usize("hidden") - 1] += 1;
+        }
+
+        assert!(hidden.iter().all(|&count| count > 50), "{:?}", hidden);
+    }
+}
@@ -0,0 +1,336 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use ndarray_rand::rand::thread_rng;
+
+use crate::neuron::tuning::{Configuration, ParameterSpace, SearchResults, Trial};
+
+/// Score every configuration of the space's grid with `resolution` values of
+/// each range, on `threads` threads
+///
+/// The objective returns a score to minimize, e.g. a validation loss. Negate it
+/// to maximize a score such as an accuracy or a reward.
+pub fn grid_search<O>(
+    space: &ParameterSpace,
+    resolution: usize,
+    threads: usize,
+    objective: O,
+) -> SearchResults
+where
+    O: Fn(&Configuration) -> f64 + Sync,
+{
+    let trials = run_trials(space.grid(resolution), 0, threads, |configuration, _| {
+        objective(configuration)
+    });
+
+    SearchResults::new(trials)
+}
+
+/// Score `trials` configurations sampled from the space, on `threads` threads
+///
+/// See `grid_search`.
+pub fn random_search<O>(
+    space: &ParameterSpace,
+    trials: usize,
+    threads: usize,
+    objective: O,
+) -> SearchResults
+where
+    O: Fn(&Configuration) -> f64 + Sync,
+{
+    let mut rng = thread_rng();
+    let configurations = (0..trials).map(|_| space.sample(&mut rng)).collect();
+    let trials = run_trials(configurations, 0, threads, |configuration, _| {
+        objective(configuration)
+    });
+
+    SearchResults::new(trials)
+}
+
+/// Score `configurations` sampled configurations with `min_budget`, then keep
+/// scoring the best `1 / eta` of them with `eta` times the budget, until the
+/// budget reaches `max_budget` or a single configuration is left
+///
+/// The objective gets a configuration and a budget, e.g. epochs to train for,
+/// and returns a score to minimize. Each configuration's trial with its largest
+/// budget is kept.
+pub fn successive_halving<O>(
+    space: &ParameterSpace,
+    configurations: usize,
+    min_budget: usize,
+    max_budget: usize,
+    eta: usize,
+    threads: usize,
+    objective: O,
+) -> SearchResults
+where
+    O: Fn(&Configuration, usize) -> f64 + Sync,
+{
+    assert!(eta >= 2, "eta must be at least 2");
+    assert!(
+        min_budget > 0 && min_budget <= max_budget,
+        "budgets must be positive and ordered"
+    );
+
+    let mut rng = thread_rng();
+    let configurations = (0..configurations)
+        .map(|_| space.sample(&mut rng))
+        .collect();
+
+    SearchResults::new(halve(
+        configurations,
+        min_budget,
+        max_budget,
+        eta,
+        threads,
+        &objective,
+    ))
+}
+
+/// Run successive halving brackets trading off the amount of configurations
+/// against their starting budget, from many configurations with a budget of 1
+/// to a few with `max_budget`
+///
+/// See `successive_halving`.
+pub fn hyperband<O>(
+    space: &ParameterSpace,
+    max_budget: usize,
+    eta: usize,
+    threads: usize,
+    objective: O,
+) -> SearchResults
+where
+    O: Fn(&Configuration, usize) -> f64 + Sync,
+{
+    assert!(eta >= 2, "eta must be at least 2");
+    assert!(max_budget > 0, "max budget must be positive");
+
+    // amount of halvings of the most aggressive bracket
+    let mut brackets = 0;
+    while eta.pow(brackets + 1) <= max_budget {
+        brackets += 1;
+    }
+
+    let mut rng = thread_rng();
+    let mut trials = vec![];
+    for s in (0..=brackets).rev() {
+        let configurations = ((brackets + 1) as usize * eta.pow(s)).div_ceil(s as usize + 1);
+        let min_budget = (max_budget / eta.pow(s)).max(1);
+
+        let configurations = (0..configurations)
+            .map(|_| space.sample(&mut rng))
+            .collect();
+        trials.extend(halve(
+            configurations,
+            min_budget,
+            max_budget,
+            eta,
+            threads,
+            &objective,
+        ));
+    }
+
+    SearchResults::new(trials)
+}
+
+/// Successive halving of the configurations, keeping each one's last trial
+fn halve<O>(
+    mut configurations: Vec<Configuration>,
+    min_budget: usize,
+    max_budget: usize,
+    eta: usize,
+    threads: usize,
+    objective: &O,
+) -> Vec<Trial>
+where
+    O: Fn(&Configuration, usize) -> f64 + Sync,
+{
+    let mut stopped = vec![];
+    let mut budget = min_budget;
+    loop {
+        let mut trials = SearchResults::new(run_trials(configurations, budget, threads, objective))
+            .trials()
+            .to_vec();
+
+        let survivors = trials.len() / eta;
+        if budget >= max_budget || survivors == 0 {
+            stopped.extend(trials);
+            return stopped;
+        }
+
+        configurations = trials
+            .drain(..survivors)
+            .map(|trial| trial.configuration)
+            .collect();
+        stopped.extend(trials);
+        budget = (budget * eta).min(max_budget);
+    }
+}
+
+/// Score the configurations with the budget, each thread taking the next
+/// configuration once it is done with its previous one
+fn run_trials<O>(
+    configurations: Vec<Configuration>,
+    budget: usize,
+    threads: usize,
+    objective: O,
+) -> Vec<Trial>
+where
+    O: Fn(&Configuration, usize) -> f64 + Sync,
+{
+    assert!(threads > 0, "threads must be positive");
+
+    let next = AtomicUsize::new(0);
+    let trials = Mutex::new(Vec::with_capacity(configurations.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads.min(configurations.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let configuration = match configurations.get(i) {
+                    Some(configuration) => configuration,
+                    None => break,
+                };
+
+                let score = objective(configuration, budget);
+                trials.lock().unwrap().push(Trial {
+                    configuration: configuration.clone(),
+                    budget,
+                    score,
+                });
+            });
+        }
+    });
+
+    trials.into_inner().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::prelude::*;
+
+    use crate::neuron::activations::{relu, sigmoid};
+    use crate::neuron::datasets::{DataLoader, InMemoryDataset};
+    use crate::neuron::losses::mse;
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::{Optimizer, SGD};
+    use crate::neuron::tuning::Scale;
+    use crate::neuron::validation::evaluate;
+
+    use super::*;
+
+    /// Distance from a known optimum, with less noise for larger budgets
+    fn quadratic(configuration: &Configuration, budget: usize) -> f64 {
+        let x = configuration.real("x");
+        let n = configuration.integer("n") as f64;
+
+        (x - 0.3).powi(2) + (n - 4.).powi(2) + 1. / (budget + 1) as f64
+    }
+
+    fn space() -> ParameterSpace {
+        ParameterSpace::new()
+            .real("x", 0., 1., Scale::Linear)
+            .integer("n", 0, 8, Scale::Linear)
+    }
+
+    #[test]
+    fn test_grid_search_finds_optimum() {
+        let results = grid_search(&space(), 11, 4, |c| quadratic(c, 0));
+        assert_eq!(results.trials().len(), 11 * 9);
+
+        let best = results.best();
+        assert!((best.configuration.real("x") - 0.3).abs() < 1e-9);
+        assert_eq!(best.configuration.integer("n"), 4);
+        assert!(results
+            .trials()
+            .windows(2)
+            .all(|pair| pair[0].score <= pair[1].score));
+
+        let table = results.to_string();
+        assert!(table.starts_with("rank"));
+        assert!(table.contains(" x "));
+        assert_eq!(table.lines().count(), 11 * 9 + 3);
+    }
+
+    #[test]
+    fn test_random_search() {
+        let results = random_search(&space(), 50, 3, |c| quadratic(c, 0));
+        assert_eq!(results.trials().len(), 50);
+        assert!(results.best().score < 2.5);
+    }
+
+    #[test]
+    fn test_successive_halving_budgets() {
+        let evaluations = AtomicUsize::new(0);
+        let results = successive_halving(&space(), 27, 1, 9, 3, 4, |c, budget| {
+            evaluations.fetch_add(budget, Ordering::SeqCst);
+            quadratic(c, budget)
+        });
+
+        // 27 configurations with budget 1, 9 with 3 and 3 with 9
+        assert_eq!(evaluations.into_inner(), 27 + 9 * 3 + 3 * 9);
+        assert_eq!(results.trials().len(), 27);
+        let budgets: Vec<usize> = results.trials().iter().map(|t| t.budget).collect();
+        assert_eq!(&budgets[..3], &[9; 3]);
+        assert_eq!(&budgets[3..9], &[3; 6]);
+        assert_eq!(budgets[9], 1);
+
+        // survivors were the best with the smallest budget
+        let survivor_score = quadratic(&results.best().configuration, 1);
+        assert!(results.trials()[9..]
+            .iter()
+            .all(|trial| trial.score >= survivor_score));
+    }
+
+    #[test]
+    fn test_hyperband() {
+        let results = hyperband(&space(), 9, 3, 4, quadratic);
+
+        // brackets of 9, 5 and 3 configurations
+        assert_eq!(results.trials().len(), 9 + 5 + 3);
+        assert_eq!(results.best().budget, 9);
+        assert!(results.trials().iter().all(|t| t.budget >= 1));
+    }
+
+    #[test]
+    fn test_tune_training() {
+        let inputs: Vec<Array1<f32>> = (0..16).map(|i| array![i as f32 / 16.]).collect();
+        let expected: Vec<Array1<f32>> = inputs.iter().map(|x| x.mapv(|x| x * 0.5)).collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+
+        let space = ParameterSpace::new()
+            .real("learning_rate", 1e-4, 1., Scale::Log)
+            .integer("batch_size", 2, 8, Scale::Log)
+            .choice("activation", vec!["relu".into(), "sigmoid".into()]);
+
+        let results = successive_halving(&space, 8, 1, 4, 2, 4, |c, epochs| {
+            let activation = match c.text("activation") {
+                "relu" => relu(),
+                _ => sigmoid(),
+            };
+            let mut network: Network = Network::builder(1)
+                .dense(4, activation)
+                .dense(1, sigmoid())
+                .build()
+                .unwrap();
+            let optimizer = SGD::with_threads(mse(), 1);
+            let loader = DataLoader::new(dataset.clone(), c.usize("batch_size"), true, false);
+            for _ in 0..epochs {
+                for (batch_inputs, batch_expected) in loader.batches() {
+                    let learning_rate = c.real("learning_rate") as f32;
+                    optimizer.optimize_batch(
+                        &mut network,
+                        &batch_inputs,
+                        &batch_expected,
+                        learning_rate,
+                    );
+                }
+            }
+
+            evaluate(&network, &dataset, &mse()).loss as f64
+        });
+
+        assert_eq!(results.best().budget, 4);
+        assert!(results.best().score.is_finite());
+    }
+}
@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+use crate::neuron::tuning::Configuration;
+
+/// Score of a configuration trained with a budget, e.g. epochs or generations
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    pub configuration: Configuration,
+    pub budget: usize,
+    /// Objective value, lower is better
+    pub score: f64,
+}
+
+/// Trials of a search, ranked from best to worst
+///
+/// Trials with larger budgets rank first, so configurations that survived
+/// successive halving rank above the ones it stopped early. Trials with a
+/// non-finite score rank last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResults {
+    trials: Vec<Trial>,
+}
+
+impl SearchResults {
+    pub(super) fn new(mut trials: Vec<Trial>) -> Self {
+        trials.sort_by(|a, b| {
+            b.budget
+                .cmp(&a.budget)
+                .then_with(|| compare_scores(a.score, b.score))
+        });
+
+        Self { trials }
+    }
+
+    pub fn trials(&self) -> &[Trial] {
+        &self.trials
+    }
+
+    /// Panics if there were no trials
+    pub fn best(&self) -> &Trial {
+        self.trials.first().expect("no trials")
+    }
+}
+
+fn compare_scores(a: f64, b: f64) -> Ordering {
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) => a.partial_cmp(&b).unwrap(),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => Ordering::Equal,
+    }
+}
+
+impl Display for SearchResults {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self
+            .trials
+            .first()
+            .map(|trial| {
+                let values = trial.configuration.values();
+                values.iter().map(|(name, _)| name.as_str()).collect()
+            })
+            .unwrap_or_default();
+        let widths: Vec<usize> = names.iter().map(|name| name.len().max(12)).collect();
+
+        let mut header = format!("{:<6} {:>12} {:>8}", "rank", "score", "budget");
+        for (name, width) in names.iter().zip(widths.iter()) {
+            header += &format!(" {:>width$}", name, width = width);
+        }
+        let separator = "-".repeat(header.len());
+
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", separator)?;
+        for (i, trial) in self.trials.iter().enumerate() {
+            write!(f, "{:<6} {:>12.6} {:>8}", i + 1, trial.score, trial.budget)?;
+            for ((_, value), width) in trial.configuration.values().iter().zip(widths.iter()) {
+                write!(f, " {:>width$}", value.to_string(), width = width)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "{}", separator)
+    }
+}
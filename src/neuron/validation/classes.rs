@@ -0,0 +1,34 @@
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::thread_rng;
+use ndarray_stats::QuantileExt;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// Group the sample indices by their class, the index of the highest expected
+/// output, optionally shuffling each group
+pub fn group_by_class<F: Float, D: Dataset<F>>(dataset: &D, shuffle: bool) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = vec![];
+    for i in 0..dataset.len() {
+        let (_, expected) = dataset.get(i);
+        let class = expected
+            .argmax()
+            .expect("expected outputs must not be empty");
+        if groups.len() <= class {
+            groups.resize(class + 1, vec![]);
+        }
+
+        groups[class].push(i);
+    }
+
+    if shuffle {
+        let mut rng = thread_rng();
+        for group in groups.iter_mut() {
+            group.shuffle(&mut rng);
+        }
+    }
+
+    groups.retain(|group| !group.is_empty());
+
+    groups
+}
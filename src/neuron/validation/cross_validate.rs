@@ -0,0 +1,216 @@
+use crate::neuron::datasets::{DataLoader, Dataset, Subset};
+use crate::neuron::networks::Model;
+use crate::neuron::optimizers::Optimizer;
+use crate::neuron::validation::{evaluate, Score, Splitter};
+use crate::neuron::Float;
+
+/// Validation scores of each cross-validation fold
+#[derive(Debug, Clone)]
+pub struct CrossValidation<F: Float = f32> {
+    scores: Vec<Score<F>>,
+}
+
+impl<F: Float> CrossValidation<F> {
+    pub fn scores(&self) -> &[Score<F>] {
+        &self.scores
+    }
+
+    /// Mean loss and accuracy over all folds
+    pub fn mean(&self) -> Score<F> {
+        let folds = F::from(self.scores.len()).unwrap();
+
+        Score {
+            loss: self
+                .scores
+                .iter()
+                .map(|s| s.loss)
+                .fold(F::zero(), |total, x| total + x)
+                / folds,
+            accuracy: self
+                .scores
+                .iter()
+                .map(|s| s.accuracy)
+                .fold(F::zero(), |total, x| total + x)
+                / folds,
+        }
+    }
+
+    /// Standard deviation of the loss and accuracy over all folds
+    pub fn std(&self) -> Score<F> {
+        let folds = F::from(self.scores.len()).unwrap();
+        let mean = self.mean();
+
+        Score {
+            loss: (self
+                .scores
+                .iter()
+                .map(|s| (s.loss - mean.loss).powi(2))
+                .fold(F::zero(), |total, x| total + x)
+                / folds)
+                .sqrt(),
+            accuracy: (self
+                .scores
+                .iter()
+                .map(|s| (s.accuracy - mean.accuracy).powi(2))
+                .fold(F::zero(), |total, x| total + x)
+                / folds)
+                .sqrt(),
+        }
+    }
+}
+
+/// Train a fresh copy of the network on each fold's train samples, and score it
+/// on the fold's validation samples
+#[allow(clippy::too_many_arguments)]
+pub fn cross_validate<F, M, O, D, S>(
+    network: &M,
+    optimizer: &O,
+    dataset: &D,
+    splitter: &S,
+    learning_rate: F,
+    batch_size: usize,
+    epochs: usize,
+) -> CrossValidation<F>
+where
+    F: Float,
+    M: Model<F>,
+    O: Optimizer<F>,
+    D: Dataset<F>,
+    S: Splitter,
+{
+    let scores = splitter
+        .split(dataset)
+        .into_iter()
+        .map(|fold| {
+            let mut fold_network = network.clone();
+            let train = DataLoader::new(Subset::new(dataset, fold.train), batch_size, true, false);
+            let validation = Subset::new(dataset, fold.validation);
+
+            optimizer.train(
+                &mut fold_network,
+                &train,
+                &validation,
+                learning_rate,
+                epochs,
+            );
+
+            evaluate(&fold_network, &validation, optimizer.get_loss())
+        })
+        .collect();
+
+    CrossValidation { scores }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use ndarray::{array, Array1, Array2};
+
+    use crate::neuron::activations::sigmoid;
+    use crate::neuron::datasets::InMemoryDataset;
+    use crate::neuron::layers::Layer;
+    use crate::neuron::losses::{mse, Loss};
+    use crate::neuron::networks::Network;
+    use crate::neuron::optimizers::SGD;
+    use crate::neuron::transfers::dense;
+    use crate::neuron::validation::KFold;
+
+    use super::*;
+
+    /// `SGD` that records the parameters and inputs of each batch it optimizes
+    struct RecordingSGD {
+        sgd: SGD,
+        batches: RefCell<Vec<(Array1<f32>, Vec<f32>)>>,
+    }
+
+    impl Optimizer for RecordingSGD {
+        fn get_loss(&self) -> &Loss {
+            self.sgd.get_loss()
+        }
+
+        fn optimize_batch<M: Model>(
+            &self,
+            network: &mut M,
+            batch_inputs: &[Array1<f32>],
+            batch_expected: &[Array1<f32>],
+            learning_rate: f32,
+        ) {
+            let inputs = batch_inputs.iter().map(|input| input[0]).collect();
+            self.batches
+                .borrow_mut()
+                .push((network.parameters(), inputs));
+
+            self.sgd
+                .optimize_batch(network, batch_inputs, batch_expected, learning_rate);
+        }
+
+        fn apply_gradients<M: Model>(
+            &self,
+            network: &mut M,
+            weights_gradients: &[Array2<f32>],
+            biases_gradients: &[Array1<f32>],
+            learning_rate: f32,
+        ) {
+            self.sgd
+                .apply_gradients(network, weights_gradients, biases_gradients, learning_rate);
+        }
+    }
+
+    #[test]
+    fn test_cross_validate_scores_each_fold() {
+        let inputs: Vec<Array1<f32>> = (0..20).map(|i| array![i as f32]).collect();
+        let expected = inputs
+            .iter()
+            .map(|x| {
+                if x[0] < 10. {
+                    array![1., 0.]
+                } else {
+                    array![0., 1.]
+                }
+            })
+            .collect();
+        let dataset = InMemoryDataset::new(inputs, expected);
+        let network = Network::new(vec![Layer::new(2, 1, dense(), sigmoid())]);
+        let optimizer = RecordingSGD {
+            sgd: SGD::new(mse()),
+            batches: RefCell::new(vec![]),
+        };
+
+        // a single batch per fold, holding all of the fold's train samples
+        let cross_validation = cross_validate(
+            &network,
+            &optimizer,
+            &dataset,
+            &KFold::new(4, true),
+            0.1,
+            20,
+            1,
+        );
+
+        assert_eq!(cross_validation.scores().len(), 4);
+        let mean = cross_validation.mean();
+        assert!(mean.accuracy >= 0. && mean.accuracy <= 1.);
+        assert!(cross_validation.std().loss >= 0.);
+
+        let batches = optimizer.batches.into_inner();
+        assert_eq!(batches.len(), 4);
+
+        let mut validated = vec![0; 20];
+        for (parameters, train) in batches {
+            // every fold is trained from the same initial weights
+            assert_eq!(parameters, network.parameters());
+
+            // the fold's validation samples are the ones it wasn't trained on
+            assert_eq!(train.len(), 15);
+            for (i, count) in validated.iter_mut().enumerate() {
+                if !train.contains(&(i as f32)) {
+                    *count += 1;
+                }
+            }
+        }
+
+        // the validation samples of the folds are disjoint and cover the dataset
+        assert_eq!(validated, vec![1; 20]);
+    }
+}
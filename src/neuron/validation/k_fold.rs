@@ -0,0 +1,77 @@
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::thread_rng;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::validation::splitter::folds_from_groups;
+use crate::neuron::validation::{Fold, Splitter};
+use crate::neuron::Float;
+
+/// Splits the samples into `k` folds of (almost) equal size, each fold is used
+/// once for validation while the others are used for training
+#[derive(Debug, Clone, Copy)]
+pub struct KFold {
+    k: usize,
+    shuffle: bool,
+}
+
+impl KFold {
+    pub fn new(k: usize, shuffle: bool) -> Self {
+        assert!(k >= 2, "k must be at least 2");
+
+        Self { k, shuffle }
+    }
+}
+
+impl Splitter for KFold {
+    fn split<F: Float, D: Dataset<F>>(&self, dataset: &D) -> Vec<Fold> {
+        assert!(
+            dataset.len() >= self.k,
+            "can't split {} samples into {} folds",
+            dataset.len(),
+            self.k
+        );
+
+        let mut indices: Vec<usize> = (0..dataset.len()).collect();
+        if self.shuffle {
+            indices.shuffle(&mut thread_rng());
+        }
+
+        // the first `len % k` folds get an extra sample
+        let fold_size = indices.len() / self.k;
+        let remainder = indices.len() % self.k;
+        let mut groups = vec![];
+        let mut start = 0;
+        for fold in 0..self.k {
+            let end = start + fold_size + if fold < remainder { 1 } else { 0 };
+            groups.push(indices[start..end].to_vec());
+            start = end;
+        }
+
+        folds_from_groups(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::neuron::datasets::InMemoryDataset;
+
+    use super::*;
+
+    #[test]
+    fn test_k_fold() {
+        let dataset = InMemoryDataset::new(vec![array![0.]; 7], vec![array![0.]; 7]);
+
+        let folds = KFold::new(3, false).split(&dataset);
+        let validations: Vec<Vec<usize>> = folds.iter().map(|f| f.validation.clone()).collect();
+        assert_eq!(validations, vec![vec![0, 1, 2], vec![3, 4], vec![5, 6]]);
+        assert_eq!(folds[1].train, vec![0, 1, 2, 5, 6]);
+
+        for fold in KFold::new(3, true).split(&dataset) {
+            let mut all: Vec<usize> = fold.train.iter().chain(&fold.validation).copied().collect();
+            all.sort_unstable();
+            assert_eq!(all, (0..7).collect::<Vec<usize>>());
+        }
+    }
+}
@@ -0,0 +1,14 @@
+pub use cross_validate::{cross_validate, CrossValidation};
+pub use k_fold::KFold;
+pub use score::{evaluate, Score};
+pub use split::{train_test_split, train_val_test_split};
+pub use splitter::{Fold, Splitter};
+pub use stratified_k_fold::StratifiedKFold;
+
+mod classes;
+mod cross_validate;
+mod k_fold;
+mod score;
+mod split;
+mod splitter;
+mod stratified_k_fold;
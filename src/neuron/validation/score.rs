@@ -0,0 +1,39 @@
+use ndarray_stats::QuantileExt;
+
+use crate::neuron::datasets::Dataset;
+use crate::neuron::losses::Loss;
+use crate::neuron::networks::Model;
+use crate::neuron::Float;
+
+/// Average loss and accuracy of a `Model` on a `Dataset`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score<F: Float = f32> {
+    pub loss: F,
+    /// Ratio of samples where the highest output matches the highest expected output
+    pub accuracy: F,
+}
+
+/// Score the network on every sample of the dataset
+pub fn evaluate<F: Float, M: Model<F>, D: Dataset<F>>(
+    network: &M,
+    dataset: &D,
+    loss: &Loss<F>,
+) -> Score<F> {
+    let samples = dataset.len();
+    let mut total_loss = F::zero();
+    let mut mistakes = 0;
+    for i in 0..samples {
+        let (input, expected) = dataset.get(i);
+        let prediction = network.predict(&input);
+        if prediction.argmax().unwrap() != expected.argmax().unwrap() {
+            mistakes += 1;
+        }
+
+        total_loss += loss.loss(&prediction, &expected).sum();
+    }
+
+    Score {
+        loss: total_loss / F::from(samples).unwrap(),
+        accuracy: F::one() - F::from(mistakes).unwrap() / F::from(samples).unwrap(),
+    }
+}
@@ -0,0 +1,99 @@
+use ndarray_rand::rand::seq::SliceRandom;
+use ndarray_rand::rand::thread_rng;
+
+use crate::neuron::datasets::{Dataset, Subset};
+use crate::neuron::validation::classes::group_by_class;
+use crate::neuron::Float;
+
+/// Randomly split the dataset into a train and a test subset
+///
+/// If `stratify` is set each subset keeps the class proportions of the whole
+/// dataset, where the class of a sample is the index of its highest expected output.
+pub fn train_test_split<F: Float, D: Dataset<F>>(
+    dataset: &D,
+    test_ratio: f32,
+    stratify: bool,
+) -> (Subset<&D>, Subset<&D>) {
+    let (train, _, test) = train_val_test_split(dataset, 0., test_ratio, stratify);
+
+    (train, test)
+}
+
+/// Randomly split the dataset into a train, a validation and a test subset
+///
+/// If `stratify` is set each subset keeps the class proportions of the whole
+/// dataset, where the class of a sample is the index of its highest expected output.
+pub fn train_val_test_split<F: Float, D: Dataset<F>>(
+    dataset: &D,
+    validation_ratio: f32,
+    test_ratio: f32,
+    stratify: bool,
+) -> (Subset<&D>, Subset<&D>, Subset<&D>) {
+    assert!(
+        validation_ratio >= 0. && test_ratio >= 0. && validation_ratio + test_ratio <= 1.,
+        "validation and test ratios must be non negative and sum to at most 1"
+    );
+
+    let groups = if stratify {
+        group_by_class(dataset, true)
+    } else {
+        let mut indices: Vec<usize> = (0..dataset.len()).collect();
+        indices.shuffle(&mut thread_rng());
+        vec![indices]
+    };
+
+    let mut train = vec![];
+    let mut validation = vec![];
+    let mut test = vec![];
+    for group in groups {
+        let test_samples = (group.len() as f32 * test_ratio).round() as usize;
+        let validation_samples = usize::min(
+            (group.len() as f32 * validation_ratio).round() as usize,
+            group.len() - test_samples,
+        );
+
+        test.extend_from_slice(&group[..test_samples]);
+        validation.extend_from_slice(&group[test_samples..test_samples + validation_samples]);
+        train.extend_from_slice(&group[test_samples + validation_samples..]);
+    }
+
+    (
+        Subset::new(dataset, train),
+        Subset::new(dataset, validation),
+        Subset::new(dataset, test),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use crate::neuron::datasets::InMemoryDataset;
+
+    use super::*;
+
+    #[test]
+    fn test_stratified_train_val_test_split() {
+        // 10 samples of class 0 and 20 samples of class 1
+        let expected: Vec<Array1<f32>> = (0..30)
+            .map(|i| {
+                if i < 10 {
+                    array![1., 0.]
+                } else {
+                    array![0., 1.]
+                }
+            })
+            .collect();
+        let dataset = InMemoryDataset::new(vec![array![0.]; 30], expected);
+
+        let (train, validation, test) = train_val_test_split(&dataset, 0.2, 0.1, true);
+        assert_eq!((train.len(), validation.len(), test.len()), (21, 6, 3));
+
+        let class_0 = |subset: &Subset<&InMemoryDataset>| {
+            subset.indices().iter().filter(|&&i| i < 10).count()
+        };
+        assert_eq!(class_0(&train), 7);
+        assert_eq!(class_0(&validation), 2);
+        assert_eq!(class_0(&test), 1);
+    }
+}
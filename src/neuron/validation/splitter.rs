@@ -0,0 +1,33 @@
+use crate::neuron::datasets::Dataset;
+use crate::neuron::Float;
+
+/// Sample indices of a single cross-validation fold
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fold {
+    pub train: Vec<usize>,
+    pub validation: Vec<usize>,
+}
+
+/// Splits a `Dataset` into cross-validation folds
+pub trait Splitter {
+    fn split<F: Float, D: Dataset<F>>(&self, dataset: &D) -> Vec<Fold>;
+}
+
+/// Create a fold for each group, validating on the group and training on the rest
+pub(super) fn folds_from_groups(groups: Vec<Vec<usize>>) -> Vec<Fold> {
+    (0..groups.len())
+        .map(|validation_group| {
+            let train = groups
+                .iter()
+                .enumerate()
+                .filter(|(group, _)| *group != validation_group)
+                .flat_map(|(_, indices)| indices.iter().copied())
+                .collect();
+
+            Fold {
+                train,
+                validation: groups[validation_group].clone(),
+            }
+        })
+        .collect()
+}
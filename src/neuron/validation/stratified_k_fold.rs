@@ -0,0 +1,77 @@
+use crate::neuron::datasets::Dataset;
+use crate::neuron::validation::classes::group_by_class;
+use crate::neuron::validation::splitter::folds_from_groups;
+use crate::neuron::validation::{Fold, Splitter};
+use crate::neuron::Float;
+
+/// Like `KFold`, but each fold keeps the class proportions of the whole dataset
+///
+/// The class of a sample is the index of its highest expected output.
+#[derive(Debug, Clone, Copy)]
+pub struct StratifiedKFold {
+    k: usize,
+    shuffle: bool,
+}
+
+impl StratifiedKFold {
+    pub fn new(k: usize, shuffle: bool) -> Self {
+        assert!(k >= 2, "k must be at least 2");
+
+        Self { k, shuffle }
+    }
+}
+
+impl Splitter for StratifiedKFold {
+    fn split<F: Float, D: Dataset<F>>(&self, dataset: &D) -> Vec<Fold> {
+        assert!(
+            dataset.len() >= self.k,
+            "can't split {} samples into {} folds",
+            dataset.len(),
+            self.k
+        );
+
+        // deal the samples of each class to the folds in turn, continuing from the
+        // fold the previous class stopped at to keep the fold sizes balanced
+        let mut groups = vec![vec![]; self.k];
+        let mut fold = 0;
+        for class in group_by_class(dataset, self.shuffle) {
+            for index in class {
+                groups[fold].push(index);
+                fold = (fold + 1) % self.k;
+            }
+        }
+
+        folds_from_groups(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+
+    use crate::neuron::datasets::InMemoryDataset;
+
+    use super::*;
+
+    #[test]
+    fn test_stratified_k_fold_keeps_class_proportions() {
+        // 6 samples of class 0 and 3 samples of class 1
+        let expected: Vec<Array1<f32>> = (0..9)
+            .map(|i| {
+                if i < 6 {
+                    array![1., 0.]
+                } else {
+                    array![0., 1.]
+                }
+            })
+            .collect();
+        let dataset = InMemoryDataset::new(vec![array![0.]; 9], expected);
+
+        for fold in StratifiedKFold::new(3, true).split(&dataset) {
+            let class_1 = fold.validation.iter().filter(|&&i| i >= 6).count();
+            assert_eq!(fold.validation.len(), 3);
+            assert_eq!(class_1, 1);
+            assert_eq!(fold.train.len(), 6);
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use ndarray::prelude::*;
-use ndarray_rand::rand::{thread_rng, Rng};
+use ndarray_rand::rand::Rng;
 use ndarray_stats::QuantileExt;
 
+use crate::neuron::checkpoints::{CheckpointError, ModelState};
 use crate::neuron::layers::Layer;
-use crate::neuron::networks::Network;
+use crate::neuron::networks::{Model, Network};
 use crate::rl::prelude::*;
-use crate::rl::trainers::genetic_algorithm::Evolve;
+use crate::rl::trainers::genetic_algorithm::{Checkpointable, Evolve};
 
 /// An `Agent` with a `Network` that can `Evolve` and perform `DiscreteAction`s or
 /// `ContinuousAction`
@@ -19,12 +20,7 @@ impl NeuroEvolutionAgent {
         Self { network }
     }
 
-    fn crossover_weights(
-        &self,
-        new_layer: &mut Layer,
-        other_layer: &Layer,
-        rng: &mut ndarray_rand::rand::prelude::ThreadRng,
-    ) {
+    fn crossover_weights<R: Rng>(&self, new_layer: &mut Layer, other_layer: &Layer, rng: &mut R) {
         let layer_weights = new_layer.get_weights_mut();
         let other_weights = other_layer.get_weights();
         for dst in 0..layer_weights.len_of(Axis(0)) {
@@ -36,12 +32,7 @@ impl NeuroEvolutionAgent {
         }
     }
 
-    fn crossover_biases(
-        &self,
-        new_layer: &mut Layer,
-        other_layer: &Layer,
-        rng: &mut ndarray_rand::rand::prelude::ThreadRng,
-    ) {
+    fn crossover_biases<R: Rng>(&self, new_layer: &mut Layer, other_layer: &Layer, rng: &mut R) {
         let layer_biases = new_layer.get_biases_mut();
         let other_biases = other_layer.get_biases();
         for dst in 0..layer_biases.len() {
@@ -66,37 +57,38 @@ impl Agent<ContinuousAction> for NeuroEvolutionAgent {
 
 impl Evolve for NeuroEvolutionAgent {
     /// mutate weights and biases of agent's network
-    fn mutate(&mut self, mutation_rate: f64) {
-        let mut rng = thread_rng();
-        for layer_weights in self.network.get_weights_mut() {
-            for weight in layer_weights {
-                if rng.gen_bool(mutation_rate) {
-                    *weight = rng.gen_range(-1.0..1.0);
-                }
+    fn mutate<R: Rng>(&mut self, mutation_rate: f64, rng: &mut R) {
+        let mut parameters = self.network.parameters();
+        for parameter in parameters.iter_mut() {
+            if rng.gen_bool(mutation_rate) {
+                *parameter = rng.gen_range(-1.0..1.0);
             }
         }
 
-        for layer_biases in self.network.get_biases_mut() {
-            for bias in layer_biases {
-                if rng.gen_bool(mutation_rate) {
-                    *bias = rng.gen_range(-1.0..1.0);
-                }
-            }
-        }
+        self.network.set_parameters(&parameters);
     }
 
     /// crossover agent's network with other's network
-    fn crossover(&self, other: &Self) -> Self {
-        let mut rng = thread_rng();
+    fn crossover<R: Rng>(&self, other: &Self, rng: &mut R) -> Self {
         let mut new_network = self.network.clone();
         let new_layers = new_network.get_layers_mut();
         let other_layers = other.network.get_layers();
 
         for (new_layer, other_layer) in new_layers.iter_mut().zip(other_layers.iter()) {
-            self.crossover_biases(new_layer, other_layer, &mut rng);
-            self.crossover_weights(new_layer, other_layer, &mut rng);
+            self.crossover_biases(new_layer, other_layer, rng);
+            self.crossover_weights(new_layer, other_layer, rng);
         }
 
         Self::new(new_network)
     }
 }
+
+impl Checkpointable for NeuroEvolutionAgent {
+    fn state(&self) -> ModelState {
+        ModelState::capture(&self.network)
+    }
+
+    fn restore(&mut self, state: &ModelState) -> Result<(), CheckpointError> {
+        state.restore(&mut self.network)
+    }
+}
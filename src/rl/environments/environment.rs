@@ -2,6 +2,9 @@ use crate::rl::prelude::*;
 
 pub trait Environment<A: Action>: Clone {
     fn reset(&mut self);
+    /// Seed the environment's random draws, so the same seed gives the same
+    /// episodes. Environments without random draws ignore the seed.
+    fn seed(&mut self, _seed: u64) {}
     fn observe(&self) -> State;
     fn step(&mut self, action: &A) -> Reward;
     fn is_done(&self) -> bool;
@@ -2,7 +2,8 @@ use std::convert::TryInto;
 use std::fmt::Display;
 
 use ndarray::prelude::*;
-use ndarray_rand::rand::{thread_rng, Rng};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{Rng, SeedableRng};
 
 use crate::rl::prelude::*;
 
@@ -14,6 +15,7 @@ pub struct FlappyEnvironment {
     player_vel: isize,
     walls: Vec<(usize, usize)>,
     done: bool,
+    rng: StdRng,
 }
 
 impl FlappyEnvironment {
@@ -25,6 +27,7 @@ impl FlappyEnvironment {
             player_vel: 0,
             walls: vec![],
             done: false,
+            rng: StdRng::from_entropy(),
         }
     }
 
@@ -66,8 +69,7 @@ impl FlappyEnvironment {
     }
 
     fn spawn_wall(&mut self) {
-        let mut rng = thread_rng();
-        let hole = rng.gen_range(0..(self.size - self.hole_size));
+        let hole = self.rng.gen_range(0..(self.size - self.hole_size));
 
         let mut walls: Vec<(usize, usize)> = (0..self.size)
             .filter_map(|w| {
@@ -109,6 +111,10 @@ impl Environment<DiscreteAction> for FlappyEnvironment {
         self.done = false;
     }
 
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     fn observe(&self) -> State {
         let mut state = Vec::with_capacity(self.observation_space());
 
@@ -122,7 +128,7 @@ impl Environment<DiscreteAction> for FlappyEnvironment {
     }
 
     fn step(&mut self, action: &DiscreteAction) -> Reward {
-        let border_crash = self.update_player(&action);
+        let border_crash = self.update_player(action);
 
         self.update_walls();
 
@@ -3,7 +3,8 @@ use std::convert::TryInto;
 use std::fmt::Display;
 
 use ndarray::prelude::*;
-use ndarray_rand::rand::{thread_rng, Rng};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{Rng, SeedableRng};
 
 use crate::rl::prelude::*;
 
@@ -15,6 +16,7 @@ pub struct JumpEnvironment {
     ground: usize,
     player_vel: isize,
     done: bool,
+    rng: StdRng,
 }
 
 impl JumpEnvironment {
@@ -27,15 +29,15 @@ impl JumpEnvironment {
             ground,
             player_vel: 0,
             done: false,
+            rng: StdRng::from_entropy(),
         }
     }
 
     /// spawn 1 or 2 wall tiles randomly
     fn spawn_wall(&mut self) {
-        let mut rng = thread_rng();
         let wx = self.size - 1;
-        let wy1 = rng.gen_range((self.ground + 1)..(self.size - 1));
-        let wy2 = rng.gen_range((self.ground + 1)..(self.size - 1));
+        let wy1 = self.rng.gen_range((self.ground + 1)..(self.size - 1));
+        let wy2 = self.rng.gen_range((self.ground + 1)..(self.size - 1));
 
         self.walls.push((wx, wy1));
 
@@ -106,6 +108,10 @@ impl Environment<DiscreteAction> for JumpEnvironment {
         self.done = false;
     }
 
+    fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     fn observe(&self) -> Array1<f32> {
         let mut state = Vec::with_capacity(self.observation_space());
         for row in 0..self.size {
@@ -129,7 +135,7 @@ impl Environment<DiscreteAction> for JumpEnvironment {
     }
 
     fn step(&mut self, action: &DiscreteAction) -> f32 {
-        self.update_player(&action);
+        self.update_player(action);
         self.update_walls();
 
         if !self.done {
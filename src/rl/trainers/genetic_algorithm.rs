@@ -1,14 +1,26 @@
+use std::path::Path;
+
 use ndarray::prelude::*;
-use ndarray_rand::rand::{thread_rng, Rng};
+use ndarray_rand::rand::rngs::StdRng;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
 use ndarray_rand::rand_distr::WeightedIndex;
 use ndarray_stats::QuantileExt;
 
+use crate::neuron::checkpoints::{
+    Checkpoint, CheckpointError, ModelState, OptimizerState, Progress,
+};
 use crate::rl::prelude::*;
 
 /// Allows Agents to be trained using a genetic algorithm
 pub trait Evolve {
-    fn mutate(&mut self, mutation_rate: f64);
-    fn crossover(&self, other: &Self) -> Self;
+    fn mutate<R: Rng>(&mut self, mutation_rate: f64, rng: &mut R);
+    fn crossover<R: Rng>(&self, other: &Self, rng: &mut R) -> Self;
+}
+
+/// Allows an Agent's learned state to be saved in a `Checkpoint`
+pub trait Checkpointable {
+    fn state(&self) -> ModelState;
+    fn restore(&mut self, state: &ModelState) -> Result<(), CheckpointError>;
 }
 
 /// Trains Agent by making it compete against different versions of itself,
@@ -26,15 +38,158 @@ impl GeneticAlgorithm {
         }
     }
 
+    /// Train as `Trainer::train` does, saving the generation, the best agent and
+    /// the population to `path` after each generation
+    ///
+    /// If a checkpoint exists at `path` the agents, mutation rate and seed are
+    /// restored from it and training resumes from the next generation. The
+    /// environments, crossovers and mutations of each generation are drawn from
+    /// an rng seeded with `seed` and the generation, so a resumed run evolves the
+    /// same population as an uninterrupted one.
+    pub fn train_checkpointed<AC, AG, E, P>(
+        &mut self,
+        agent: &mut AG,
+        env: &E,
+        epochs: usize,
+        verbose: bool,
+        seed: u64,
+        path: P,
+    ) -> Result<(), CheckpointError>
+    where
+        AC: Action,
+        AG: Agent<AC> + Evolve + Checkpointable,
+        E: Environment<AC>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut agents = vec![agent.clone(); self.agent_amount];
+        let mut progress = Progress {
+            epoch: 0,
+            step: 0,
+            seed,
+            learning_rate: None,
+            mutation_rate: Some(self.mutation_rate),
+        };
+        if path.exists() {
+            let checkpoint = Checkpoint::load(path)?;
+            if checkpoint.models.len() != self.agent_amount + 1 {
+                return Err(CheckpointError::Mismatch(format!(
+                    "checkpoint has {} agents, expected {}",
+                    checkpoint.models.len(),
+                    self.agent_amount + 1
+                )));
+            }
+
+            agent.restore(&checkpoint.models[0])?;
+            for (population_agent, state) in agents.iter_mut().zip(&checkpoint.models[1..]) {
+                population_agent.restore(state)?;
+            }
+            progress = checkpoint.progress;
+        }
+        let mutation_rate = progress.mutation_rate.ok_or_else(|| {
+            CheckpointError::Mismatch("checkpoint has no mutation rate".to_string())
+        })?;
+
+        let mut envs = vec![env.clone(); self.agent_amount];
+        while progress.epoch < epochs {
+            let mut rng = StdRng::seed_from_u64(progress.seed.wrapping_add(progress.epoch as u64));
+            agents = self.run_generation(
+                agent,
+                agents,
+                &mut envs,
+                progress.epoch,
+                mutation_rate,
+                verbose,
+                &mut rng,
+            );
+            progress.epoch += 1;
+
+            let models = std::iter::once(&*agent)
+                .chain(agents.iter())
+                .map(|agent| agent.state())
+                .collect();
+            let checkpoint = Checkpoint {
+                models,
+                optimizer: OptimizerState::default(),
+                progress,
+            };
+            checkpoint.save(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Score each agent in its environment, set the agent as the best one and
+    /// return the next generation
+    #[allow(clippy::too_many_arguments)]
+    fn run_generation<AC, AG, E, R>(
+        &mut self,
+        agent: &mut AG,
+        mut agents: Vec<AG>,
+        envs: &mut [E],
+        epoch: usize,
+        mutation_rate: f64,
+        verbose: bool,
+        rng: &mut R,
+    ) -> Vec<AG>
+    where
+        AC: Action,
+        AG: Agent<AC> + Evolve,
+        E: Environment<AC>,
+        R: Rng,
+    {
+        let max_reward = envs[0].max_reward();
+        let mut scores = Array1::zeros(self.agent_amount);
+
+        // evaluate each agent
+        for (i, (agent, environment)) in agents.iter_mut().zip(envs.iter_mut()).enumerate() {
+            let mut score = 0.;
+            environment.seed(rng.gen());
+            environment.reset();
+
+            while !environment.is_done() && score < max_reward {
+                let action = agent.act(&environment.observe());
+                let reward = environment.step(&action);
+                score += reward;
+            }
+
+            scores[i] = score;
+        }
+
+        if verbose {
+            println!(
+                "epoch {} | max score: {} | avg scores: {} | min score: {}",
+                epoch,
+                scores.max().unwrap(),
+                scores.sum() / self.agent_amount as f32,
+                scores.min().unwrap()
+            );
+        }
+
+        // update agent as the best agent of the current generation
+        *agent = agents[scores.argmax().unwrap()].clone();
+
+        // spawn new generation
+        let mut agents = self.new_generation(agents.iter().collect(), &scores, mutation_rate, rng);
+
+        // HILLCLIMBING: save the best agent from each generation
+        agents[0] = agent.clone();
+
+        agents
+    }
+
     /// use scores to generate new generation using survival of the fittest
-    fn new_generation<AC, AG>(
+    fn new_generation<AC, AG, R>(
         &mut self,
         old_generation: Vec<&AG>,
         scores: &Array1<Reward>,
+        mutation_rate: f64,
+        rng: &mut R,
     ) -> Vec<AG>
     where
         AC: Action,
         AG: Agent<AC> + Evolve,
+        R: Rng,
     {
         assert_eq!(
             scores.len(),
@@ -51,14 +206,13 @@ impl GeneticAlgorithm {
         let weighted_dist = WeightedIndex::new(&weights).unwrap();
 
         let mut new_generation = vec![];
-        let mut rng = thread_rng();
         for _ in 0..self.agent_amount {
             let parents_indices: Vec<usize> =
-                (&mut rng).sample_iter(&weighted_dist).take(2).collect();
+                (&mut *rng).sample_iter(&weighted_dist).take(2).collect();
             let a0 = &old_generation[parents_indices[0]];
             let a1 = &old_generation[parents_indices[1]];
-            let mut child = a0.crossover(a1);
-            child.mutate(self.mutation_rate);
+            let mut child = a0.crossover(a1, rng);
+            child.mutate(mutation_rate, rng);
 
             new_generation.push(child);
         }
@@ -72,54 +226,23 @@ where
     AC: Action,
     AG: Agent<AC> + Evolve,
 {
-    fn train<'a, E: Environment<AC>>(
-        &mut self,
-        agent: &'a mut AG,
-        env: &E,
-        epochs: usize,
-        verbose: bool,
-    ) {
+    fn train<E: Environment<AC>>(&mut self, agent: &mut AG, env: &E, epochs: usize, verbose: bool) {
         // create multiple agents and an env for each one
         let mut agents = vec![agent.clone(); self.agent_amount];
         let mut envs = vec![env.clone(); self.agent_amount];
+        let mut rng = thread_rng();
 
         // run epochs
-        let max_reward = env.max_reward();
         for e in 0..epochs {
-            let mut scores = Array1::zeros(self.agent_amount);
-
-            // evaluate each agent
-            for (i, (agent, environment)) in agents.iter_mut().zip(envs.iter_mut()).enumerate() {
-                let mut score = 0.;
-                environment.reset();
-
-                while !environment.is_done() && score < max_reward {
-                    let action = agent.act(&environment.observe());
-                    let reward = environment.step(&action);
-                    score += reward;
-                }
-
-                scores[i] = score;
-            }
-
-            if verbose {
-                println!(
-                    "epoch {} | max score: {} | avg scores: {} | min score: {}",
-                    e,
-                    scores.max().unwrap(),
-                    scores.sum() / self.agent_amount as f32,
-                    scores.min().unwrap()
-                );
-            }
-
-            // update agent as the best agent of the current generation
-            *agent = agents[scores.argmax().unwrap()].clone();
-
-            // spawn new generation
-            agents = self.new_generation(agents.iter().collect(), &scores);
-
-            // HILLCLIMBING: save the best agent from each generation
-            agents[0] = agent.clone();
+            agents = self.run_generation(
+                agent,
+                agents,
+                &mut envs,
+                e,
+                self.mutation_rate,
+                verbose,
+                &mut rng,
+            );
         }
     }
 }
@@ -152,4 +275,80 @@ mod tests {
         let epochs = 10;
         learner.train(&mut agent, &env, epochs, false);
     }
+
+    #[test]
+    fn test_neuro_evolution_resumes_from_checkpoint() {
+        let env = JumpEnvironment::new(10);
+        let network = Network::new(vec![
+            Layer::new(3, env.observation_space(), dense(), relu()),
+            Layer::new(env.action_space(), 3, dense(), linear()),
+        ]);
+        let path = std::env::temp_dir().join(format!("evolution_{}.json", std::process::id()));
+
+        let mut agent = NeuroEvolutionAgent::new(network);
+        let mut learner = GeneticAlgorithm::new(5, 0.1);
+        learner
+            .train_checkpointed(&mut agent, &env, 2, false, 0, &path)
+            .unwrap();
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.progress.epoch, 2);
+        assert_eq!(checkpoint.models.len(), 6);
+
+        // a fresh agent resumes as the best agent of the interrupted run
+        let mut resumed = NeuroEvolutionAgent::new(Network::new(vec![
+            Layer::new(3, env.observation_space(), dense(), relu()),
+            Layer::new(env.action_space(), 3, dense(), linear()),
+        ]));
+        learner
+            .train_checkpointed(&mut resumed, &env, 2, false, 0, &path)
+            .unwrap();
+        assert_eq!(resumed.state(), agent.state());
+
+        learner
+            .train_checkpointed(&mut resumed, &env, 3, false, 0, &path)
+            .unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap().progress.epoch, 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_resumed_evolution_matches_uninterrupted() {
+        let env = JumpEnvironment::new(10);
+        let agent = NeuroEvolutionAgent::new(Network::new(vec![
+            Layer::new(3, env.observation_space(), dense(), relu()),
+            Layer::new(env.action_space(), 3, dense(), linear()),
+        ]));
+        let directory = std::env::temp_dir();
+        let path =
+            |name: &str| directory.join(format!("evolution_{}_{}.json", name, std::process::id()));
+
+        let mut uninterrupted = agent.clone();
+        GeneticAlgorithm::new(5, 0.1)
+            .train_checkpointed(&mut uninterrupted, &env, 3, false, 7, path("full"))
+            .unwrap();
+
+        // interrupted after the first generation, and resumed with the seed and
+        // mutation rate of the checkpoint
+        let mut interrupted = agent;
+        GeneticAlgorithm::new(5, 0.1)
+            .train_checkpointed(&mut interrupted, &env, 1, false, 7, path("part"))
+            .unwrap();
+        let checkpoint = Checkpoint::load(path("part")).unwrap();
+        assert_eq!(checkpoint.progress.seed, 7);
+        assert_eq!(checkpoint.progress.mutation_rate, Some(0.1));
+        assert_eq!(checkpoint.progress.learning_rate, None);
+
+        let mut resumed = interrupted.clone();
+        GeneticAlgorithm::new(5, 0.5)
+            .train_checkpointed(&mut resumed, &env, 3, false, 0, path("part"))
+            .unwrap();
+
+        let full = Checkpoint::load(path("full")).unwrap();
+        let part = Checkpoint::load(path("part")).unwrap();
+        assert_eq!(part.progress, full.progress);
+        assert_eq!(part.models, full.models);
+        assert_eq!(resumed.state(), uninterrupted.state());
+        std::fs::remove_file(path("full")).unwrap();
+        std::fs::remove_file(path("part")).unwrap();
+    }
 }
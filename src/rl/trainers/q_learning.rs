@@ -38,9 +38,9 @@ impl<A> Trainer<DiscreteAction, A> for QLearner
 where
     A: Agent<DiscreteAction> + QFunction,
 {
-    fn train<'a, E: Environment<DiscreteAction>>(
+    fn train<E: Environment<DiscreteAction>>(
         &mut self,
-        agent: &'a mut A,
+        agent: &mut A,
         env: &E,
         epochs: usize,
         verbose: bool,